@@ -0,0 +1,168 @@
+#[macro_use]
+extern crate bencher;
+
+use bencher::Bencher;
+use db::{Db, Store};
+use db_query::SaveBuilder;
+use ic::structures::{
+    memory::{MemoryId, MemoryManager},
+    DefaultMemoryImpl,
+};
+use mimic_base::types::{
+    test::store::{CreateBasic, Filterable},
+    Ulid,
+};
+use orm::traits::{EntityDynamic, Path};
+use std::{cell::RefCell, sync::Once};
+
+//
+// the save pipeline against an in-memory store, one entity per call versus
+// the same entities in one batch, for a one field entity and one with
+// sanitized and validated strings
+//
+// create keeps adding rows, replace and update write over rows that were
+// put there before timing starts
+//
+
+const STORE: &str = mimic_base::canister::test::store::Data::PATH;
+const SIZES: [usize; 3] = [1, 10, 100];
+
+thread_local! {
+    static MEMORY_MANAGER: MemoryManager<DefaultMemoryImpl> =
+        MemoryManager::init(DefaultMemoryImpl::default());
+
+    static DATA: RefCell<Store> = RefCell::new(Store::init(
+        MEMORY_MANAGER.with(|mm| mm.get(MemoryId::new(0)))
+    ));
+
+    static DB: Db = {
+        let mut db = Db::new();
+        db.insert(STORE, &DATA);
+        db
+    };
+}
+
+// init
+// the resolver reads the schema, so it has to be there before the first save
+fn init() {
+    static INIT: Once = Once::new();
+    INIT.call_once(|| {
+        let json = serde_json::to_string(&*orm_schema::build::schema()).unwrap();
+        core_schema::init_schema_json(&json).unwrap();
+    });
+}
+
+// basic
+fn basic(n: usize) -> Vec<CreateBasic> {
+    (0..n)
+        .map(|_| CreateBasic {
+            id: Ulid::generate(),
+        })
+        .collect()
+}
+
+// filterable
+fn filterable(n: usize) -> Vec<Filterable> {
+    (0..n)
+        .map(|i| Filterable {
+            id: Ulid::generate(),
+            name: format!("Entity Number {i}"),
+            description: "a description long enough to be worth sanitizing".into(),
+        })
+        .collect()
+}
+
+// bytes
+// what gets written per iteration, so the output reads as bytes/op
+fn bytes<E: EntityDynamic>(entities: &[E]) -> u64 {
+    entities
+        .iter()
+        .map(|e| e.serialize_dyn().unwrap().len() as u64)
+        .sum()
+}
+
+// seed
+// puts the rows in place for the replace and update benches, create
+// generates its own ids so it never collides with them
+fn seed<E: EntityDynamic + Clone + 'static>(entities: &[E]) {
+    DB.with(|db| {
+        db_query::replace(db)
+            .from_entities(entities.to_vec())
+            .unwrap();
+    });
+}
+
+// single
+fn single<E: EntityDynamic + Clone + 'static>(
+    bench: &mut Bencher,
+    save: fn(&Db) -> SaveBuilder,
+    entities: &[E],
+) {
+    init();
+    seed(entities);
+
+    DB.with(|db| {
+        bench.iter(|| {
+            for e in entities {
+                save(db).from_entity(e.clone()).unwrap();
+            }
+        });
+    });
+
+    bench.bytes = bytes(entities);
+}
+
+// batch
+fn batch<E: EntityDynamic + Clone + 'static>(
+    bench: &mut Bencher,
+    save: fn(&Db) -> SaveBuilder,
+    entities: &[E],
+) {
+    init();
+    seed(entities);
+
+    DB.with(|db| {
+        bench.iter(|| {
+            save(db).from_entities(entities.to_vec()).unwrap();
+        });
+    });
+
+    bench.bytes = bytes(entities);
+}
+
+// save_benches
+// one bench fn per mode, entity and size, as bencher can't take arguments
+macro_rules! save_benches {
+    ($($name:ident: $run:ident, $save:path, $make:ident, $n:expr;)*) => {
+        $(
+            fn $name(bench: &mut Bencher) {
+                $run(bench, $save, &$make($n));
+            }
+        )*
+
+        benchmark_group!(benches, $($name),*);
+    };
+}
+
+save_benches! {
+    create_basic_single_1: single, db_query::create, basic, SIZES[0];
+    create_basic_single_10: single, db_query::create, basic, SIZES[1];
+    create_basic_single_100: single, db_query::create, basic, SIZES[2];
+    create_basic_batch_10: batch, db_query::create, basic, SIZES[1];
+    create_basic_batch_100: batch, db_query::create, basic, SIZES[2];
+    create_filterable_single_1: single, db_query::create, filterable, SIZES[0];
+    create_filterable_single_10: single, db_query::create, filterable, SIZES[1];
+    create_filterable_single_100: single, db_query::create, filterable, SIZES[2];
+    create_filterable_batch_10: batch, db_query::create, filterable, SIZES[1];
+    create_filterable_batch_100: batch, db_query::create, filterable, SIZES[2];
+    replace_basic_single_100: single, db_query::replace, basic, SIZES[2];
+    replace_basic_batch_100: batch, db_query::replace, basic, SIZES[2];
+    replace_filterable_single_100: single, db_query::replace, filterable, SIZES[2];
+    replace_filterable_batch_100: batch, db_query::replace, filterable, SIZES[2];
+    update_basic_single_100: single, db_query::update, basic, SIZES[2];
+    update_basic_batch_100: batch, db_query::update, basic, SIZES[2];
+    update_filterable_single_100: single, db_query::update, filterable, SIZES[2];
+    update_filterable_batch_100: batch, db_query::update, filterable, SIZES[2];
+}
+
+benchmark_main!(benches);
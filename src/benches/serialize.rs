@@ -0,0 +1,125 @@
+#[macro_use]
+extern crate bencher;
+
+use bencher::{run_tests_console, Bencher, TestOpts};
+use candid::CandidType;
+use mimic_base::types::{
+    test::{has_map::HasMap, store::Filterable},
+    Ulid,
+};
+use serde::{de::DeserializeOwned, Serialize};
+
+//
+// candid against lib_cbor (the format the stores use) for the same entities,
+// bytes/op is the encoded size so the two can be compared on size as well
+// as time
+//
+
+// filterable
+// a few short fields, the shape of most rows
+fn filterable() -> Filterable {
+    Filterable {
+        id: Ulid::generate(),
+        name: "The Book of Magic".into(),
+        description: "This book has so much magic in it".into(),
+    }
+}
+
+// has_map
+// a row holding a collection, where per-item overhead adds up
+fn has_map() -> HasMap {
+    let mut e = HasMap::default();
+    for i in 0..100 {
+        e.map_int_string.insert(i, format!("value {i}"));
+    }
+
+    e
+}
+
+fn candid_encode<T: CandidType>(value: &T) -> Vec<u8> {
+    candid::encode_one(value).unwrap()
+}
+
+fn candid_decode<T: CandidType + DeserializeOwned>(bytes: &[u8]) -> T {
+    candid::decode_one(bytes).unwrap()
+}
+
+fn cbor_encode<T: Serialize>(value: &T) -> Vec<u8> {
+    lib_cbor::serialize(value).unwrap()
+}
+
+fn cbor_decode<T: DeserializeOwned>(bytes: &[u8]) -> T {
+    lib_cbor::deserialize(bytes).unwrap()
+}
+
+// serialize_benches
+// an encode and a decode bench per format and entity
+macro_rules! serialize_benches {
+    ($($entity:ident: $ty:ty => $candid_ser:ident, $candid_de:ident, $cbor_ser:ident, $cbor_de:ident;)*) => {
+        $(
+            fn $candid_ser(bench: &mut Bencher) {
+                let e = $entity();
+                bench.iter(|| candid_encode(&e));
+                bench.bytes = candid_encode(&e).len() as u64;
+            }
+
+            fn $candid_de(bench: &mut Bencher) {
+                let bytes = candid_encode(&$entity());
+                bench.iter(|| candid_decode::<$ty>(&bytes));
+                bench.bytes = bytes.len() as u64;
+            }
+
+            fn $cbor_ser(bench: &mut Bencher) {
+                let e = $entity();
+                bench.iter(|| cbor_encode(&e));
+                bench.bytes = cbor_encode(&e).len() as u64;
+            }
+
+            fn $cbor_de(bench: &mut Bencher) {
+                let bytes = cbor_encode(&$entity());
+                bench.iter(|| cbor_decode::<$ty>(&bytes));
+                bench.bytes = bytes.len() as u64;
+            }
+        )*
+
+        benchmark_group!(
+            benches,
+            $($candid_ser, $candid_de, $cbor_ser, $cbor_de),*
+        );
+
+        // sizes
+        // bencher only shows bytes as throughput, so print them as well
+        fn sizes() {
+            $(
+                let e = $entity();
+                println!(
+                    "{}: candid {} bytes, cbor {} bytes",
+                    stringify!($entity),
+                    candid_encode(&e).len(),
+                    cbor_encode(&e).len(),
+                );
+            )*
+            println!();
+        }
+    };
+}
+
+serialize_benches! {
+    filterable: Filterable => candid_encode_filterable, candid_decode_filterable,
+        cbor_encode_filterable, cbor_decode_filterable;
+    has_map: HasMap => candid_encode_has_map, candid_decode_has_map,
+        cbor_encode_has_map, cbor_decode_has_map;
+}
+
+// main
+// what benchmark_main! expands to, with the sizes printed first
+fn main() {
+    sizes();
+
+    let mut opts = TestOpts::default();
+    if let Some(arg) = std::env::args().skip(1).find(|arg| *arg != "--bench") {
+        opts.filter = Some(arg);
+    }
+
+    run_tests_console(&opts, benches()).unwrap();
+}
@@ -1,10 +1,13 @@
 use crate::Error;
 use candid::{CandidType, Principal};
-use core_state::{ChildIndexManager, SubnetIndexManager};
+use core_state::{ChildIndexManager, ParentIndexManager, SubnetIndexManager};
+use db::Db;
 use ic::{api::is_controller, caller};
+use orm::traits::Entity;
 use orm_schema::node::AccessPolicy;
 use serde::{Deserialize, Serialize};
 use snafu::Snafu;
+use std::marker::PhantomData;
 
 ///
 /// AuthError
@@ -12,9 +15,15 @@ use snafu::Snafu;
 
 #[derive(CandidType, Debug, Serialize, Deserialize, Snafu)]
 pub enum AuthError {
+    #[snafu(display("anonymous callers are not allowed"))]
+    Anonymous,
+
     #[snafu(display("one or more rules must be defined"))]
     NoRulesDefined,
 
+    #[snafu(display("principal '{id}' is not an allowed caller"))]
+    NotAllowedCaller { id: Principal },
+
     #[snafu(display("this action is not allowed due to configuration settings"))]
     NotAllowed,
 
@@ -27,12 +36,18 @@ pub enum AuthError {
     #[snafu(display("principal '{id}' is not a controller of this canister'"))]
     NotController { id: Principal },
 
+    #[snafu(display("principal '{id}' was not created by this canister'"))]
+    NotCreated { id: Principal },
+
     #[snafu(display("principal '{id}' is not the parent of this canister'"))]
     NotParent { id: Principal },
 
     #[snafu(display("principal '{id}' does not have the permission '{permission}'"))]
     NotPermitted { id: Principal, permission: String },
 
+    #[snafu(display("principal '{id}' does not have the role '{role}'"))]
+    NotRole { id: Principal, role: String },
+
     #[snafu(display("principal '{id}' is not root"))]
     NotRoot { id: Principal },
 
@@ -47,6 +62,9 @@ pub enum AuthError {
 
     #[snafu(transparent)]
     CoreState { source: core_state::Error },
+
+    #[snafu(transparent)]
+    Query { source: db_query::Error },
 }
 
 ///
@@ -58,6 +76,7 @@ pub enum Guard {
     CanisterPath(String),
     Child,
     Controller,
+    Created,
     Parent,
     Permission(String),
     Policy(AccessPolicy),
@@ -72,6 +91,7 @@ impl Guard {
             Self::CanisterPath(path) => guard_canister_type(id, &path),
             Self::Child => guard_child(id),
             Self::Controller => guard_controller(id),
+            Self::Created => guard_created(id),
             Self::Parent => guard_parent(id),
             Self::Permission(path) => guard_permission(id, &path).await,
             Self::Policy(req) => guard_policy(id, &req).await,
@@ -138,6 +158,16 @@ fn guard_controller(id: Principal) -> Result<(), Error> {
     }
 }
 
+// guard_created
+// root only, passes for any canister root created or reserved
+fn guard_created(id: Principal) -> Result<(), Error> {
+    if ParentIndexManager::contains(id) {
+        Ok(())
+    } else {
+        Err(AuthError::NotCreated { id })?
+    }
+}
+
 // guard_root
 fn guard_root(id: Principal) -> Result<(), Error> {
     let root_id = crate::canister::root_id()?;
@@ -199,3 +229,167 @@ fn guard_this(id: Principal) -> Result<(), Error> {
         Err(AuthError::NotThis { id })?
     }
 }
+
+///
+/// REQUIRE
+/// synchronous checks on the caller for the top of an endpoint, they
+/// return the AuthError directly rather than going through guard
+///
+
+// require_caller_in
+pub fn require_caller_in(ids: &[Principal]) -> Result<(), AuthError> {
+    check_caller_in(caller(), ids)
+}
+
+// require_controller
+pub fn require_controller() -> Result<(), AuthError> {
+    let id = caller();
+
+    if is_controller(&id) {
+        Ok(())
+    } else {
+        Err(AuthError::NotController { id })
+    }
+}
+
+// require_not_anonymous
+pub fn require_not_anonymous() -> Result<(), AuthError> {
+    check_not_anonymous(caller())
+}
+
+// require_role
+// passes if the resolver says the principal holds the role
+pub fn require_role<R: RoleResolver>(
+    resolver: &R,
+    id: Principal,
+    role: &str,
+) -> Result<(), AuthError> {
+    let roles = resolver.roles(id)?;
+
+    if roles.iter().any(|r| r == role) {
+        Ok(())
+    } else {
+        Err(AuthError::NotRole {
+            id,
+            role: role.to_string(),
+        })
+    }
+}
+
+// check_caller_in
+fn check_caller_in(id: Principal, ids: &[Principal]) -> Result<(), AuthError> {
+    if ids.contains(&id) {
+        Ok(())
+    } else {
+        Err(AuthError::NotAllowedCaller { id })
+    }
+}
+
+// check_not_anonymous
+fn check_not_anonymous(id: Principal) -> Result<(), AuthError> {
+    if id == Principal::anonymous() {
+        Err(AuthError::Anonymous)
+    } else {
+        Ok(())
+    }
+}
+
+///
+/// RoleResolver
+/// looks up the roles held by a principal
+///
+
+pub trait RoleResolver {
+    fn roles(&self, id: Principal) -> Result<Vec<String>, AuthError>;
+}
+
+///
+/// RoleAssignment
+/// an entity keyed by the principal's text that lists the roles it holds
+///
+
+pub trait RoleAssignment: Entity {
+    fn roles(&self) -> Vec<String>;
+}
+
+///
+/// EntityRoleResolver
+/// loads the RoleAssignment entity for a principal, no row means no roles
+///
+
+pub struct EntityRoleResolver<'a, E> {
+    db: &'a Db,
+    _phantom: PhantomData<E>,
+}
+
+impl<'a, E> EntityRoleResolver<'a, E> {
+    #[must_use]
+    pub const fn new(db: &'a Db) -> Self {
+        Self {
+            db,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<E> RoleResolver for EntityRoleResolver<'_, E>
+where
+    E: RoleAssignment + 'static,
+{
+    fn roles(&self, id: Principal) -> Result<Vec<String>, AuthError> {
+        let row = db_query::load::<E>(self.db).get(&[id.to_text()])?;
+
+        Ok(row.map(|row| row.value.entity.roles()).unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_caller() {
+        let allowed = Principal::from_slice(&[1]);
+        let other = Principal::from_slice(&[2]);
+
+        assert!(check_caller_in(allowed, &[allowed]).is_ok());
+        assert!(matches!(
+            check_caller_in(other, &[allowed]),
+            Err(AuthError::NotAllowedCaller { id }) if id == other
+        ));
+
+        assert!(check_not_anonymous(allowed).is_ok());
+        assert!(matches!(
+            check_not_anonymous(Principal::anonymous()),
+            Err(AuthError::Anonymous)
+        ));
+    }
+
+    struct FakeResolver(Vec<(Principal, &'static str)>);
+
+    impl RoleResolver for FakeResolver {
+        fn roles(&self, id: Principal) -> Result<Vec<String>, AuthError> {
+            Ok(self
+                .0
+                .iter()
+                .filter(|(p, _)| *p == id)
+                .map(|(_, role)| (*role).to_string())
+                .collect())
+        }
+    }
+
+    #[test]
+    fn test_require_role() {
+        let admin = Principal::from_slice(&[1]);
+        let user = Principal::from_slice(&[2]);
+        let resolver = FakeResolver(vec![(admin, "admin"), (admin, "user"), (user, "user")]);
+
+        assert!(require_role(&resolver, admin, "admin").is_ok());
+        assert!(require_role(&resolver, user, "user").is_ok());
+        assert!(matches!(
+            require_role(&resolver, user, "admin"),
+            Err(AuthError::NotRole { id, role }) if id == user && role == "admin"
+        ));
+        assert!(require_role(&resolver, Principal::anonymous(), "user").is_err());
+    }
+}
@@ -1,9 +1,10 @@
 use crate::Error;
 use candid::{CandidType, Principal};
 use config::get_config;
+use core_wasm::WasmManager;
 use ic::{
     api::management_canister::{
-        main::{CanisterInstallMode, InstallCodeArgument, WasmModule},
+        main::{CanisterInstallMode, DefiniteCanisterSettings, InstallCodeArgument, WasmModule},
         provisional::CanisterSettings,
     },
     id, log, Log,
@@ -17,46 +18,181 @@ use snafu::Snafu;
 
 #[derive(CandidType, Debug, Serialize, Deserialize, Snafu)]
 pub enum CreateError {
+    #[snafu(display("{id} can't be made a controller"))]
+    ControllerNotAllowed { id: Principal },
+
+    #[snafu(display("{setting} is above the maximum of {max}"))]
+    SettingTooHigh { setting: String, max: u64 },
+
     #[snafu(transparent)]
     Config { source: config::Error },
+
+    #[snafu(transparent)]
+    Wasm { source: core_wasm::Error },
+}
+
+///
+/// CALL_FEE
+/// what root pays per call to the management canister or the new canister,
+/// create, install, start and init_async
+///
+/// BYTE_FEE
+/// what root pays per byte sent, the wasm being almost all of it
+///
+/// CREATE_FEE
+/// taken by the management canister out of the initial cycles
+///
+/// the fees are the 13 node subnet prices
+///
+
+const CALL_FEE: u128 = 260_000;
+const CALLS: u128 = 4;
+const BYTE_FEE: u128 = 1_000;
+const CREATE_FEE: u128 = 500_000_000_000;
+
+///
+/// CreateCost
+///
+/// initial_cycles : sent with create_canister, from the canister schema
+/// create_fee : the part of initial_cycles the new canister never sees
+/// transfer_cycles : the calls and the bytes of the wasm
+/// total : what root's balance goes down by, initial_cycles + transfer_cycles
+///
+
+#[derive(CandidType, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct CreateCost {
+    pub wasm_bytes: u64,
+    pub initial_cycles: u128,
+    pub create_fee: u128,
+    pub transfer_cycles: u128,
+    pub total: u128,
+}
+
+impl CreateCost {
+    #[must_use]
+    pub const fn new(initial_cycles: u128, wasm_bytes: u64) -> Self {
+        let transfer_cycles = CALL_FEE * CALLS + BYTE_FEE * wasm_bytes as u128;
+
+        Self {
+            wasm_bytes,
+            initial_cycles,
+            create_fee: CREATE_FEE,
+            transfer_cycles,
+            total: initial_cycles.saturating_add(transfer_cycles),
+        }
+    }
+}
+
+///
+/// estimate_create_cost
+/// what create_canister would cost for the path with the wasm root holds,
+/// nothing is sent so a caller can check its balance first
+///
+
+pub fn estimate_create_cost(canister_path: &str) -> Result<CreateCost, Error> {
+    let canister_schema = crate::schema::canister(canister_path)?;
+    let bytes = WasmManager::get_wasm(canister_path).map_err(CreateError::from)?;
+
+    Ok(CreateCost::new(
+        canister_schema.initial_cycles,
+        bytes.len() as u64,
+    ))
+}
+
+///
+/// MAX_COMPUTE_ALLOCATION
+/// in percent of an execution core, paid for by the canister every round
+///
+/// MAX_MEMORY_ALLOCATION
+/// in bytes, reserved and paid for whether it's used or not
+///
+
+const MAX_COMPUTE_ALLOCATION: u64 = 10;
+const MAX_MEMORY_ALLOCATION: u64 = 4 * 1024 * 1024 * 1024;
+
+///
+/// CreatedCanister
+///
+/// settings : as read back from the management canister once the code is
+/// installed, so they're what was applied rather than what was asked for
+///
+
+#[derive(CandidType, Clone, Debug, Serialize, Deserialize)]
+pub struct CreatedCanister {
+    pub canister_id: Principal,
+    pub settings: DefiniteCanisterSettings,
 }
 
 ///
 /// create_canister
+/// reserve and install in one go
+///
+/// settings : None uses the defaults, with the configured controllers,
+/// anything else is checked by check_settings first
 ///
 
 pub async fn create_canister(
     canister_path: &str,
     bytes: &[u8],
     parent_id: Principal,
-) -> Result<Principal, Error> {
-    let config = get_config().map_err(CreateError::from)?;
+    init_arg: Vec<u8>,
+    settings: Option<CanisterSettings>,
+) -> Result<CreatedCanister, Error> {
+    if let Some(settings) = &settings {
+        let config = get_config().map_err(CreateError::from)?;
+        let mut allowed = config.ic.controllers.clone();
+        allowed.extend([id(), parent_id]);
 
-    //
-    // controllers
-    // default controllers + root
-    //
+        check_settings(settings, &allowed)?;
+    }
 
-    let mut controllers: Vec<Principal> = config.ic.controllers.clone();
-    controllers.push(id());
+    let canister_id = new_canister(canister_path, settings).await?;
+    install_canister(canister_id, canister_path, bytes, parent_id, init_arg).await?;
 
-    //
-    // create canister
-    //
+    let settings = crate::mgmt::canister_status(canister_id).await?.settings;
 
-    let canister_schema = crate::schema::canister(canister_path)?;
-    let cycles = canister_schema.initial_cycles;
-    let settings = Some(CanisterSettings {
-        controllers: Some(controllers),
-        ..Default::default()
-    });
+    Ok(CreatedCanister {
+        canister_id,
+        settings,
+    })
+}
 
-    let canister_id = super::mgmt::create_canister(
-        ::ic::api::management_canister::main::CreateCanisterArgument { settings },
-        cycles,
-    )
-    .await?;
+///
+/// reserve_canister
+/// creates the canister with its cycles and stops it, so the id can be
+/// handed out now and the code installed later with install_canister
+///
 
+pub async fn reserve_canister(canister_path: &str) -> Result<Principal, Error> {
+    let canister_id = new_canister(canister_path, None).await?;
+    crate::mgmt::stop_canister(canister_id).await?;
+
+    log!(
+        Log::Ok,
+        "canister_reserve: {} reserved {}",
+        canister_path,
+        canister_id
+    );
+
+    Ok(canister_id)
+}
+
+///
+/// install_canister
+/// installs the wasm into an empty canister, reserved or just created,
+/// starts it and runs init_async
+///
+/// init takes the root and parent ids, then init_arg as a blob for the
+/// canister to decode itself
+///
+
+pub async fn install_canister(
+    canister_id: Principal,
+    canister_path: &str,
+    bytes: &[u8],
+    parent_id: Principal,
+    init_arg: Vec<u8>,
+) -> Result<(), Error> {
     //
     // install code
     //
@@ -65,10 +201,13 @@ pub async fn create_canister(
         mode: CanisterInstallMode::Install,
         canister_id,
         wasm_module: WasmModule::from(bytes),
-        arg: ::candid::utils::encode_args((id(), parent_id)).unwrap(),
+        arg: ::candid::utils::encode_args((id(), parent_id, init_arg)).unwrap(),
     };
     crate::mgmt::install_code(install_arg).await?;
 
+    // a reserved canister is stopped, starting a running one is a no-op
+    crate::mgmt::start_canister(canister_id).await?;
+
     //
     // call init_async
     //
@@ -85,12 +224,149 @@ pub async fn create_canister(
     let bytes_fmt = bytes.len() as f64 / 1_000.0;
     log!(
         Log::Ok,
-        "canister_create: {} created ({} KB) {} with {}",
+        "canister_install: {} installed ({} KB) {}",
         canister_path,
         bytes_fmt,
+        canister_id
+    );
+
+    Ok(())
+}
+
+// check_settings
+// controllers can only be root, the parent or the configured controllers,
+// and the allocations root's cycles would pay for are capped
+fn check_settings(settings: &CanisterSettings, allowed: &[Principal]) -> Result<(), CreateError> {
+    for id in settings.controllers.iter().flatten() {
+        if !allowed.contains(id) {
+            return Err(CreateError::ControllerNotAllowed { id: *id });
+        }
+    }
+
+    let caps = [
+        (
+            "compute_allocation",
+            &settings.compute_allocation,
+            MAX_COMPUTE_ALLOCATION,
+        ),
+        (
+            "memory_allocation",
+            &settings.memory_allocation,
+            MAX_MEMORY_ALLOCATION,
+        ),
+    ];
+    for (setting, value, max) in caps {
+        let too_high = value
+            .as_ref()
+            .is_some_and(|value| u64::try_from(&value.0).map_or(true, |value| value > max));
+        if too_high {
+            return Err(CreateError::SettingTooHigh {
+                setting: setting.to_string(),
+                max,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+// new_canister
+// an empty canister funded with the initial cycles from its schema
+async fn new_canister(
+    canister_path: &str,
+    settings: Option<CanisterSettings>,
+) -> Result<Principal, Error> {
+    let config = get_config().map_err(CreateError::from)?;
+
+    //
+    // controllers
+    // the ones asked for or the default controllers, root is always added
+    // as it has to install the code
+    //
+
+    let mut settings = settings.unwrap_or_default();
+    let mut controllers = settings
+        .controllers
+        .take()
+        .unwrap_or_else(|| config.ic.controllers.clone());
+    if !controllers.contains(&id()) {
+        controllers.push(id());
+    }
+
+    //
+    // create canister
+    //
+
+    let canister_schema = crate::schema::canister(canister_path)?;
+    let cycles = canister_schema.initial_cycles;
+    let settings = Some(CanisterSettings {
+        controllers: Some(controllers),
+        ..settings
+    });
+
+    let canister_id = super::mgmt::create_canister(
+        ::ic::api::management_canister::main::CreateCanisterArgument { settings },
+        cycles,
+    )
+    .await?;
+
+    log!(
+        Log::Ok,
+        "canister_create: {} created {} with {}",
+        canister_path,
         canister_id,
         ::ic::format_cycles(cycles)
     );
 
     Ok(canister_id)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use candid::Nat;
+
+    #[test]
+    fn test_create_cost() {
+        let cost = CreateCost::new(5 * ic::TC, 2_000_000);
+
+        assert_eq!(cost.transfer_cycles, 4 * 260_000 + 2_000_000_000);
+        assert_eq!(cost.total, 5 * ic::TC + cost.transfer_cycles);
+        assert!(cost.create_fee < cost.initial_cycles);
+    }
+
+    #[test]
+    fn test_check_settings() {
+        let (root, parent, other) = (
+            Principal::from_slice(&[1]),
+            Principal::from_slice(&[2]),
+            Principal::from_slice(&[3]),
+        );
+        let allowed = [root, parent];
+
+        let ok = CanisterSettings {
+            controllers: Some(vec![parent]),
+            compute_allocation: Some(Nat::from(MAX_COMPUTE_ALLOCATION)),
+            ..Default::default()
+        };
+        assert!(check_settings(&ok, &allowed).is_ok());
+
+        let foreign = CanisterSettings {
+            controllers: Some(vec![parent, other]),
+            ..Default::default()
+        };
+        assert!(matches!(
+            check_settings(&foreign, &allowed),
+            Err(CreateError::ControllerNotAllowed { id }) if id == other
+        ));
+
+        let greedy = CanisterSettings {
+            memory_allocation: Some(Nat::from(MAX_MEMORY_ALLOCATION + 1)),
+            ..Default::default()
+        };
+        assert!(matches!(
+            check_settings(&greedy, &allowed),
+            Err(CreateError::SettingTooHigh { .. })
+        ));
+    }
+}
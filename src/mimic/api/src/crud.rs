@@ -45,18 +45,20 @@ pub fn load<E>(db: &Db, request: LoadRequest) -> Result<LoadResponse, Error>
 where
     E: Entity + 'static,
 {
-    let iter = db_query::load::<E>(db)
+    // counts without a text filter don't need to load any rows
+    let count_keys = request.filter.is_none();
+    let query = db_query::load::<E>(db)
         .method(request.method)
         .order_option(request.order)
         .filter_option(request.filter)
         .limit_option(request.limit)
         .offset(request.offset)
-        .execute()
-        .map_err(CrudError::from)?;
+        .cursor_option(request.cursor);
 
     let res = match request.format {
         LoadFormat::Rows => {
             // convert to query rows
+            let iter = query.execute().map_err(CrudError::from)?;
             let rows = iter
                 .into_iter()
                 .map(QueryRow::try_from)
@@ -65,7 +67,21 @@ where
 
             LoadResponse::Rows(rows)
         }
-        LoadFormat::Count => LoadResponse::Count(iter.count() as u32),
+        LoadFormat::Count if count_keys => {
+            let count = query.count().map_err(CrudError::from)?;
+
+            LoadResponse::Count(count as u32)
+        }
+        LoadFormat::Count => {
+            let iter = query.execute().map_err(CrudError::from)?;
+
+            LoadResponse::Count(iter.count() as u32)
+        }
+        LoadFormat::Page => {
+            let page = query.page().map_err(CrudError::from)?;
+
+            LoadResponse::Page(page)
+        }
     };
 
     Ok(res)
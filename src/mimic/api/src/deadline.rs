@@ -0,0 +1,111 @@
+use std::{
+    cell::RefCell,
+    future::Future,
+    pin::{pin, Pin},
+    rc::Rc,
+    task::{Context, Poll, Waker},
+    time::Duration,
+};
+
+///
+/// DeadlineState
+/// shared with the timer, which flags it and wakes the waiting task
+///
+
+#[derive(Default)]
+struct DeadlineState {
+    expired: bool,
+    waker: Option<Waker>,
+}
+
+impl DeadlineState {
+    // expire
+    fn expire(&mut self) {
+        self.expired = true;
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+///
+/// Deadline
+///
+
+struct Deadline<'a, F: Future> {
+    fut: Pin<&'a mut F>,
+    state: Rc<RefCell<DeadlineState>>,
+}
+
+impl<F: Future> Future for Deadline<'_, F> {
+    type Output = Option<F::Output>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if let Poll::Ready(output) = self.fut.as_mut().poll(cx) {
+            return Poll::Ready(Some(output));
+        }
+
+        let mut state = self.state.borrow_mut();
+        if state.expired {
+            Poll::Ready(None)
+        } else {
+            state.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+// with_deadline
+// None if the deadline passes first, the IC can't cancel a call so it
+// carries on in the background and its response is dropped
+pub async fn with_deadline<F: Future>(fut: F, deadline: Duration) -> Option<F::Output> {
+    let state = Rc::new(RefCell::new(DeadlineState::default()));
+    let timer_state = Rc::clone(&state);
+    let timer = ic::timers::set_timer(deadline, move || timer_state.borrow_mut().expire());
+
+    let output = Deadline {
+        fut: pin!(fut),
+        state,
+    }
+    .await;
+    ic::timers::clear_timer(timer);
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{future::pending, sync::Arc, task::Wake};
+
+    struct Noop;
+
+    impl Wake for Noop {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    #[test]
+    fn test_deadline() {
+        let waker = Waker::from(Arc::new(Noop));
+        let mut cx = Context::from_waker(&waker);
+        let state = Rc::new(RefCell::new(DeadlineState::default()));
+
+        // a call that never answers is only pending until the timer fires
+        let mut fut = pin!(pending::<u8>());
+        let mut deadline = pin!(Deadline {
+            fut: fut.as_mut(),
+            state: Rc::clone(&state),
+        });
+        assert!(deadline.as_mut().poll(&mut cx).is_pending());
+        state.borrow_mut().expire();
+        assert_eq!(deadline.as_mut().poll(&mut cx), Poll::Ready(None));
+
+        // one that answers wins even after the timer
+        let mut fut = pin!(async { 1 });
+        let deadline = pin!(Deadline {
+            fut: fut.as_mut(),
+            state,
+        });
+        assert_eq!(deadline.poll(&mut cx), Poll::Ready(Some(1)));
+    }
+}
@@ -0,0 +1,14 @@
+use crate::Error;
+use candid::Principal;
+use ic::{log, Log};
+
+/// delete_canister
+/// stops the canister then deletes it, its remaining cycles are lost
+pub async fn delete_canister(canister_id: Principal) -> Result<(), Error> {
+    crate::mgmt::stop_canister(canister_id).await?;
+    crate::mgmt::delete_canister(canister_id).await?;
+
+    log!(Log::Ok, "canister_delete: {} deleted", canister_id);
+
+    Ok(())
+}
@@ -3,6 +3,9 @@ pub mod canister;
 pub mod cascade;
 pub mod create;
 pub mod crud;
+pub mod deadline;
+pub mod delete;
+pub mod meter;
 pub mod mgmt;
 pub mod request;
 pub mod schema;
@@ -20,7 +23,7 @@ use candid::{
 use ic::api::call::{call_raw, RejectionCode};
 use serde::{Deserialize, Serialize};
 use snafu::Snafu;
-use std::future::Future;
+use std::{future::Future, time::Duration};
 
 ///
 /// Error
@@ -88,6 +91,24 @@ pub fn call<T: ArgumentEncoder, R: for<'a> ArgumentDecoder<'a>>(
     }
 }
 
+// call_with_deadline
+// call, but gives up with a Timeout once the deadline passes
+pub async fn call_with_deadline<T: ArgumentEncoder, R: for<'a> ArgumentDecoder<'a>>(
+    id: Principal,
+    method: &str,
+    args: T,
+    deadline: Option<Duration>,
+) -> Result<R, Error> {
+    let fut = call(id, method, args);
+
+    match deadline {
+        Some(deadline) => deadline::with_deadline(fut, deadline)
+            .await
+            .ok_or_else(|| request::RequestError::timeout(method, deadline))?,
+        None => fut.await,
+    }
+}
+
 #[allow(clippy::needless_pass_by_value)]
 fn decoder_error_to_reject<T>(err: candid::error::Error) -> Error {
     (
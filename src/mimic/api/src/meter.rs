@@ -0,0 +1,107 @@
+use std::{
+    future::Future,
+    pin::{pin, Pin},
+    task::{Context, Poll},
+};
+
+///
+/// Metered
+///
+/// adds up what the balance drops by during each poll, other messages only
+/// run while the future is awaiting so whatever they spend isn't counted
+///
+
+struct Metered<'a, F: Future> {
+    fut: Pin<&'a mut F>,
+    balance: fn() -> u128,
+    spent: u128,
+}
+
+impl<F: Future> Future for Metered<'_, F> {
+    type Output = (F::Output, u128);
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let before = (self.balance)();
+        let poll = self.fut.as_mut().poll(cx);
+        self.spent = self
+            .spent
+            .saturating_add(before.saturating_sub((self.balance)()));
+
+        poll.map(|output| (output, self.spent))
+    }
+}
+
+// metered
+// the output and the cycles the future spent, balance is passed in so it
+// can be tested off the IC
+pub async fn metered<F: Future>(fut: F, balance: fn() -> u128) -> (F::Output, u128) {
+    Metered {
+        fut: pin!(fut),
+        balance,
+        spent: 0,
+    }
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        cell::Cell,
+        future::poll_fn,
+        sync::Arc,
+        task::{Wake, Waker},
+    };
+
+    struct Noop;
+
+    impl Wake for Noop {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    thread_local! {
+        static BALANCE: Cell<u128> = const { Cell::new(1_000) };
+    }
+
+    fn balance() -> u128 {
+        BALANCE.get()
+    }
+
+    fn spend(cycles: u128) {
+        BALANCE.set(BALANCE.get() - cycles);
+    }
+
+    #[test]
+    fn test_metered() {
+        let waker = Waker::from(Arc::new(Noop));
+        let mut cx = Context::from_waker(&waker);
+
+        let mut fut = pin!(async {
+            spend(100);
+            let mut awaited = false;
+            poll_fn(|_| {
+                if awaited {
+                    Poll::Ready(())
+                } else {
+                    awaited = true;
+                    Poll::Pending
+                }
+            })
+            .await;
+            spend(50);
+
+            1
+        });
+        let mut metered = pin!(Metered {
+            fut: fut.as_mut(),
+            balance,
+            spent: 0,
+        });
+        assert!(metered.as_mut().poll(&mut cx).is_pending());
+
+        // another message spends while the request is awaiting
+        spend(500);
+        assert_eq!(metered.poll(&mut cx), Poll::Ready((1, 150)));
+        assert_eq!(balance(), 350);
+    }
+}
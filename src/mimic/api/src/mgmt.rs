@@ -2,8 +2,10 @@ use crate::Error;
 use candid::Principal;
 use ic::api::management_canister::main::{
     canister_status as ic_canister_status, create_canister as ic_create_canister,
-    deposit_cycles as ic_deposit_cycles, install_code as ic_install_code, CanisterIdRecord,
-    CanisterStatusResponse, CreateCanisterArgument, InstallCodeArgument,
+    delete_canister as ic_delete_canister, deposit_cycles as ic_deposit_cycles,
+    install_code as ic_install_code, start_canister as ic_start_canister,
+    stop_canister as ic_stop_canister, CanisterIdRecord, CanisterStatusResponse,
+    CreateCanisterArgument, InstallCodeArgument,
 };
 
 // module_hash
@@ -38,6 +40,14 @@ pub async fn create_canister(
     Ok(res.canister_id)
 }
 
+// delete_canister
+// the canister has to be stopped first
+pub async fn delete_canister(canister_id: Principal) -> Result<(), Error> {
+    ic_delete_canister(CanisterIdRecord { canister_id }).await?;
+
+    Ok(())
+}
+
 // deposit_cycles
 pub async fn deposit_cycles(canister_id: Principal, cycles: u128) -> Result<(), Error> {
     ic_deposit_cycles(CanisterIdRecord { canister_id }, cycles).await?;
@@ -51,3 +61,17 @@ pub async fn install_code(arg: InstallCodeArgument) -> Result<(), Error> {
 
     Ok(())
 }
+
+// start_canister
+pub async fn start_canister(canister_id: Principal) -> Result<(), Error> {
+    ic_start_canister(CanisterIdRecord { canister_id }).await?;
+
+    Ok(())
+}
+
+// stop_canister
+pub async fn stop_canister(canister_id: Principal) -> Result<(), Error> {
+    ic_stop_canister(CanisterIdRecord { canister_id }).await?;
+
+    Ok(())
+}
@@ -1,10 +1,14 @@
-use crate::Error;
+use crate::{create::CreatedCanister, Error};
 use candid::{CandidType, Principal};
-use core_state::ChildIndexManager;
+use core_state::{ChildIndexManager, ParentIndexManager, RequestCostManager};
 use core_wasm::WasmManager;
-use ic::{caller, format_cycles, log, Log};
+use ic::{
+    api::management_canister::main::CanisterSettings, caller, format_cycles, helper::get_wasm_hash,
+    log, Log,
+};
 use serde::{Deserialize, Serialize};
 use snafu::Snafu;
+use std::time::Duration;
 use strum::Display;
 
 ///
@@ -16,10 +20,49 @@ pub enum RequestError {
     #[snafu(display("invalid response: {response}"))]
     InvalidResponse { response: Response },
 
+    #[snafu(display("{canister_id} is not a child of this canister"))]
+    NotChild { canister_id: Principal },
+
+    #[snafu(display("{caller} did not ask root for {canister_id}"))]
+    NotParent {
+        caller: Principal,
+        canister_id: Principal,
+    },
+
+    #[snafu(display("call to {method} timed out after {millis}ms"))]
+    Timeout { method: String, millis: u64 },
+
+    #[snafu(display(
+        "the code on {canister_id} isn't a version of {path} held by this build of root"
+    ))]
+    UnknownVersion {
+        canister_id: Principal,
+        path: String,
+    },
+
+    #[snafu(display("wasm hash mismatch for {path}: expected {expected}, found {found}"))]
+    WasmHashMismatch {
+        path: String,
+        expected: String,
+        found: String,
+    },
+
     #[snafu(transparent)]
     Wasm { source: core_wasm::Error },
 }
 
+impl RequestError {
+    // timeout
+    // partial milliseconds round up so a short deadline doesn't read as 0ms
+    #[must_use]
+    pub fn timeout(method: &str, deadline: Duration) -> Self {
+        Self::Timeout {
+            method: method.to_string(),
+            millis: u64::try_from(deadline.as_nanos().div_ceil(1_000_000)).unwrap_or(u64::MAX),
+        }
+    }
+}
+
 ///
 /// Request
 ///
@@ -31,16 +74,70 @@ pub struct Request {
 
 impl Request {
     #[must_use]
-    pub const fn new_canister_create(path: String) -> Self {
+    pub const fn new_canister_create(
+        path: String,
+        init_arg: Vec<u8>,
+        settings: Option<CanisterSettings>,
+    ) -> Self {
+        Self {
+            kind: RequestKind::CanisterCreate(CanisterCreate {
+                path,
+                init_arg,
+                settings,
+            }),
+        }
+    }
+
+    #[must_use]
+    pub const fn new_canister_reserve(path: String) -> Self {
+        Self {
+            kind: RequestKind::CanisterReserve(CanisterReserve { path }),
+        }
+    }
+
+    #[must_use]
+    pub const fn new_canister_delete(canister_id: Principal) -> Self {
+        Self {
+            kind: RequestKind::CanisterDelete(CanisterDelete { canister_id }),
+        }
+    }
+
+    #[must_use]
+    pub const fn new_canister_install(
+        canister_id: Principal,
+        path: String,
+        init_arg: Vec<u8>,
+    ) -> Self {
+        Self {
+            kind: RequestKind::CanisterInstall(CanisterInstall {
+                canister_id,
+                path,
+                init_arg,
+            }),
+        }
+    }
+
+    #[must_use]
+    pub const fn new_canister_upgrade(
+        canister_id: Principal,
+        path: String,
+        version: Option<u32>,
+        module_hash: Option<Vec<u8>>,
+    ) -> Self {
         Self {
-            kind: RequestKind::CanisterCreate(CanisterCreate { path }),
+            kind: RequestKind::CanisterUpgrade(CanisterUpgrade {
+                canister_id,
+                path,
+                version,
+                module_hash,
+            }),
         }
     }
 
     #[must_use]
-    pub const fn new_canister_upgrade(canister_id: Principal, path: String) -> Self {
+    pub const fn new_canister_rollback(canister_id: Principal, path: String) -> Self {
         Self {
-            kind: RequestKind::CanisterUpgrade(CanisterUpgrade { canister_id, path }),
+            kind: RequestKind::CanisterRollback(CanisterRollback { canister_id, path }),
         }
     }
 
@@ -50,6 +147,36 @@ impl Request {
             kind: RequestKind::Cycles(Cycles { cycles }),
         }
     }
+
+    #[must_use]
+    pub const fn new_cycles_distribute(budget: u128) -> Self {
+        Self {
+            kind: RequestKind::CyclesDistribute(CyclesDistribute { budget }),
+        }
+    }
+}
+
+// distribute
+// every deficit is covered if the budget allows, otherwise each canister
+// gets its share of the budget, rounded down
+fn distribute(budget: u128, deficits: &[(Principal, u128)]) -> Vec<(Principal, u128)> {
+    let total = deficits
+        .iter()
+        .fold(0u128, |total, (_, deficit)| total.saturating_add(*deficit));
+
+    deficits
+        .iter()
+        .map(|(id, deficit)| {
+            let cycles = if total <= budget {
+                *deficit
+            } else {
+                deficit.saturating_mul(budget) / total
+            };
+
+            (*id, cycles)
+        })
+        .filter(|(_, cycles)| *cycles > 0)
+        .collect()
 }
 
 ///
@@ -59,27 +186,88 @@ impl Request {
 #[derive(CandidType, Clone, Debug, Display, Serialize, Deserialize)]
 pub enum RequestKind {
     CanisterCreate(CanisterCreate),
+    CanisterDelete(CanisterDelete),
+    CanisterInstall(CanisterInstall),
+    CanisterReserve(CanisterReserve),
+    CanisterRollback(CanisterRollback),
     CanisterUpgrade(CanisterUpgrade),
     Cycles(Cycles), // cycles amount
+    CyclesDistribute(CyclesDistribute),
 }
 
 ///
 /// CanisterCreate
 ///
+/// init_arg : candid-encoded, handed to the new canister's init after the
+/// root and parent ids, empty if it takes nothing
+/// settings : None for the defaults, root is added to the controllers either way
+///
 
 #[derive(CandidType, Clone, Debug, Serialize, Deserialize)]
 pub struct CanisterCreate {
     pub path: String,
+    pub init_arg: Vec<u8>,
+    pub settings: Option<CanisterSettings>,
+}
+
+///
+/// CanisterDelete
+///
+
+#[derive(CandidType, Clone, Debug, Serialize, Deserialize)]
+pub struct CanisterDelete {
+    pub canister_id: Principal,
+}
+
+///
+/// CanisterInstall
+/// installs the wasm for path into a canister from CanisterReserve
+///
+/// init_arg : candid-encoded, as in CanisterCreate
+///
+
+#[derive(CandidType, Clone, Debug, Serialize, Deserialize)]
+pub struct CanisterInstall {
+    pub canister_id: Principal,
+    pub path: String,
+    pub init_arg: Vec<u8>,
+}
+
+///
+/// CanisterReserve
+/// creates an empty stopped canister, its cycles come from the schema for path
+///
+
+#[derive(CandidType, Clone, Debug, Serialize, Deserialize)]
+pub struct CanisterReserve {
+    pub path: String,
+}
+
+///
+/// CanisterRollback
+/// reinstalls the version of path before the one the canister is running
+///
+
+#[derive(CandidType, Clone, Debug, Serialize, Deserialize)]
+pub struct CanisterRollback {
+    pub canister_id: Principal,
+    pub path: String,
 }
 
 ///
 /// CanisterUpgrade
 ///
+/// version : the wasm version to install, the latest if None
+/// module_hash : the sha256 the wasm is expected to have, the upgrade is
+/// refused if the wasm held by root doesn't match
+///
 
 #[derive(CandidType, Clone, Debug, Serialize, Deserialize)]
 pub struct CanisterUpgrade {
     pub canister_id: Principal,
     pub path: String,
+    pub version: Option<u32>,
+    pub module_hash: Option<Vec<u8>>,
 }
 
 ///
@@ -91,15 +279,30 @@ pub struct Cycles {
     pub cycles: u128,
 }
 
+///
+/// CyclesDistribute
+/// the most root will send, root works out which children need cycles
+///
+
+#[derive(CandidType, Clone, Debug, Serialize, Deserialize)]
+pub struct CyclesDistribute {
+    pub budget: u128,
+}
+
 ///
 /// Response
 ///
 
 #[derive(CandidType, Clone, Debug, Display, Serialize, Deserialize)]
 pub enum Response {
-    CanisterCreate(Principal),
+    CanisterCreate(CreatedCanister),
+    CanisterDelete,
+    CanisterInstall,
+    CanisterReserve(Principal),
+    CanisterRollback(u32),
     CanisterUpgrade,
     Cycles,
+    CyclesDistribute(Vec<(Principal, Result<(), String>)>),
 }
 
 ///
@@ -107,34 +310,180 @@ pub enum Response {
 ///
 
 // response
+// the cycles each request costs root are added to the RequestCost totals,
+// metered per poll as other requests run on root while this one awaits
 pub async fn response(req: Request) -> Result<Response, Error> {
     // ::ic::println!("root response : {req:?}");
 
-    match req.kind {
-        RequestKind::CanisterCreate(kind) => response_create_canister(&kind.path).await,
+    let kind = req.kind.to_string();
+    let (res, cycles) =
+        crate::meter::metered(response_kind(req.kind), crate::canister::balance).await;
+    let cost = RequestCostManager::record(&kind, cycles);
+    log!(
+        Log::Info,
+        "response {kind}: {} cycles, {} over {} calls",
+        format_cycles(cycles),
+        format_cycles(cost.cycles),
+        cost.calls
+    );
+
+    res
+}
+
+// response_kind
+async fn response_kind(kind: RequestKind) -> Result<Response, Error> {
+    match kind {
+        RequestKind::CanisterCreate(kind) => {
+            response_create_canister(&kind.path, kind.init_arg, kind.settings).await
+        }
+        RequestKind::CanisterDelete(kind) => response_delete_canister(kind.canister_id).await,
+        RequestKind::CanisterInstall(kind) => {
+            response_install_canister(kind.canister_id, &kind.path, kind.init_arg).await
+        }
+        RequestKind::CanisterReserve(kind) => response_reserve_canister(&kind.path).await,
+        RequestKind::CanisterRollback(kind) => {
+            response_rollback_canister(kind.canister_id, &kind.path).await
+        }
         RequestKind::CanisterUpgrade(kind) => {
-            response_upgrade_canister(kind.canister_id, &kind.path).await
+            response_upgrade_canister(
+                kind.canister_id,
+                &kind.path,
+                kind.version,
+                kind.module_hash.as_deref(),
+            )
+            .await
         }
         RequestKind::Cycles(kind) => response_send_cycles(caller(), kind.cycles).await,
+        RequestKind::CyclesDistribute(kind) => response_distribute_cycles(kind.budget).await,
     }
 }
 
 // response_create_canister
-async fn response_create_canister(path: &str) -> Result<Response, Error> {
+async fn response_create_canister(
+    path: &str,
+    init_arg: Vec<u8>,
+    settings: Option<CanisterSettings>,
+) -> Result<Response, Error> {
     let bytes = WasmManager::get_wasm(path).map_err(RequestError::from)?;
-    let new_canister_id = crate::create::create_canister(path, bytes, caller()).await?;
+    let created = crate::create::create_canister(path, bytes, caller(), init_arg, settings).await?;
+    ParentIndexManager::set_parent(created.canister_id, caller());
 
-    Ok(Response::CanisterCreate(new_canister_id))
+    Ok(Response::CanisterCreate(created))
 }
 
-// response_upgrade_canister
-async fn response_upgrade_canister(canister_id: Principal, path: &str) -> Result<Response, Error> {
+// response_delete_canister
+async fn response_delete_canister(canister_id: Principal) -> Result<Response, Error> {
+    authorize_parent(canister_id)?;
+
+    crate::delete::delete_canister(canister_id).await?;
+    let _ = ParentIndexManager::remove(canister_id);
+
+    Ok(Response::CanisterDelete)
+}
+
+// authorize_parent
+// a request aimed at a canister is only carried out for the canister that
+// asked root to create it, which is root itself for auto-created canisters
+fn authorize_parent(canister_id: Principal) -> Result<(), Error> {
+    let caller = caller();
+
+    if !ParentIndexManager::is_parent(canister_id, caller) {
+        Err(RequestError::NotParent {
+            caller,
+            canister_id,
+        })?;
+    }
+
+    Ok(())
+}
+
+// response_reserve_canister
+// the caller is recorded so only it can install into the canister
+async fn response_reserve_canister(path: &str) -> Result<Response, Error> {
+    let new_canister_id = crate::create::reserve_canister(path).await?;
+    ParentIndexManager::set_parent(new_canister_id, caller());
+
+    Ok(Response::CanisterReserve(new_canister_id))
+}
+
+// response_install_canister
+// get_wasm checks the header before the reserved canister is touched
+async fn response_install_canister(
+    canister_id: Principal,
+    path: &str,
+    init_arg: Vec<u8>,
+) -> Result<Response, Error> {
+    authorize_parent(canister_id)?;
+
     let bytes = WasmManager::get_wasm(path).map_err(RequestError::from)?;
+
+    crate::create::install_canister(canister_id, path, bytes, caller(), init_arg).await?;
+
+    Ok(Response::CanisterInstall)
+}
+
+// response_upgrade_canister
+async fn response_upgrade_canister(
+    canister_id: Principal,
+    path: &str,
+    version: Option<u32>,
+    module_hash: Option<&[u8]>,
+) -> Result<Response, Error> {
+    authorize_parent(canister_id)?;
+
+    let bytes = match version {
+        Some(version) => WasmManager::get_wasm_version(path, version),
+        None => WasmManager::get_wasm(path),
+    }
+    .map_err(RequestError::from)?;
+
+    // the header was checked when the wasm was fetched
+    if let Some(expected) = module_hash {
+        let found = get_wasm_hash(bytes);
+        if found != expected {
+            Err(RequestError::WasmHashMismatch {
+                path: path.to_string(),
+                expected: hex::encode(expected),
+                found: hex::encode(found),
+            })?;
+        }
+    }
+
     crate::upgrade::upgrade_canister(canister_id, bytes).await?;
 
     Ok(Response::CanisterUpgrade)
 }
 
+// response_rollback_canister
+// the running version is found from the canister's module hash, so a
+// canister on code root doesn't hold can't be rolled back, see WASM_FILES
+async fn response_rollback_canister(canister_id: Principal, path: &str) -> Result<Response, Error> {
+    authorize_parent(canister_id)?;
+
+    let unknown = || RequestError::UnknownVersion {
+        canister_id,
+        path: path.to_string(),
+    };
+
+    let module_hash = crate::mgmt::module_hash(canister_id)
+        .await?
+        .ok_or_else(unknown)?;
+    let current = WasmManager::version_of(path, &module_hash)
+        .map_err(RequestError::from)?
+        .ok_or_else(unknown)?;
+
+    let version = WasmManager::previous_version(path, current).map_err(RequestError::from)?;
+    let bytes = WasmManager::get_wasm_version(path, version).map_err(RequestError::from)?;
+    crate::upgrade::upgrade_canister(canister_id, bytes).await?;
+
+    log!(
+        Log::Ok,
+        "canister_rollback: {canister_id} ({path}) v{current} -> v{version}"
+    );
+
+    Ok(Response::CanisterRollback(version))
+}
+
 // response_send_cycles
 async fn response_send_cycles(canister_id: Principal, cycles: u128) -> Result<Response, Error> {
     // actually send cycles
@@ -153,35 +502,92 @@ async fn response_send_cycles(canister_id: Principal, cycles: u128) -> Result<Re
     Ok(Response::Cycles)
 }
 
+// response_distribute_cycles
+// only root can ask, the deficits come from the balance of each of root's
+// children against the cycles in its schema, so nothing in the request
+// decides who gets what, each target succeeds or fails on its own
+async fn response_distribute_cycles(budget: u128) -> Result<Response, Error> {
+    let caller = caller();
+    if caller != crate::canister::id() {
+        Err(crate::auth::AuthError::NotThis { id: caller })?;
+    }
+
+    let mut deficits = Vec::new();
+    for (canister_id, path) in ChildIndexManager::get() {
+        let status = crate::mgmt::canister_status(canister_id).await?;
+        let balance = u128::try_from(&status.cycles.0).unwrap_or(u128::MAX);
+        let canister_schema = crate::schema::canister(&path)?;
+
+        deficits.push((
+            canister_id,
+            cycles_needed(
+                balance,
+                canister_schema.initial_cycles,
+                canister_schema.min_cycles,
+            ),
+        ));
+    }
+
+    let targets = distribute(budget, &deficits);
+    let mut results = Vec::with_capacity(targets.len());
+    for (canister_id, cycles) in targets {
+        let res = crate::mgmt::deposit_cycles(canister_id, cycles)
+            .await
+            .map_err(|e| e.to_string());
+
+        log!(
+            Log::Info,
+            "root_distribute_cycles: {} cycles to {canister_id}: {res:?}",
+            format_cycles(cycles)
+        );
+        results.push((canister_id, res));
+    }
+
+    Ok(Response::CyclesDistribute(results))
+}
+
 ///
 /// REQUEST
 /// all types of canister, but root just passes it to response
 ///
 
 // request
-pub async fn request(request: Request) -> Result<Response, Error> {
+// deadline : after this long the request fails with a Timeout, root may
+// still carry it out as calls can't be cancelled
+pub async fn request(request: Request, deadline: Option<Duration>) -> Result<Response, Error> {
     // ::ic::println!("request: {req:?}");
 
     let root_id = crate::canister::root_id()?;
-    let res = crate::call::<_, (Result<Response, Error>,)>(root_id, "response", (request,))
-        .await?
-        .0?;
+    let res = crate::call_with_deadline::<_, (Result<Response, Error>,)>(
+        root_id,
+        "response",
+        (request,),
+        deadline,
+    )
+    .await?
+    .0?;
 
     Ok(res)
 }
 
 // request_canister_create
 // create a Request and pass it to the request shared endpoint
-pub async fn request_canister_create(canister_path: &str) -> Result<Principal, Error> {
-    let req = Request::new_canister_create(canister_path.to_string());
-
-    match request(req).await {
+// init_arg, settings : see CanisterCreate
+pub async fn request_canister_create(
+    canister_path: &str,
+    init_arg: Vec<u8>,
+    settings: Option<CanisterSettings>,
+    deadline: Option<Duration>,
+) -> Result<CreatedCanister, Error> {
+    let req = Request::new_canister_create(canister_path.to_string(), init_arg, settings);
+
+    match request(req, deadline).await {
         Ok(response) => match response {
-            Response::CanisterCreate(new_canister_id) => {
+            Response::CanisterCreate(created) => {
                 // success, update child index
-                ChildIndexManager::add_canister(new_canister_id, canister_path);
+                ChildIndexManager::add_canister(created.canister_id, canister_path);
 
-                Ok(new_canister_id)
+                Ok(created)
             }
             _ => Err(RequestError::InvalidResponse { response })?,
         },
@@ -189,43 +595,141 @@ pub async fn request_canister_create(canister_path: &str) -> Result<Principal, E
     }
 }
 
+// request_canister_delete
+// only a child can be deleted, it's taken out of the child index once
+// root has deleted it
+pub async fn request_canister_delete(
+    canister_id: Principal,
+    deadline: Option<Duration>,
+) -> Result<(), Error> {
+    if ChildIndexManager::get_canister(canister_id).is_none() {
+        Err(RequestError::NotChild { canister_id })?;
+    }
+
+    let req = Request::new_canister_delete(canister_id);
+
+    match request(req, deadline).await? {
+        Response::CanisterDelete => {
+            let _ = ChildIndexManager::remove_canister(canister_id);
+
+            Ok(())
+        }
+        response => Err(RequestError::InvalidResponse { response })?,
+    }
+}
+
+// request_canister_reserve
+// the canister isn't a child until its code is installed
+pub async fn request_canister_reserve(
+    canister_path: &str,
+    deadline: Option<Duration>,
+) -> Result<Principal, Error> {
+    let req = Request::new_canister_reserve(canister_path.to_string());
+
+    match request(req, deadline).await? {
+        Response::CanisterReserve(canister_id) => Ok(canister_id),
+        response => Err(RequestError::InvalidResponse { response })?,
+    }
+}
+
+// request_canister_install
+// installs into a canister from request_canister_reserve and adds it to
+// the child index
+// init_arg : candid-encoded, see CanisterCreate
+pub async fn request_canister_install(
+    canister_id: Principal,
+    canister_path: &str,
+    init_arg: Vec<u8>,
+    deadline: Option<Duration>,
+) -> Result<(), Error> {
+    let req = Request::new_canister_install(canister_id, canister_path.to_string(), init_arg);
+
+    match request(req, deadline).await? {
+        Response::CanisterInstall => {
+            ChildIndexManager::add_canister(canister_id, canister_path);
+
+            Ok(())
+        }
+        response => Err(RequestError::InvalidResponse { response })?,
+    }
+}
+
 // request_canister_upgrade
+// version : if set, root installs that version instead of the latest
+// module_hash : if set, root only upgrades with a wasm of that sha256
 pub async fn request_canister_upgrade(
     canister_id: Principal,
     canister_path: String,
+    version: Option<u32>,
+    module_hash: Option<Vec<u8>>,
+    deadline: Option<Duration>,
 ) -> Result<(), Error> {
-    let req = Request::new_canister_upgrade(canister_id, canister_path);
-    let _res = request(req).await?;
+    let req = Request::new_canister_upgrade(canister_id, canister_path, version, module_hash);
+    let _res = request(req, deadline).await?;
 
     Ok(())
 }
 
+// request_upgrade_by_path
+// upgrades every child created from the path to the latest wasm, one at a
+// time, a failed upgrade is recorded and the rest carry on
+pub async fn request_upgrade_by_path(
+    canister_path: &str,
+    deadline: Option<Duration>,
+) -> Vec<(Principal, Result<(), String>)> {
+    let children = ChildIndexManager::by_path(canister_path);
+
+    let mut results = Vec::with_capacity(children.len());
+    for canister_id in children {
+        let res =
+            request_canister_upgrade(canister_id, canister_path.to_string(), None, None, deadline)
+                .await
+                .map_err(|e| e.to_string());
+
+        if let Err(e) = &res {
+            log!(
+                Log::Warn,
+                "upgrade_by_path: {canister_id} ({canister_path}): {e}"
+            );
+        }
+        results.push((canister_id, res));
+    }
+
+    results
+}
+
+// request_canister_rollback
+// returns the version the canister was rolled back to
+pub async fn request_canister_rollback(
+    canister_id: Principal,
+    canister_path: String,
+    deadline: Option<Duration>,
+) -> Result<u32, Error> {
+    let req = Request::new_canister_rollback(canister_id, canister_path);
+
+    match request(req, deadline).await? {
+        Response::CanisterRollback(version) => Ok(version),
+        response => Err(RequestError::InvalidResponse { response })?,
+    }
+}
+
 // request_cycles
-pub async fn request_cycles() -> Result<(), Error> {
-    // Get the schema and balance, handling potential errors early
-    let canister_schema = crate::canister::schema()?;
-    let balance = crate::canister::balance();
+pub async fn request_cycles(deadline: Option<Duration>) -> Result<(), Error> {
+    let status = cycle_status()?;
 
     log!(
         Log::Info,
         "cc check: balance: {}, initial {}, min {}",
-        format_cycles(balance),
-        format_cycles(canister_schema.initial_cycles),
-        format_cycles(canister_schema.min_cycles)
+        format_cycles(status.balance),
+        format_cycles(status.initial_cycles),
+        format_cycles(status.min_cycles)
     );
 
-    // Check if we need cycles and calculate the needed amount
-    let cycles_needed =
-        if balance < canister_schema.min_cycles && canister_schema.initial_cycles > balance {
-            canister_schema.initial_cycles - balance
-        } else {
-            0
-        };
-
     // Request cycles if needed
+    let cycles_needed = status.cycles_needed;
     if cycles_needed > 0 {
         let req = Request::new_cycles(cycles_needed);
-        let response = request(req).await?;
+        let response = request(req, deadline).await?;
 
         match response {
             Response::Cycles => {
@@ -233,7 +737,7 @@ pub async fn request_cycles() -> Result<(), Error> {
                     Log::Info,
                     "cc check: requested {}, end balance: {}",
                     format_cycles(cycles_needed),
-                    format_cycles(balance)
+                    format_cycles(status.balance)
                 );
 
                 Ok(())
@@ -244,3 +748,102 @@ pub async fn request_cycles() -> Result<(), Error> {
         Ok(())
     }
 }
+
+///
+/// CycleStatus
+///
+/// cycles_needed : what request_cycles would ask root for, 0 if the balance
+/// is above min_cycles
+///
+
+#[derive(CandidType, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct CycleStatus {
+    pub balance: u128,
+    pub initial_cycles: u128,
+    pub min_cycles: u128,
+    pub cycles_needed: u128,
+}
+
+// cycle_status
+// reads the same numbers as request_cycles without sending anything
+pub fn cycle_status() -> Result<CycleStatus, Error> {
+    let canister_schema = crate::canister::schema()?;
+    let balance = crate::canister::balance();
+
+    Ok(CycleStatus {
+        balance,
+        initial_cycles: canister_schema.initial_cycles,
+        min_cycles: canister_schema.min_cycles,
+        cycles_needed: cycles_needed(
+            balance,
+            canister_schema.initial_cycles,
+            canister_schema.min_cycles,
+        ),
+    })
+}
+
+// cycles_needed
+// tops the balance back up to initial_cycles once it drops below min_cycles
+const fn cycles_needed(balance: u128, initial_cycles: u128, min_cycles: u128) -> u128 {
+    if balance < min_cycles && initial_cycles > balance {
+        initial_cycles - balance
+    } else {
+        0
+    }
+}
+
+// request_cycles_distribute
+// root only, shares the budget between root's children by their deficit
+pub async fn request_cycles_distribute(
+    budget: u128,
+    deadline: Option<Duration>,
+) -> Result<Vec<(Principal, Result<(), String>)>, Error> {
+    let req = Request::new_cycles_distribute(budget);
+
+    match request(req, deadline).await? {
+        Response::CyclesDistribute(results) => Ok(results),
+        response => Err(RequestError::InvalidResponse { response })?,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distribute() {
+        let (a, b, c) = (
+            Principal::from_slice(&[1]),
+            Principal::from_slice(&[2]),
+            Principal::from_slice(&[3]),
+        );
+        let deficits = [(a, 300), (b, 100), (c, 0)];
+
+        // enough for everyone
+        assert_eq!(distribute(1_000, &deficits), vec![(a, 300), (b, 100)]);
+
+        // shared by deficit
+        assert_eq!(distribute(200, &deficits), vec![(a, 150), (b, 50)]);
+        assert!(distribute(0, &deficits).is_empty());
+    }
+
+    #[test]
+    fn test_cycles_needed() {
+        assert_eq!(cycles_needed(50, 100, 80), 50);
+        assert_eq!(cycles_needed(90, 100, 80), 0);
+        assert_eq!(cycles_needed(50, 40, 80), 0);
+    }
+
+    #[test]
+    fn test_timeout() {
+        let millis = |deadline| match RequestError::timeout("m", deadline) {
+            RequestError::Timeout { millis, .. } => millis,
+            _ => unreachable!(),
+        };
+
+        assert_eq!(millis(Duration::from_millis(1_500)), 1_500);
+        assert_eq!(millis(Duration::from_micros(1)), 1);
+        assert_eq!(millis(Duration::ZERO), 0);
+        assert_eq!(millis(Duration::MAX), u64::MAX);
+    }
+}
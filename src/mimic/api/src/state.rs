@@ -1,7 +1,10 @@
+use candid::Principal;
 use core_state::{
     AppState, AppStateManager, CanisterState, CanisterStateManager, ChildIndex, ChildIndexManager,
-    SubnetIndex, SubnetIndexManager, UserIndex, UserIndexManager,
+    RequestCostManager, RequestCosts, SubnetIndex, SubnetIndexManager, UserIndex, UserIndexManager,
 };
+use ic::structures::memory::MemoryUsage;
+use std::collections::BTreeMap;
 
 ///
 /// STATE LOOKUP
@@ -26,6 +29,24 @@ pub fn child_index() -> ChildIndex {
     ChildIndexManager::get()
 }
 
+// child_index_by_path
+#[must_use]
+pub fn child_index_by_path(path: &str) -> Vec<Principal> {
+    ChildIndexManager::by_path(path)
+}
+
+// memory_usage
+#[must_use]
+pub fn memory_usage() -> BTreeMap<String, MemoryUsage> {
+    core_state::memory_usage()
+}
+
+// request_costs
+#[must_use]
+pub fn request_costs() -> RequestCosts {
+    RequestCostManager::get()
+}
+
 // subnet_index
 #[must_use]
 pub fn subnet_index() -> SubnetIndex {
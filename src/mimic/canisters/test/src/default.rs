@@ -18,5 +18,6 @@ impl DefaultTester {
 
         assert_eq!(r.u8_value, 1);
         assert_eq!(r.u8_static_fn, 32);
+        assert_eq!(r.u8_expr, 40);
     }
 }
@@ -1,4 +1,6 @@
-use mimic_base::types::test::sanitize::ClampRecord;
+use mimic_base::types::test::sanitize::{
+    ClampEntity, ClampRecord, TrimLower, U8ClampHighLow, U8ClampLowHigh,
+};
 
 ///
 /// SanitizeTester
@@ -10,6 +12,8 @@ impl SanitizeTester {
     // test
     pub fn test() {
         Self::test_clamp();
+        Self::test_order();
+        Self::test_builder();
     }
 
     //
@@ -33,4 +37,28 @@ impl SanitizeTester {
         orm::sanitize(&mut r);
         assert!(r.value == 20.into());
     }
+
+    // test_order
+    // sanitizers run in the order they're declared
+    fn test_order() {
+        let mut s = TrimLower::from("  Hello ");
+        orm::sanitize(&mut s);
+        assert!(s == "hello".into());
+
+        // the same clamps in the other order give a different result
+        let mut high_low = U8ClampHighLow::from(15_u8);
+        orm::sanitize(&mut high_low);
+        assert!(high_low == 5_u8.into());
+
+        let mut low_high = U8ClampLowHigh::from(15_u8);
+        orm::sanitize(&mut low_high);
+        assert!(low_high == 10_u8.into());
+    }
+
+    // test_builder
+    // build sanitizes before it validates, like a save
+    fn test_builder() {
+        let e = ClampEntity::builder().with_value(25_u8).build().unwrap();
+        assert!(e.value == 20.into());
+    }
 }
@@ -1,4 +1,5 @@
-use mimic_base::types::test::validate::Validator;
+use mimic_base::types::test::validate::{DateRange, MultipleTenType, Validator};
+use orm::traits::EntityDynamic;
 
 ///
 /// ValidateTester
@@ -10,6 +11,8 @@ impl ValidateTester {
     // test
     pub fn test() {
         Self::test_record();
+        Self::test_entity_check();
+        Self::test_builder();
     }
 
     //
@@ -21,15 +24,18 @@ impl ValidateTester {
         // ok
         let e = Validator {
             guide: 5.into(),
-            multiple_ten: 30.into(),
+            multiple_ten: MultipleTenType::try_from(30).unwrap(),
         };
         let res = orm::validate(&e);
         assert!(res.is_ok(), "{res:?}");
 
         // fail
+        // TryFrom won't build an invalid value so it's set through DerefMut
+        let mut multiple_ten = MultipleTenType::default();
+        *multiple_ten = 43;
         let e = Validator {
             guide: 0.into(),
-            multiple_ten: 43.into(),
+            multiple_ten,
         };
         let res = orm::validate(&e);
 
@@ -42,8 +48,63 @@ impl ValidateTester {
                     2,
                     "both guide and multiple_ten fields expected to fail validation"
                 );
+
+                // each error names its field and the validator that failed
+                let found: Vec<(&str, Option<&str>)> = errors
+                    .iter()
+                    .map(|e| (e.path.as_str(), e.validator.as_deref()))
+                    .collect();
+                assert!(found.contains(&("guide", Some("guide"))));
+                assert!(found.contains(&(
+                    "multiple_ten",
+                    Some("mimic_base::validator::number::MultipleOf")
+                )));
             }
             Err(e) => panic!("unexpected error: {e}"),
         }
     }
+
+    // test_entity_check
+    fn test_entity_check() {
+        let ok = DateRange {
+            start: 1,
+            end: 2,
+            ..Default::default()
+        };
+        assert!(orm::validate(&ok).is_ok());
+
+        let bad = DateRange {
+            start: 2,
+            end: 1,
+            ..Default::default()
+        };
+        match orm::validate(&bad) {
+            Err(orm::Error::Validation { errors }) => {
+                assert_eq!(errors.len(), 1);
+                assert_eq!(errors[0].path, "end");
+                assert_eq!(errors[0].validator.as_deref(), Some("date_order"));
+            }
+            res => panic!("unexpected result: {res:?}"),
+        }
+
+        // a save runs the checks through the entity adapter
+        assert!(bad.validate_self().is_err());
+    }
+
+    // test_builder
+    fn test_builder() {
+        let range = DateRange::builder()
+            .with_start(1_u64)
+            .with_end(2_u64)
+            .build()
+            .unwrap();
+        assert_eq!((range.start, range.end), (1, 2));
+
+        // build runs the entity checks too
+        assert!(DateRange::builder()
+            .with_start(2_u64)
+            .with_end(1_u64)
+            .build()
+            .is_err());
+    }
 }
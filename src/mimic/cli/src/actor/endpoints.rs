@@ -34,6 +34,12 @@ pub fn canister_endpoints(builder: &mut ActorBuilder) {
             ::mimic::api::canister::balance()
         }
 
+        // canister_cycle_status
+        #[::mimic::ic::query]
+        fn canister_cycle_status() -> Result<::mimic::api::request::CycleStatus, ::mimic::Error> {
+            ::mimic::api::request::cycle_status().map_err(::mimic::Error::from)
+        }
+
         // canister_caller
         #[::mimic::ic::query]
         fn canister_caller() -> Principal {
@@ -64,6 +70,27 @@ pub fn canister_endpoints(builder: &mut ActorBuilder) {
             ::mimic::api::canister::version()
         }
 
+        // canister_delete_child
+        #[::mimic::ic::update]
+        async fn canister_delete_child(canister_id: Principal) -> Result<(), ::mimic::Error> {
+            guard(vec![Guard::Controller]).await?;
+
+            ::mimic::api::request::request_canister_delete(canister_id, None).await?;
+
+            Ok(())
+        }
+
+        // canister_upgrade_path
+        // returns each child of the path with how its upgrade went
+        #[::mimic::ic::update]
+        async fn canister_upgrade_path(
+            path: String,
+        ) -> Result<Vec<(Principal, Result<(), String>)>, ::mimic::Error> {
+            guard(vec![Guard::Controller]).await?;
+
+            Ok(::mimic::api::request::request_upgrade_by_path(&path, None).await)
+        }
+
         // canister_upgrade_children
         // canister_id : None means upgrade all children
         #[::mimic::ic::update]
@@ -76,7 +103,8 @@ pub fn canister_endpoints(builder: &mut ActorBuilder) {
             for (child_id, path) in child_index() {
                 if canister_id.is_none() || canister_id == Some(child_id) {
                     if let Err(e) =
-                        ::mimic::api::request::request_canister_upgrade(child_id, path.clone()).await
+                        ::mimic::api::request::request_canister_upgrade(child_id, path.clone(), None, None, None)
+                            .await
                     {
                         log!(Log::Warn, "{child_id} ({path}): {e}");
                     }
@@ -139,12 +167,15 @@ pub fn ic_endpoints(builder: &mut ActorBuilder) {
         // pre_upgrade
         #[::mimic::ic::pre_upgrade]
         fn pre_upgrade() {
+            actorgen::log_store_sizes();
             pre_upgrade2().unwrap();
         }
 
         // post_upgrade
         #[::mimic::ic::post_upgrade]
         fn post_upgrade() {
+            register_memory();
+            actorgen::init_timers().unwrap();
             post_upgrade2().unwrap();
         }
 
@@ -186,6 +217,12 @@ pub fn state_endpoints(builder: &mut ActorBuilder) {
             ::mimic::api::state::child_index()
         }
 
+        // child_index_by_path
+        #[::mimic::ic::query]
+        fn child_index_by_path(path: String) -> Vec<Principal> {
+            ::mimic::api::state::child_index_by_path(&path)
+        }
+
         // subnet_index
         #[::mimic::ic::query]
         fn subnet_index() -> ::mimic::core::state::SubnetIndex {
@@ -215,7 +252,56 @@ pub fn store_endpoints(builder: &mut ActorBuilder) {
 
             Ok(keys)
         }
+
+        // store_stats
+        #[::mimic::ic::query(composite = true)]
+        #[allow(clippy::needless_pass_by_value)]
+        async fn store_stats(store_name: String) -> Result<::mimic::db::StoreStats, ::mimic::Error> {
+            guard(vec![Guard::Controller]).await?;
+
+            DB.with(|db| db.stats(&store_name)).map_err(::mimic::Error::from)
+        }
+
+        // set_read_only
+        // rejects every save and delete until it's turned off again
+        #[::mimic::ic::update]
+        async fn set_read_only(read_only: bool) -> Result<(), ::mimic::Error> {
+            guard(vec![Guard::Controller]).await?;
+
+            ::mimic::core::state::CanisterStateManager::set_read_only(read_only)
+                .map_err(::mimic::Error::from)
+        }
+
+        // memory_usage
+        // stable memory pages per structure, to check an upgrade will fit
+        #[::mimic::ic::query(composite = true)]
+        async fn memory_usage() -> Result<
+            ::std::collections::BTreeMap<String, ::mimic::ic::structures::memory::MemoryUsage>,
+            ::mimic::Error,
+        > {
+            guard(vec![Guard::Controller]).await?;
+
+            Ok(::mimic::api::state::memory_usage())
+        }
     };
 
     builder.extend_actor(q);
 }
+
+// list_endpoints
+// lists every query and update method tracked by the builder, including itself
+pub fn list_endpoints(builder: &mut ActorBuilder) {
+    builder.add_endpoint("list_endpoints");
+    let endpoints = &builder.endpoints;
+
+    let q = quote! {
+
+        // list_endpoints
+        #[::mimic::ic::query]
+        fn list_endpoints() -> Vec<String> {
+            vec![#(#endpoints.to_string()),*]
+        }
+    };
+
+    builder.actor_tokens.extend(q);
+}
@@ -23,6 +23,8 @@ fn init_root(builder: &ActorBuilder) -> TokenStream {
     quote! {
         #[::mimic::ic::init]
         fn init() {
+            register_memory();
+
             let id = id();
 
             log!(Log::Info, "**********************************");
@@ -44,12 +46,15 @@ fn init_default(builder: &ActorBuilder) -> TokenStream {
 
     quote! {
         #[::mimic::ic::init]
-        fn init(root_id: Principal, parent_id: Principal) {
+        fn init(root_id: Principal, parent_id: Principal, init_arg: Vec<u8>) {
+            register_memory();
+
             log!(Log::Info, "init: {}", #canister_path);
 
             CanisterStateManager::set_path(#canister_path.to_string()).unwrap();
             CanisterStateManager::set_root_id(root_id).unwrap();
             CanisterStateManager::set_parent_id(parent_id).unwrap();
+            CanisterStateManager::set_init_arg(init_arg).unwrap();
 
             #hooks
         }
@@ -64,6 +69,8 @@ fn init_test(builder: &ActorBuilder) -> TokenStream {
     quote! {
         #[::mimic::ic::init]
         fn init() {
+            register_memory();
+
             log!(Log::Info, "init: test");
 
             CanisterStateManager::set_path(#canister_path.to_string()).unwrap();
@@ -7,12 +7,12 @@ pub mod stores;
 pub mod timers;
 pub mod user;
 
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use orm_schema::{
     build::schema,
     node::{Canister, CanisterBuild, Entity, Store},
 };
-use proc_macro2::TokenStream;
+use proc_macro2::{Delimiter, TokenStream, TokenTree};
 use quote::quote;
 use std::process;
 
@@ -24,6 +24,36 @@ use std::process;
 pub struct Command {
     #[clap(help = "Name of the canister to generate code for")]
     canister_name: String,
+
+    #[clap(
+        long,
+        value_enum,
+        default_value_t = CyclesCheck::Timer,
+        help = "How the canister schedules its cycles check"
+    )]
+    cycles_check: CyclesCheck,
+
+    #[clap(
+        long,
+        default_value_t = 600,
+        value_parser = clap::value_parser!(u64).range(1..),
+        help = "Heartbeats between each cycles check in heartbeat mode"
+    )]
+    heartbeats: u64,
+}
+
+///
+/// CyclesCheck
+///
+/// Timer     : a recurring timer, set again after every upgrade
+/// Heartbeat : counts canister heartbeats, for canisters that can't rely on timers
+///
+
+#[derive(Clone, Copy, Debug, Default, ValueEnum)]
+pub enum CyclesCheck {
+    #[default]
+    Timer,
+    Heartbeat,
 }
 
 // process
@@ -41,7 +71,9 @@ pub fn process(command: Command) {
     };
 
     // create the ActorBuilder and generate the code
-    let code = ActorBuilder::new(canister.clone());
+    let mut code = ActorBuilder::new(canister.clone());
+    code.cycles_check = command.cycles_check;
+    code.heartbeats = command.heartbeats;
     let tokens = code.expand();
 
     println!("{tokens}");
@@ -53,7 +85,10 @@ pub fn process(command: Command) {
 
 pub struct ActorBuilder {
     pub canister: Canister,
+    pub cycles_check: CyclesCheck,
+    pub heartbeats: u64,
     pub hooks: Vec<String>,
+    pub endpoints: Vec<String>,
     pub actor_tokens: TokenStream,
     pub module_tokens: TokenStream,
 }
@@ -64,14 +99,22 @@ impl ActorBuilder {
     pub fn new(canister: Canister) -> Self {
         Self {
             canister,
+            cycles_check: CyclesCheck::default(),
+            heartbeats: 600,
             hooks: Vec::new(),
+            endpoints: Vec::new(),
             actor_tokens: quote!(),
             module_tokens: quote!(),
         }
     }
 
     // extend_actor
+    // any query or update methods are tracked for list_endpoints
     pub fn extend_actor(&mut self, tokens: TokenStream) {
+        for endpoint in find_endpoints(tokens.clone()) {
+            self.add_endpoint(&endpoint);
+        }
+
         self.actor_tokens.extend(tokens);
     }
 
@@ -85,6 +128,14 @@ impl ActorBuilder {
         self.hooks.push(hook.to_string());
     }
 
+    // add_endpoint
+    // for endpoints that are registered by hand rather than found in the tokens
+    pub fn add_endpoint(&mut self, endpoint: &str) {
+        if !self.endpoints.iter().any(|e| e == endpoint) {
+            self.endpoints.push(endpoint.to_string());
+        }
+    }
+
     // expand
     #[must_use]
     pub fn expand(mut self) -> TokenStream {
@@ -111,6 +162,10 @@ impl ActorBuilder {
         self.add_hook("init2");
         init::extend(&mut self);
 
+        // endpoints
+        // this goes after init so every endpoint has been tracked
+        endpoints::list_endpoints(&mut self);
+
         //
         // generate code
         //
@@ -170,3 +225,67 @@ impl ActorBuilder {
         entities
     }
 }
+
+// find_endpoints
+// walks the top level of the tokens, returning the name of every fn that
+// has an ic query or update attribute
+fn find_endpoints(tokens: TokenStream) -> Vec<String> {
+    let mut endpoints = Vec::new();
+    let mut pending = false;
+    let mut next_is_name = false;
+
+    for tt in tokens {
+        match tt {
+            TokenTree::Group(group)
+                if group.delimiter() == Delimiter::Bracket
+                    && group.stream().into_iter().any(|tt| {
+                        matches!(tt, TokenTree::Ident(ident) if ident == "query" || ident == "update")
+                    }) =>
+            {
+                pending = true;
+            }
+            TokenTree::Ident(ident) if next_is_name => {
+                endpoints.push(ident.to_string());
+                pending = false;
+                next_is_name = false;
+            }
+            TokenTree::Ident(ident) if pending && ident == "fn" => next_is_name = true,
+            TokenTree::Punct(punct) if punct.as_char() == ';' => pending = false,
+            _ => {}
+        }
+    }
+
+    endpoints
+}
+
+//
+// TESTS
+//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_endpoints() {
+        let tokens = quote! {
+            // canister_id
+            #[::mimic::ic::query]
+            fn canister_id() -> Principal {
+                ::mimic::api::canister::id()
+            }
+
+            async fn guard_crud() -> Result<(), ::mimic::Error> {
+                Ok(())
+            }
+
+            #[::mimic::ic::update]
+            #[allow(clippy::too_many_lines)]
+            pub async fn crud_save() -> Result<(), ::mimic::Error> {
+                Ok(())
+            }
+        };
+
+        assert_eq!(find_endpoints(tokens), vec!["canister_id", "crud_save"]);
+    }
+}
@@ -23,12 +23,39 @@ pub fn root_actor(builder: &mut ActorBuilder) {
         }
 
         // response
+        // only root itself and the canisters it made can send requests
         #[::mimic::ic::update]
         async fn response(req: Request) -> Result<Response, ::mimic::Error> {
+            guard(vec![Guard::This, Guard::Created]).await?;
+
             let res = ::mimic::api::request::response(req).await?;
 
             Ok(res)
         }
+
+        // estimate_create_cost
+        #[::mimic::ic::query]
+        fn estimate_create_cost(
+            path: String,
+        ) -> Result<::mimic::api::create::CreateCost, ::mimic::Error> {
+            ::mimic::api::create::estimate_create_cost(&path).map_err(::mimic::Error::from)
+        }
+
+        // cycles_distribute
+        #[::mimic::ic::update]
+        async fn cycles_distribute(
+            budget: u128,
+        ) -> Result<Vec<(Principal, Result<(), String>)>, ::mimic::Error> {
+            guard(vec![Guard::Controller]).await?;
+
+            ::mimic::api::request::request_cycles_distribute(budget, None).await
+        }
+
+        // request_costs
+        #[::mimic::ic::query]
+        fn request_costs() -> ::mimic::core::state::RequestCosts {
+            ::mimic::api::state::request_costs()
+        }
     };
 
     builder.extend_actor(q);
@@ -53,8 +80,10 @@ pub fn root_module(builder: &mut ActorBuilder) {
             for path in paths {
                 if SubnetIndexManager::get_canister(path).is_none() {
                     // set the canister within the service index
-                    let new_canister_id = ::mimic::api::request::request_canister_create(path)
-                        .await?;
+                    let new_canister_id =
+                        ::mimic::api::request::request_canister_create(path, vec![], None, None)
+                            .await?
+                            .canister_id;
 
                     SubnetIndexManager::set_canister(path, new_canister_id);
                 } else {
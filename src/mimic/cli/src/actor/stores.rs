@@ -4,12 +4,14 @@ use quote::quote;
 // extend
 pub fn extend(builder: &mut ActorBuilder) {
     stores(builder);
+    store_sizes(builder);
 }
 
 // stores
 fn stores(builder: &mut ActorBuilder) {
     let mut store_defs = quote!();
     let mut db_inserts = quote!();
+    let mut store_registers = quote!();
 
     for (store_path, store) in builder.get_stores() {
         let cell_ident = store.cell_ident();
@@ -19,9 +21,9 @@ fn stores(builder: &mut ActorBuilder) {
         store_defs.extend(quote! {
             static #cell_ident: RefCell<::mimic::db::Store> = RefCell::new(
                 ::mimic::db::Store::init(
-                    ::mimic::core::state::MEMORY_MANAGER.with(|mm| mm.borrow().get(
-                        ::mimic::ic::structures::memory::MemoryId::new(#memory_id)
-                    ))
+                    ::mimic::core::state::MEMORY_MANAGER.with_borrow(|mm| {
+                        ::mimic::ic::structures::memory::get_memory(mm, #memory_id, #store_path)
+                    })
                 )
             );
         });
@@ -30,6 +32,11 @@ fn stores(builder: &mut ActorBuilder) {
         db_inserts.extend(quote! {
             db.insert(#store_path, & #cell_ident);
         });
+
+        store_registers.extend(quote! {
+            ::mimic::ic::structures::memory::register(#memory_id, #store_path)
+                .unwrap_or_else(|e| panic!("{e}"));
+        });
     }
 
     // format stores variable
@@ -54,7 +61,50 @@ fn stores(builder: &mut ActorBuilder) {
 
             static DB: ::mimic::db::Db = #db;
         }
+
+        // register_memory
+        // claims the memory id of every structure before any is used, called
+        // first thing from init and post_upgrade so a collision traps there
+        fn register_memory() {
+            ::mimic::core::state::register_memory().unwrap_or_else(|e| panic!("{e}"));
+            #store_registers
+        }
     };
 
     builder.extend_actor(q);
 }
+
+// store_sizes
+// logs the rows and stable memory of each store, called from pre_upgrade
+fn store_sizes(builder: &mut ActorBuilder) {
+    let threshold = builder.canister.upgrade_warn_bytes();
+    let mut checks = quote!();
+
+    for (store_path, store) in builder.get_stores() {
+        let cell_ident = store.cell_ident();
+        let memory_id = store.memory_id;
+
+        checks.extend(quote! {
+            let rows = #cell_ident.with_borrow(|store| store.len());
+            let bytes = ::mimic::core::state::MEMORY_MANAGER.with_borrow(|mm| {
+                ::mimic::ic::structures::Memory::size(
+                    &mm.get(::mimic::ic::structures::memory::MemoryId::new(#memory_id))
+                )
+            }) * ::mimic::ic::structures::memory::WASM_PAGE_SIZE;
+
+            if bytes > #threshold {
+                log!(Log::Warn, "store {}: {} rows, {} bytes (over {} bytes)", #store_path, rows, bytes, #threshold);
+            } else {
+                log!(Log::Info, "store {}: {} rows, {} bytes", #store_path, rows, bytes);
+            }
+        });
+    }
+
+    let q = quote! {
+        pub fn log_store_sizes() {
+            #checks
+        }
+    };
+
+    builder.extend_module(q);
+}
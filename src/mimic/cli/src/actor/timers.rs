@@ -1,18 +1,28 @@
-use super::ActorBuilder;
+use super::{ActorBuilder, CyclesCheck};
+use orm_schema::node::CanisterBuild;
+use proc_macro2::TokenStream;
 use quote::quote;
 
 // extend
 pub fn extend(builder: &mut ActorBuilder) {
-    let q = quote! {
-        pub fn init_timers() -> Result<(), ::mimic::Error> {
-
-            //
-            // check_cycles
-            //
-            //::ic::println!("init_timers: NO TIMERS RIGHT NOW");
-            //let secs = ::std::time::Duration::from_secs(600);
+    // root has nobody to request cycles from, so only children check
+    let checks_cycles = matches!(
+        builder.canister.build,
+        CanisterBuild::Basic(_) | CanisterBuild::User
+    );
 
+    let timers = match builder.cycles_check {
+        CyclesCheck::Timer if checks_cycles => check_cycles(builder),
+        CyclesCheck::Heartbeat if checks_cycles => {
+            heartbeat(builder);
+            quote!()
+        }
+        _ => quote!(),
+    };
 
+    let q = quote! {
+        pub fn init_timers() -> Result<(), ::mimic::Error> {
+            #timers
 
             Ok(())
         }
@@ -22,3 +32,64 @@ pub fn extend(builder: &mut ActorBuilder) {
     builder.add_hook("actorgen::init_timers");
     builder.extend_module(q);
 }
+
+// check_cycles
+fn check_cycles(builder: &ActorBuilder) -> TokenStream {
+    let secs = builder.canister.cycles_check_secs();
+
+    quote! {
+        //
+        // check_cycles
+        // the guard stops a second call from scheduling another timer
+        //
+
+        thread_local! {
+            static CHECK_CYCLES: ::std::cell::Cell<bool> = const { ::std::cell::Cell::new(false) };
+        }
+
+        if !CHECK_CYCLES.replace(true) {
+            ::mimic::ic::timers::set_timer_interval(::std::time::Duration::from_secs(#secs), || {
+                ::mimic::ic::spawn(async {
+                    if let Err(e) = ::mimic::api::request::request_cycles(None).await {
+                        log!(Log::Warn, "check_cycles: {e}");
+                    }
+                });
+            });
+        }
+    }
+}
+
+// heartbeat
+// requests cycles on every nth heartbeat, the counter resets on upgrade
+fn heartbeat(builder: &mut ActorBuilder) {
+    let heartbeats = builder.heartbeats;
+
+    builder.extend_actor(quote! {
+        // heartbeat
+        #[::mimic::ic::heartbeat]
+        fn heartbeat() {
+            actorgen::heartbeat_check_cycles();
+        }
+    });
+
+    builder.extend_module(quote! {
+        pub fn heartbeat_check_cycles() {
+            thread_local! {
+                static HEARTBEATS: ::std::cell::Cell<u64> = const { ::std::cell::Cell::new(0) };
+            }
+
+            let count = HEARTBEATS.get() + 1;
+            if count < #heartbeats {
+                HEARTBEATS.set(count);
+                return;
+            }
+            HEARTBEATS.set(0);
+
+            ::mimic::ic::spawn(async {
+                if let Err(e) = ::mimic::api::request::request_cycles(None).await {
+                    log!(Log::Warn, "check_cycles: {e}");
+                }
+            });
+        }
+    });
+}
@@ -0,0 +1,636 @@
+use crate::actor::ActorBuilder;
+use ::candid::{
+    pretty::candid::compile,
+    types::{internal::TypeContainer, Type as IdlType},
+};
+use clap::Parser;
+use orm_schema::{
+    build::schema,
+    node::{
+        Canister, Entity, Enum, EnumHash, FieldList, Item, Map, Newtype, Primitive, Record, Schema,
+        Tuple, Value,
+    },
+    types::{Cardinality, PrimitiveType},
+};
+use proc_macro2::TokenStream;
+use quote::ToTokens;
+use std::{collections::BTreeMap, process};
+use syn::{
+    ext::IdentExt, FnArg, GenericArgument, Item as SynItem, PathArguments, ReturnType, Type,
+};
+
+// KEYWORDS
+// candid reserved words, a label that's one of these has to be quoted
+const KEYWORDS: &[&str] = &[
+    "blob",
+    "bool",
+    "composite_query",
+    "empty",
+    "float32",
+    "float64",
+    "func",
+    "import",
+    "int",
+    "int8",
+    "int16",
+    "int32",
+    "int64",
+    "nat",
+    "nat8",
+    "nat16",
+    "nat32",
+    "nat64",
+    "null",
+    "oneway",
+    "opt",
+    "principal",
+    "query",
+    "record",
+    "reserved",
+    "service",
+    "text",
+    "type",
+    "variant",
+    "vec",
+];
+
+///
+/// Command
+///
+
+#[derive(Parser)]
+pub struct Command {
+    #[clap(help = "Name of the canister to generate the candid types for")]
+    canister_name: String,
+}
+
+///
+/// External
+///
+/// the candid of the rust types the generated actor methods name, taken from
+/// their CandidType impls and keyed by the rust ident, the container gives
+/// types that share a name a suffix (Error_1...) so the ident maps to
+/// whichever name its type ended up with
+///
+
+#[derive(Default)]
+pub struct External {
+    container: TypeContainer,
+    types: BTreeMap<String, IdlType>,
+}
+
+impl External {
+    // new
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // add
+    // ident is what the actor code calls the type, which for an alias isn't
+    // the name of the type it resolves to
+    pub fn add<T: ::candid::CandidType>(&mut self, ident: &str) -> &mut Self {
+        let ty = self.container.add::<T>();
+        self.types.insert(ident.to_string(), ty);
+
+        self
+    }
+}
+
+// process
+// external holds the candid of the mimic types the actor methods use
+pub fn process(command: Command, external: &External) {
+    let schema = schema();
+    let mut canisters =
+        schema.filter_nodes::<Canister, _>(|node| node.name() == command.canister_name);
+    let Some((_, canister)) = canisters.next() else {
+        eprintln!(
+            "Canister '{}' not found in the schema",
+            command.canister_name
+        );
+        process::exit(1);
+    };
+
+    // every entity in the canister, and whatever they reference, then the
+    // methods of the actor the canister is generated with
+    let entities = ActorBuilder::new(canister.clone()).get_entities();
+    let actor = ActorBuilder::new(canister.clone()).expand();
+    let mut builder = CandidBuilder::new(&schema, external);
+    let res = entities
+        .iter()
+        .try_for_each(|(path, _)| builder.add_type(path).map(|_| ()))
+        .and_then(|()| builder.add_service(actor))
+        .and_then(|()| builder.expand());
+    match res {
+        Ok(out) => print!("{out}"),
+        Err(e) => {
+            eprintln!("{e}");
+            process::exit(1);
+        }
+    }
+}
+
+///
+/// CandidBuilder
+///
+/// walks the schema from a set of type paths and renders each node the way
+/// its generated rust type derives CandidType, so newtypes become the candid
+/// type they wrap and entities and records become records
+///
+/// the service block comes from the generated actor, the crud endpoints only
+/// carry entities as bytes so the schema types are what describe them, and
+/// the mimic types the methods use (Error, LoadRequest...) are defined from
+/// their CandidType impls, passed in as an External
+///
+
+pub struct CandidBuilder<'a> {
+    schema: &'a Schema,
+    external: &'a External,
+    names: BTreeMap<String, String>,
+    types: BTreeMap<String, String>,
+    uses_external: bool,
+    service: Option<String>,
+}
+
+impl<'a> CandidBuilder<'a> {
+    // new
+    #[must_use]
+    pub const fn new(schema: &'a Schema, external: &'a External) -> Self {
+        Self {
+            schema,
+            external,
+            names: BTreeMap::new(),
+            types: BTreeMap::new(),
+            uses_external: false,
+            service: None,
+        }
+    }
+
+    // add_type
+    // returns the candid name for the path, adding a definition the first
+    // time a path is seen, primitives are inlined so they have no name
+    pub fn add_type(&mut self, path: &str) -> Result<String, String> {
+        if let Some(primitive) = self.schema.get_node::<Primitive>(path) {
+            return Ok(primitive_type(primitive.ty).to_string());
+        }
+        if let Some(name) = self.names.get(path) {
+            return Ok(name.clone());
+        }
+
+        // the name goes in before the definition so recursive types resolve
+        let name = path.rsplit("::").next().unwrap_or(path).to_string();
+        if let Some((other, _)) = self.names.iter().find(|(_, n)| **n == name) {
+            return Err(format!(
+                "candid type name '{name}' is used by both {other} and {path}"
+            ));
+        }
+        self.names.insert(path.to_string(), name.clone());
+
+        let schema = self.schema;
+        let def = if let Some(node) = schema.get_node::<Entity>(path) {
+            self.fields(&node.fields)?
+        } else if let Some(node) = schema.get_node::<Record>(path) {
+            self.fields(&node.fields)?
+        } else if let Some(node) = schema.get_node::<Newtype>(path) {
+            self.value(&node.value)?
+        } else if let Some(node) = schema.get_node::<Enum>(path) {
+            let mut variants = Vec::new();
+            for variant in &node.variants {
+                variants.push(match &variant.value {
+                    Some(value) => format!("{} : {}", label(&variant.name), self.value(value)?),
+                    None => label(&variant.name),
+                });
+            }
+            braces("variant", &variants)
+        } else if let Some(node) = schema.get_node::<EnumHash>(path) {
+            let keys: Vec<String> = node.keys.iter().map(|key| label(key)).collect();
+            braces("variant", &keys)
+        } else if let Some(node) = schema.get_node::<Map>(path) {
+            let key = self.item(&node.key)?;
+            let value = self.value(&node.value)?;
+            format!("vec record {{ {key}; {value} }}")
+        } else if let Some(node) = schema.get_node::<Tuple>(path) {
+            let mut values = Vec::new();
+            for value in &node.values {
+                values.push(self.value(value)?);
+            }
+            braces("record", &values)
+        } else {
+            return Err(format!("no candid type for schema path: {path}"));
+        };
+
+        self.types.insert(name.clone(), def);
+
+        Ok(name)
+    }
+
+    // add_service
+    // reads the query and update methods out of the generated actor code,
+    // and the init args if it takes any
+    pub fn add_service(&mut self, actor: TokenStream) -> Result<(), String> {
+        let file: syn::File = syn::parse2(actor).map_err(|e| e.to_string())?;
+        let mut init = String::new();
+        let mut methods = Vec::new();
+
+        for item in &file.items {
+            let SynItem::Fn(item) = item else {
+                continue;
+            };
+            let Some(attr) = item.attrs.iter().find_map(|attr| {
+                let ident = &attr.path().segments.last()?.ident;
+                ["init", "query", "update"]
+                    .into_iter()
+                    .find(|kind| ident == kind)
+                    .map(|kind| (kind, attr.to_token_stream().to_string()))
+            }) else {
+                continue;
+            };
+
+            let mut args = Vec::new();
+            for arg in &item.sig.inputs {
+                if let FnArg::Typed(arg) = arg {
+                    args.push(self.rust_type(&arg.ty)?);
+                }
+            }
+            let args = args.join(", ");
+
+            let ret = match &item.sig.output {
+                ReturnType::Type(_, ty) if !is_unit(ty) => self.rust_type(ty)?,
+                _ => String::new(),
+            };
+            let name = label(&item.sig.ident.unraw().to_string());
+
+            match attr {
+                ("init", _) => init = args,
+                ("query", tokens) if tokens.contains("composite") => {
+                    methods.push(format!("{name} : ({args}) -> ({ret}) composite_query"));
+                }
+                ("query", _) => methods.push(format!("{name} : ({args}) -> ({ret}) query")),
+                _ => methods.push(format!("{name} : ({args}) -> ({ret})")),
+            }
+        }
+
+        let init = if init.is_empty() {
+            String::new()
+        } else {
+            format!("({init}) -> ")
+        };
+        let methods: String = methods.iter().map(|m| format!("  {m};\n")).collect();
+        self.service = Some(format!("service : {init}{{\n{methods}}}"));
+
+        Ok(())
+    }
+
+    // expand
+    // the external type definitions if a method uses any, then one schema
+    // type per line sorted by name, then the service
+    pub fn expand(&self) -> Result<String, String> {
+        let mut out = String::new();
+        if self.uses_external {
+            let env = &self.external.container.env;
+            if let Some(name) = env.0.keys().find(|name| self.types.contains_key(*name)) {
+                return Err(format!(
+                    "candid type name '{name}' is used by both a rust type and the schema"
+                ));
+            }
+            out.push_str(&compile(env, &None));
+        }
+        for (name, def) in &self.types {
+            out.push_str(&format!("type {name} = {def};\n"));
+        }
+        if let Some(service) = &self.service {
+            out.push_str(&format!("{service};\n"));
+        }
+
+        Ok(out)
+    }
+
+    // fields
+    fn fields(&mut self, list: &FieldList) -> Result<String, String> {
+        let mut fields = Vec::new();
+        for field in &list.fields {
+            fields.push(format!(
+                "{} : {}",
+                label(&field.name),
+                self.value(&field.value)?
+            ));
+        }
+
+        Ok(braces("record", &fields))
+    }
+
+    // value
+    fn value(&mut self, value: &Value) -> Result<String, String> {
+        let item = self.item(&value.item)?;
+
+        Ok(match value.cardinality {
+            Cardinality::One => item,
+            Cardinality::Opt => format!("opt {item}"),
+            Cardinality::Many => format!("vec {item}"),
+        })
+    }
+
+    // item
+    fn item(&mut self, item: &Item) -> Result<String, String> {
+        match item {
+            Item::Is(node) => self.add_type(&node.path),
+            Item::Relation(_) => Ok(primitive_type(PrimitiveType::Ulid).to_string()),
+        }
+    }
+
+    // rust_type
+    // the candid for a type in a method signature, following the CandidType
+    // impls of std and candid, any other named type has to be external or
+    // the file wouldn't type check
+    fn rust_type(&mut self, ty: &Type) -> Result<String, String> {
+        let path = match ty {
+            Type::Path(path) => &path.path,
+            Type::Reference(r) => return self.rust_type(&r.elem),
+            Type::Tuple(tuple) if !tuple.elems.is_empty() => {
+                let mut values = Vec::new();
+                for ty in &tuple.elems {
+                    values.push(self.rust_type(ty)?);
+                }
+                return Ok(braces("record", &values));
+            }
+            _ => {
+                return Err(format!(
+                    "no candid type for rust type: {}",
+                    ty.to_token_stream()
+                ))
+            }
+        };
+        let Some(segment) = path.segments.last() else {
+            return Err("empty rust type path".to_string());
+        };
+
+        let mut args = Vec::new();
+        if let PathArguments::AngleBracketed(generics) = &segment.arguments {
+            for arg in &generics.args {
+                if let GenericArgument::Type(ty) = arg {
+                    args.push(ty);
+                }
+            }
+        }
+
+        let ident = segment.ident.to_string();
+        let candid = match (ident.as_str(), args.as_slice()) {
+            ("bool", []) => "bool".to_string(),
+            ("String" | "str", []) => "text".to_string(),
+            ("Principal", []) => "principal".to_string(),
+            ("f32", []) => "float32".to_string(),
+            ("f64", []) => "float64".to_string(),
+            ("u8", []) => "nat8".to_string(),
+            ("u16", []) => "nat16".to_string(),
+            ("u32", []) => "nat32".to_string(),
+            ("u64", []) => "nat64".to_string(),
+            ("u128", []) => "nat".to_string(),
+            ("i8", []) => "int8".to_string(),
+            ("i16", []) => "int16".to_string(),
+            ("i32", []) => "int32".to_string(),
+            ("i64", []) => "int64".to_string(),
+            ("i128", []) => "int".to_string(),
+            ("Vec", [ty]) if ty.to_token_stream().to_string() == "u8" => "blob".to_string(),
+            ("Vec", [ty]) => format!("vec {}", self.rust_type(ty)?),
+            ("Option", [ty]) => format!("opt {}", self.rust_type(ty)?),
+            ("Result", [ok, err]) => {
+                let ok = if is_unit(ok) {
+                    "null".to_string()
+                } else {
+                    self.rust_type(ok)?
+                };
+                format!("variant {{ Ok : {ok}; Err : {} }}", self.rust_type(err)?)
+            }
+            ("BTreeMap" | "HashMap", [key, value]) => format!(
+                "vec record {{ {}; {} }}",
+                self.rust_type(key)?,
+                self.rust_type(value)?
+            ),
+            (_, []) => {
+                let Some(ty) = self.external.types.get(&ident) else {
+                    return Err(format!("no candid definition for rust type: {ident}"));
+                };
+                self.uses_external = true;
+                ty.to_string()
+            }
+            _ => {
+                return Err(format!(
+                    "no candid type for rust type: {}",
+                    ty.to_token_stream()
+                ))
+            }
+        };
+
+        Ok(candid)
+    }
+}
+
+// label
+// a field, variant or method name, quoted if candid can't take it bare
+fn label(name: &str) -> String {
+    let mut chars = name.chars();
+    let is_ident = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+
+    if is_ident && !KEYWORDS.contains(&name) {
+        name.to_string()
+    } else {
+        format!("\"{name}\"")
+    }
+}
+
+// is_unit
+fn is_unit(ty: &Type) -> bool {
+    matches!(ty, Type::Tuple(tuple) if tuple.elems.is_empty())
+}
+
+// primitive_type
+// follows the CandidType impls in the types crate, Decimal goes over the
+// wire as a float64 and Ulid as text
+const fn primitive_type(ty: PrimitiveType) -> &'static str {
+    match ty {
+        PrimitiveType::Blob => "blob",
+        PrimitiveType::Bool => "bool",
+        PrimitiveType::Decimal | PrimitiveType::F64 => "float64",
+        PrimitiveType::F32 => "float32",
+        PrimitiveType::I8 => "int8",
+        PrimitiveType::I16 => "int16",
+        PrimitiveType::I32 => "int32",
+        PrimitiveType::I64 => "int64",
+        PrimitiveType::I128 => "int",
+        PrimitiveType::Principal => "principal",
+        PrimitiveType::String | PrimitiveType::Ulid => "text",
+        PrimitiveType::Timestamp | PrimitiveType::U64 => "nat64",
+        PrimitiveType::U8 => "nat8",
+        PrimitiveType::U16 => "nat16",
+        PrimitiveType::U32 => "nat32",
+        PrimitiveType::U128 => "nat",
+    }
+}
+
+// braces
+fn braces(keyword: &str, parts: &[String]) -> String {
+    if parts.is_empty() {
+        format!("{keyword} {{}}")
+    } else {
+        format!("{keyword} {{ {} }}", parts.join("; "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::candid::CandidType;
+    use orm_schema::{
+        node::SchemaNode,
+        test::{def, field, is, relation, value},
+    };
+
+    #[test]
+    fn test_candid_types() {
+        let mut schema = Schema::new();
+        schema.add_node(SchemaNode::Primitive(Primitive {
+            def: def("U8"),
+            ty: PrimitiveType::U8,
+            path: "u8".to_string(),
+        }));
+        schema.add_node(SchemaNode::Newtype(Newtype {
+            def: def("Level"),
+            value: value(Cardinality::One, is("test::U8")),
+            primitive: Some(PrimitiveType::U8),
+            guide: None,
+            sanitizers: Vec::new(),
+            validators: Vec::new(),
+        }));
+        schema.add_node(SchemaNode::Record(Record {
+            def: def("Player"),
+            fields: FieldList {
+                fields: vec![
+                    field("level", value(Cardinality::Opt, is("test::Level"))),
+                    field(
+                        "friends",
+                        value(Cardinality::Many, relation("test::Player")),
+                    ),
+                ],
+                order: Vec::new(),
+            },
+        }));
+
+        let external = External::new();
+        let mut builder = CandidBuilder::new(&schema, &external);
+        assert_eq!(builder.add_type("test::Player").unwrap(), "Player");
+        assert_eq!(
+            builder.expand().unwrap(),
+            "type Level = nat8;\ntype Player = record { level : opt Level; friends : vec text };\n"
+        );
+
+        assert!(builder.add_type("test::Missing").is_err());
+    }
+
+    #[test]
+    fn test_candid_keywords() {
+        let mut schema = Schema::new();
+        schema.add_node(SchemaNode::Primitive(Primitive {
+            def: def("Text"),
+            ty: PrimitiveType::String,
+            path: "String".to_string(),
+        }));
+        let text = || value(Cardinality::One, is("test::Text"));
+        schema.add_node(SchemaNode::Record(Record {
+            def: def("Post"),
+            fields: FieldList {
+                fields: vec![
+                    field("type", text()),
+                    field("title", text()),
+                    field("record", text()),
+                ],
+                order: Vec::new(),
+            },
+        }));
+
+        let external = External::new();
+        let mut builder = CandidBuilder::new(&schema, &external);
+        builder.add_type("test::Post").unwrap();
+        assert_eq!(
+            builder.expand().unwrap(),
+            "type Post = record { \"type\" : text; title : text; \"record\" : text };\n"
+        );
+    }
+
+    #[allow(dead_code)]
+    #[derive(CandidType)]
+    enum Error {
+        NotFound(String),
+    }
+
+    mod other {
+        use ::candid::CandidType;
+
+        #[allow(dead_code)]
+        #[derive(CandidType)]
+        pub enum Error {
+            Other,
+        }
+    }
+
+    #[test]
+    fn test_candid_service() {
+        let schema = Schema::new();
+        let actor = quote::quote! {
+            #[::mimic::ic::init]
+            fn init(root_id: Principal, init_arg: Vec<u8>) {}
+
+            #[::mimic::ic::query]
+            fn canister_time() -> u64 {
+                0
+            }
+
+            #[::mimic::ic::query(composite = true)]
+            async fn store_keys(store_name: String) -> Result<Vec<String>, ::mimic::Error> {
+                Ok(Vec::new())
+            }
+
+            #[::mimic::ic::update]
+            async fn set_read_only(read_only: bool) -> Result<(), ::mimic::Error> {
+                Ok(())
+            }
+
+            #[::mimic::ic::update]
+            async fn cycles_distribute() -> Vec<(Principal, Result<(), String>)> {
+                Vec::new()
+            }
+
+            #[::mimic::ic::update]
+            fn query() {}
+
+            fn pre_upgrade() {}
+        };
+
+        // the mimic types have to be passed in
+        let external = External::new();
+        let mut builder = CandidBuilder::new(&schema, &external);
+        assert!(builder.add_service(actor.clone()).is_err());
+
+        // the other Error is added first so this one gets the suffix
+        let mut external = External::new();
+        external
+            .add::<other::Error>("OtherError")
+            .add::<Error>("Error");
+        let mut builder = CandidBuilder::new(&schema, &external);
+        builder.add_service(actor).unwrap();
+        assert_eq!(
+            builder.expand().unwrap(),
+            "type Error = variant { Other };\n\
+             type Error_1 = variant { NotFound : text };\n\
+             service : (principal, blob) -> {\n  \
+             canister_time : () -> (nat64) query;\n  \
+             store_keys : (text) -> (variant { Ok : vec text; Err : Error_1 }) composite_query;\n  \
+             set_read_only : (bool) -> (variant { Ok : null; Err : Error_1 });\n  \
+             cycles_distribute : () -> (vec record { principal; variant { Ok : null; Err : text } });\n  \
+             \"query\" : () -> ();\n\
+             };\n"
+        );
+    }
+}
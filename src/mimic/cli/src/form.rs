@@ -0,0 +1,284 @@
+use crate::actor::ActorBuilder;
+use clap::Parser;
+use orm_schema::{
+    build::schema,
+    node::{Arg, ArgNumber, Canister, Entity, Field, Item, Newtype, Schema},
+    types::{Cardinality, PrimitiveType},
+};
+use serde_json::{json, Map, Value as JsonValue};
+use std::process;
+
+///
+/// Command
+///
+
+#[derive(Parser)]
+pub struct Command {
+    #[clap(help = "Name of the canister to generate the form descriptors for")]
+    canister_name: String,
+}
+
+// process
+pub fn process(command: Command) {
+    let schema = schema();
+    let mut canisters =
+        schema.filter_nodes::<Canister, _>(|node| node.name() == command.canister_name);
+    let Some((_, canister)) = canisters.next() else {
+        eprintln!(
+            "Canister '{}' not found in the schema",
+            command.canister_name
+        );
+        process::exit(1);
+    };
+
+    let entities = ActorBuilder::new(canister.clone()).get_entities();
+    let builder = FormBuilder::new(&schema);
+    let mut output = Map::new();
+    for (path, _) in &entities {
+        match builder.entity(path) {
+            Ok(descriptor) => {
+                output.insert(path.clone(), descriptor);
+            }
+            Err(e) => {
+                eprintln!("{e}");
+                process::exit(1);
+            }
+        }
+    }
+
+    println!("{}", JsonValue::Object(output));
+}
+
+///
+/// FormBuilder
+///
+/// describes each field of an entity so a UI can render and check a form
+/// without knowing the types, the validators, sanitizers and guide come
+/// from the newtypes the field's item wraps, outermost first
+///
+
+pub struct FormBuilder<'a> {
+    schema: &'a Schema,
+}
+
+impl<'a> FormBuilder<'a> {
+    // new
+    #[must_use]
+    pub const fn new(schema: &'a Schema) -> Self {
+        Self { schema }
+    }
+
+    // entity
+    pub fn entity(&self, path: &str) -> Result<JsonValue, String> {
+        let entity = self
+            .schema
+            .get_node::<Entity>(path)
+            .ok_or_else(|| format!("entity not found: {path}"))?;
+
+        let fields: Vec<JsonValue> = entity.fields.fields.iter().map(|f| self.field(f)).collect();
+
+        Ok(json!({ "fields": fields }))
+    }
+
+    // field
+    // empty lists and unknown types are left out to keep the output compact
+    fn field(&self, field: &'a Field) -> JsonValue {
+        let mut out = Map::new();
+        out.insert("name".to_string(), json!(field.name));
+        out.insert("cardinality".to_string(), json!(field.value.cardinality));
+
+        let item_path = match &field.value.item {
+            Item::Is(item) => {
+                out.insert("item".to_string(), json!(item.path));
+                Some(item.path.as_str())
+            }
+            Item::Relation(rel) => {
+                out.insert("relation".to_string(), json!(rel.path));
+                None
+            }
+        };
+
+        let ty = match item_path {
+            Some(path) => self.schema.primitive_type(path),
+            None => Some(PrimitiveType::Ulid),
+        };
+        if let Some(ty) = ty {
+            out.insert("type".to_string(), json!(ty));
+        }
+
+        let mut validators = Vec::new();
+        let mut sanitizers = Vec::new();
+        let mut guide = None;
+        for newtype in self.newtypes(item_path) {
+            validators.extend(
+                newtype
+                    .validators
+                    .iter()
+                    .map(|v| type_json(&v.path, &v.args)),
+            );
+            sanitizers.extend(
+                newtype
+                    .sanitizers
+                    .iter()
+                    .map(|s| type_json(&s.path, &s.args)),
+            );
+            if guide.is_none() {
+                guide = newtype.guide.as_ref();
+            }
+        }
+
+        if !validators.is_empty() {
+            out.insert("validators".to_string(), JsonValue::Array(validators));
+        }
+        if !sanitizers.is_empty() {
+            out.insert("sanitizers".to_string(), JsonValue::Array(sanitizers));
+        }
+        if let Some(guide) = guide {
+            let entries: Vec<JsonValue> = guide
+                .entries
+                .iter()
+                .map(|entry| json!({ "name": entry.name, "value": number_json(&entry.value) }))
+                .collect();
+            out.insert("guide".to_string(), JsonValue::Array(entries));
+        }
+
+        JsonValue::Object(out)
+    }
+
+    // newtypes
+    // the chain of single-value newtypes starting at the path
+    fn newtypes(&self, mut path: Option<&'a str>) -> Vec<&'a Newtype> {
+        let mut chain = Vec::new();
+        while let Some(node) = path.and_then(|path| self.schema.get_node::<Newtype>(path)) {
+            chain.push(node);
+            path = match (&node.value.cardinality, &node.value.item) {
+                (Cardinality::One, Item::Is(item)) => Some(item.path.as_str()),
+                _ => None,
+            };
+        }
+
+        chain
+    }
+}
+
+// type_json
+fn type_json(path: &str, args: &[Arg]) -> JsonValue {
+    if args.is_empty() {
+        json!({ "path": path })
+    } else {
+        let args: Vec<JsonValue> = args.iter().map(arg_json).collect();
+
+        json!({ "path": path, "args": args })
+    }
+}
+
+// arg_json
+// args lose their rust type and become plain json values
+fn arg_json(arg: &Arg) -> JsonValue {
+    match arg {
+        Arg::Bool(b) => json!(b),
+        Arg::Char(c) => json!(c.to_string()),
+        Arg::Expr(s) | Arg::Path(s) | Arg::String(s) => json!(s),
+        Arg::Number(n) => number_json(n),
+    }
+}
+
+// number_json
+// 128 bit integers that don't fit json numbers are written as strings
+fn number_json(n: &ArgNumber) -> JsonValue {
+    match *n {
+        ArgNumber::F32(n) => json!(n),
+        ArgNumber::F64(n) => json!(n),
+        ArgNumber::I8(n) => json!(n),
+        ArgNumber::I16(n) => json!(n),
+        ArgNumber::I32(n) => json!(n),
+        ArgNumber::I64(n) => json!(n),
+        ArgNumber::Isize(n) => json!(n),
+        ArgNumber::U8(n) => json!(n),
+        ArgNumber::U16(n) => json!(n),
+        ArgNumber::U32(n) => json!(n),
+        ArgNumber::U64(n) => json!(n),
+        ArgNumber::Usize(n) => json!(n),
+        ArgNumber::I128(n) => i64::try_from(n).map_or_else(|_| json!(n.to_string()), |n| json!(n)),
+        ArgNumber::U128(n) => u64::try_from(n).map_or_else(|_| json!(n.to_string()), |n| json!(n)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use orm_schema::{
+        node::{Args, FieldList, Guide, GuideEntry, Primitive, SchemaNode, TypeValidator},
+        test::{def, field, is, relation, value},
+        types::KeyStrategy,
+    };
+
+    #[test]
+    fn test_form_descriptor() {
+        let mut schema = Schema::new();
+        schema.add_node(SchemaNode::Primitive(Primitive {
+            def: def("U8"),
+            ty: PrimitiveType::U8,
+            path: "u8".to_string(),
+        }));
+        schema.add_node(SchemaNode::Newtype(Newtype {
+            def: def("Level"),
+            value: value(Cardinality::One, is("test::U8")),
+            primitive: Some(PrimitiveType::U8),
+            guide: Some(Guide {
+                entries: vec![GuideEntry {
+                    name: Some("max".to_string()),
+                    value: ArgNumber::U8(10),
+                }],
+            }),
+            sanitizers: Vec::new(),
+            validators: vec![TypeValidator {
+                path: "validator::number::Range".to_string(),
+                args: Args(vec![
+                    Arg::Number(ArgNumber::U8(1)),
+                    Arg::Number(ArgNumber::U8(10)),
+                ]),
+            }],
+        }));
+        schema.add_node(SchemaNode::Entity(Entity {
+            def: def("Player"),
+            store: "test::Store".to_string(),
+            sort_keys: Vec::new(),
+            primary_keys: vec!["guild".to_string()],
+            key_strategy: KeyStrategy::default(),
+            indexes: Vec::new(),
+            sources: Vec::new(),
+            crud: None,
+            fields: FieldList {
+                fields: vec![
+                    field("level", value(Cardinality::Opt, is("test::Level"))),
+                    field("guild", value(Cardinality::One, relation("test::Guild"))),
+                ],
+                order: Vec::new(),
+            },
+        }));
+
+        let builder = FormBuilder::new(&schema);
+        assert_eq!(
+            builder.entity("test::Player").unwrap(),
+            json!({ "fields": [
+                {
+                    "name": "level",
+                    "cardinality": "Opt",
+                    "item": "test::Level",
+                    "type": "U8",
+                    "validators": [{ "path": "validator::number::Range", "args": [1, 10] }],
+                    "guide": [{ "name": "max", "value": 10 }],
+                },
+                {
+                    "name": "guild",
+                    "cardinality": "One",
+                    "relation": "test::Guild",
+                    "type": "Ulid",
+                },
+            ]})
+        );
+
+        assert!(builder.entity("test::Missing").is_err());
+    }
+}
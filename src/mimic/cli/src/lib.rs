@@ -1,5 +1,9 @@
 pub mod actor;
+pub mod candid;
+pub mod form;
 pub mod schema;
+pub mod typescript;
+pub mod validate;
 
 use clap::{Parser, Subcommand};
 
@@ -26,16 +30,41 @@ enum Command {
     #[clap(name = "actor", about = "generate actor rust code")]
     Actor(actor::Command),
 
+    #[clap(name = "candid", about = "generate candid types from the schema")]
+    Candid(candid::Command),
+
+    #[clap(name = "form", about = "generate entity form descriptors as JSON")]
+    Form(form::Command),
+
     #[clap(name = "schema", about = "generate the schema JSON")]
-    Schema,
+    Schema(schema::Command),
+
+    #[clap(
+        name = "typescript",
+        about = "generate typescript types from the schema"
+    )]
+    TypeScript(typescript::Command),
+
+    #[clap(name = "validate", about = "validate a schema JSON file")]
+    Validate(validate::Command),
 }
 
 // run
+// without the candid of the mimic types, so the candid command fails if the
+// actor uses any of them
 pub fn run() {
+    run_with(&candid::External::new());
+}
+
+// run_with
+// types holds the candid definitions of the mimic types the generated actor uses
+pub fn run_with(types: &candid::External) {
     let cli = Cli::parse();
 
     // VALIDATE SCHEMA
-    if !cli.skip_validation {
+    // the validate command checks its own schema file instead
+    let validate_file = matches!(cli.command, Command::Validate(_));
+    if !cli.skip_validation && !validate_file {
         if let Err(e) = orm_schema::build::validate() {
             eprintln!("{e}");
             std::process::exit(2);
@@ -45,6 +74,10 @@ pub fn run() {
     // ROUTE COMMAND
     match cli.command {
         Command::Actor(args) => actor::process(args),
-        Command::Schema => schema::process(),
+        Command::Candid(args) => candid::process(args, types),
+        Command::Form(args) => form::process(args),
+        Command::Schema(args) => schema::process(args),
+        Command::TypeScript(args) => typescript::process(args),
+        Command::Validate(args) => validate::process(args),
     }
 }
@@ -1,8 +1,48 @@
+use clap::Parser;
 use orm_schema::build::schema;
+use std::io::{stdout, IsTerminal};
+
+///
+/// Command
+///
+
+#[derive(Parser)]
+pub struct Command {
+    #[clap(
+        long,
+        action,
+        conflicts_with = "minify",
+        help = "Pretty-print the JSON output"
+    )]
+    pretty: bool,
+
+    #[clap(long, action, help = "Minify the JSON output")]
+    minify: bool,
+}
+
+impl Command {
+    // pretty
+    // explicit flags win, otherwise pretty when writing to a terminal
+    fn pretty(&self) -> bool {
+        if self.pretty {
+            true
+        } else if self.minify {
+            false
+        } else {
+            stdout().is_terminal()
+        }
+    }
+}
 
 // process
-pub fn process() {
-    let output = serde_json::to_string(&*schema()).unwrap();
+pub fn process(command: Command) {
+    let schema = schema();
+    let output = if command.pretty() {
+        serde_json::to_string_pretty(&*schema)
+    } else {
+        serde_json::to_string(&*schema)
+    }
+    .unwrap();
 
     println!("{output}");
 }
@@ -0,0 +1,277 @@
+use crate::actor::ActorBuilder;
+use clap::Parser;
+use orm_schema::{
+    build::schema,
+    node::{
+        Canister, Entity, Enum, EnumHash, FieldList, Item, Map, Newtype, Primitive, Record, Schema,
+        Tuple, Value,
+    },
+    types::{Cardinality, PrimitiveType},
+};
+use std::{collections::BTreeMap, process};
+
+///
+/// Command
+///
+
+#[derive(Parser)]
+pub struct Command {
+    #[clap(help = "Name of the canister to generate the typescript types for")]
+    canister_name: String,
+}
+
+// process
+pub fn process(command: Command) {
+    let schema = schema();
+    let mut canisters =
+        schema.filter_nodes::<Canister, _>(|node| node.name() == command.canister_name);
+    let Some((_, canister)) = canisters.next() else {
+        eprintln!(
+            "Canister '{}' not found in the schema",
+            command.canister_name
+        );
+        process::exit(1);
+    };
+
+    // every entity in the canister, and whatever they reference
+    let entities = ActorBuilder::new(canister.clone()).get_entities();
+    let mut builder = TypeScriptBuilder::new(&schema);
+    for (path, _) in &entities {
+        if let Err(e) = builder.add_type(path) {
+            eprintln!("{e}");
+            process::exit(1);
+        }
+    }
+
+    print!("{}", builder.expand());
+}
+
+///
+/// TypeScriptBuilder
+///
+/// the typescript counterpart of CandidBuilder, it describes the JSON the
+/// ORM produces so entities and records become interfaces, enums become
+/// unions of their serde representation and newtypes alias what they wrap
+///
+
+pub struct TypeScriptBuilder<'a> {
+    schema: &'a Schema,
+    names: BTreeMap<String, String>,
+    types: BTreeMap<String, String>,
+}
+
+impl<'a> TypeScriptBuilder<'a> {
+    // new
+    #[must_use]
+    pub const fn new(schema: &'a Schema) -> Self {
+        Self {
+            schema,
+            names: BTreeMap::new(),
+            types: BTreeMap::new(),
+        }
+    }
+
+    // add_type
+    // returns the typescript name for the path, adding a declaration the
+    // first time a path is seen, primitives are inlined so they have no name
+    pub fn add_type(&mut self, path: &str) -> Result<String, String> {
+        if let Some(primitive) = self.schema.get_node::<Primitive>(path) {
+            return Ok(primitive_type(primitive.ty).to_string());
+        }
+        if let Some(name) = self.names.get(path) {
+            return Ok(name.clone());
+        }
+
+        // the name goes in before the declaration so recursive types resolve
+        let name = path.rsplit("::").next().unwrap_or(path).to_string();
+        if let Some((other, _)) = self.names.iter().find(|(_, n)| **n == name) {
+            return Err(format!(
+                "typescript type name '{name}' is used by both {other} and {path}"
+            ));
+        }
+        self.names.insert(path.to_string(), name.clone());
+
+        let schema = self.schema;
+        let decl = if let Some(node) = schema.get_node::<Entity>(path) {
+            format!("export interface {name} {}", self.fields(&node.fields)?)
+        } else if let Some(node) = schema.get_node::<Record>(path) {
+            format!("export interface {name} {}", self.fields(&node.fields)?)
+        } else if let Some(node) = schema.get_node::<Newtype>(path) {
+            format!("export type {name} = {};", self.value(&node.value)?)
+        } else if let Some(node) = schema.get_node::<Enum>(path) {
+            let mut variants = Vec::new();
+            for variant in &node.variants {
+                variants.push(match &variant.value {
+                    Some(value) => format!("{{ {}: {} }}", variant.name, self.value(value)?),
+                    None => format!("\"{}\"", variant.name),
+                });
+            }
+            format!("export type {name} = {};", union(&variants))
+        } else if let Some(node) = schema.get_node::<EnumHash>(path) {
+            let keys: Vec<String> = node.keys.iter().map(|key| format!("\"{key}\"")).collect();
+            format!("export type {name} = {};", union(&keys))
+        } else if let Some(node) = schema.get_node::<Map>(path) {
+            let key = self.item(&node.key)?;
+            let value = self.value(&node.value)?;
+            format!("export type {name} = Array<[{key}, {value}]>;")
+        } else if let Some(node) = schema.get_node::<Tuple>(path) {
+            let mut values = Vec::new();
+            for value in &node.values {
+                values.push(self.value(value)?);
+            }
+            format!("export type {name} = [{}];", values.join(", "))
+        } else {
+            return Err(format!("no typescript type for schema path: {path}"));
+        };
+
+        self.types.insert(name.clone(), decl);
+
+        Ok(name)
+    }
+
+    // expand
+    // one declaration per line, sorted by name
+    #[must_use]
+    pub fn expand(&self) -> String {
+        self.types
+            .values()
+            .map(|decl| format!("{decl}\n"))
+            .collect()
+    }
+
+    // fields
+    fn fields(&mut self, list: &FieldList) -> Result<String, String> {
+        let mut fields = Vec::new();
+        for field in &list.fields {
+            fields.push(format!("{}: {};", field.name, self.value(&field.value)?));
+        }
+
+        if fields.is_empty() {
+            Ok("{}".to_string())
+        } else {
+            Ok(format!("{{ {} }}", fields.join(" ")))
+        }
+    }
+
+    // value
+    fn value(&mut self, value: &Value) -> Result<String, String> {
+        let item = self.item(&value.item)?;
+
+        Ok(match value.cardinality {
+            Cardinality::One => item,
+            Cardinality::Opt => format!("{item} | null"),
+            Cardinality::Many => format!("Array<{item}>"),
+        })
+    }
+
+    // item
+    fn item(&mut self, item: &Item) -> Result<String, String> {
+        match item {
+            Item::Is(node) => self.add_type(&node.path),
+            Item::Relation(_) => Ok(primitive_type(PrimitiveType::Ulid).to_string()),
+        }
+    }
+}
+
+// primitive_type
+// follows the json of entity_to_json, Blob is base64, Timestamp is
+// RFC3339 and Decimal keeps its precision as a string,
+// JSON.parse reads every integer as a number, so 64 and 128 bit values
+// past 2^53 lose precision on the way in
+const fn primitive_type(ty: PrimitiveType) -> &'static str {
+    match ty {
+        PrimitiveType::Bool => "boolean",
+        PrimitiveType::Blob
+        | PrimitiveType::Decimal
+        | PrimitiveType::Principal
+        | PrimitiveType::String
+        | PrimitiveType::Timestamp
+        | PrimitiveType::Ulid => "string",
+        PrimitiveType::F32
+        | PrimitiveType::F64
+        | PrimitiveType::I8
+        | PrimitiveType::I16
+        | PrimitiveType::I32
+        | PrimitiveType::I64
+        | PrimitiveType::I128
+        | PrimitiveType::U8
+        | PrimitiveType::U16
+        | PrimitiveType::U32
+        | PrimitiveType::U64
+        | PrimitiveType::U128 => "number",
+    }
+}
+
+// union
+fn union(parts: &[String]) -> String {
+    if parts.is_empty() {
+        "never".to_string()
+    } else {
+        parts.join(" | ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use orm_schema::{
+        node::SchemaNode,
+        test::{def, field, is, relation, value, variant},
+    };
+
+    #[test]
+    fn test_typescript_types() {
+        let mut schema = Schema::new();
+        schema.add_node(SchemaNode::Primitive(Primitive {
+            def: def("U8"),
+            ty: PrimitiveType::U8,
+            path: "u8".to_string(),
+        }));
+        schema.add_node(SchemaNode::Primitive(Primitive {
+            def: def("U64"),
+            ty: PrimitiveType::U64,
+            path: "u64".to_string(),
+        }));
+        schema.add_node(SchemaNode::Newtype(Newtype {
+            def: def("Level"),
+            value: value(Cardinality::One, is("test::U8")),
+            primitive: Some(PrimitiveType::U8),
+            guide: None,
+            sanitizers: Vec::new(),
+            validators: Vec::new(),
+        }));
+        schema.add_node(SchemaNode::Enum(Enum {
+            def: def("Rank"),
+            variants: vec![
+                variant("Low", None),
+                variant("Custom", Some(value(Cardinality::One, is("test::Level")))),
+            ],
+        }));
+        schema.add_node(SchemaNode::Record(Record {
+            def: def("Player"),
+            fields: FieldList {
+                fields: vec![
+                    field("level", value(Cardinality::Opt, is("test::Level"))),
+                    field("rank", value(Cardinality::One, is("test::Rank"))),
+                    field("score", value(Cardinality::One, is("test::U64"))),
+                    field(
+                        "friends",
+                        value(Cardinality::Many, relation("test::Player")),
+                    ),
+                ],
+                order: Vec::new(),
+            },
+        }));
+
+        let mut builder = TypeScriptBuilder::new(&schema);
+        assert_eq!(builder.add_type("test::Player").unwrap(), "Player");
+        assert_eq!(
+            builder.expand(),
+            "export type Level = number;\n\
+             export interface Player { level: Level | null; rank: Rank; score: number; friends: Array<string>; }\n\
+             export type Rank = \"Low\" | { Custom: Level };\n"
+        );
+
+        assert!(builder.add_type("test::Missing").is_err());
+    }
+}
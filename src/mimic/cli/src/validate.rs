@@ -0,0 +1,39 @@
+use clap::Parser;
+use orm_schema::build::{load_json, validate, Error};
+use std::{fs, path::PathBuf, process};
+
+///
+/// Command
+///
+
+#[derive(Parser)]
+pub struct Command {
+    #[clap(help = "Path to the schema JSON file")]
+    path: PathBuf,
+}
+
+// process
+pub fn process(command: Command) {
+    let json = fs::read_to_string(&command.path).unwrap_or_else(|e| {
+        eprintln!("unable to read '{}': {e}", command.path.display());
+        process::exit(1);
+    });
+
+    if let Err(e) = load_json(&json) {
+        eprintln!("{e}");
+        process::exit(1);
+    }
+
+    // print each error with its node path
+    match validate() {
+        Ok(()) => println!("schema is valid"),
+        Err(Error::Validation { errors }) => {
+            eprint!("{errors}");
+            process::exit(2);
+        }
+        Err(e) => {
+            eprintln!("{e}");
+            process::exit(2);
+        }
+    }
+}
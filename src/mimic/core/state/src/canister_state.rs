@@ -1,4 +1,4 @@
-use super::{Error, CANISTER_STATE};
+use super::{Error, CANISTER_STATE, INIT_ARG};
 use candid::{CandidType, Principal};
 use derive_more::{Deref, DerefMut};
 use ic::structures::{memory::VirtualMemory, Cell};
@@ -89,6 +89,39 @@ impl CanisterStateManager {
 
         Self::set(state)
     }
+
+    // get_init_arg
+    // the candid-encoded argument the canister was created with, empty if
+    // it was given none, it has its own cell so reading the state doesn't
+    // decode it
+    #[must_use]
+    pub fn get_init_arg() -> Vec<u8> {
+        INIT_ARG.with_borrow(|cell| cell.get())
+    }
+
+    // set_init_arg
+    pub fn set_init_arg(init_arg: Vec<u8>) -> Result<(), Error> {
+        INIT_ARG
+            .with_borrow_mut(|cell| cell.set(init_arg))
+            .map_err(CanisterStateError::from)?;
+
+        Ok(())
+    }
+
+    // is_read_only
+    #[must_use]
+    pub fn is_read_only() -> bool {
+        Self::get().read_only
+    }
+
+    // set_read_only
+    // while set every save and delete is rejected, reads carry on as normal
+    pub fn set_read_only(read_only: bool) -> Result<(), Error> {
+        let mut state = Self::get();
+        state.read_only = read_only;
+
+        Self::set(state)
+    }
 }
 
 ///
@@ -105,6 +138,20 @@ impl CanisterStateStable {
     }
 }
 
+///
+/// InitArgStable
+///
+
+#[derive(Deref, DerefMut)]
+pub(crate) struct InitArgStable(Cell<Vec<u8>>);
+
+impl InitArgStable {
+    #[must_use]
+    pub fn init(memory: VirtualMemory) -> Self {
+        Self(Cell::init(memory, Vec::new()).unwrap())
+    }
+}
+
 ///
 /// CanisterState
 ///
@@ -114,4 +161,8 @@ pub struct CanisterState {
     path: Option<String>,
     root_id: Option<Principal>,
     parent_id: Option<Principal>,
+
+    // default so state written before the flag existed still loads
+    #[serde(default)]
+    read_only: bool,
 }
@@ -1,7 +1,8 @@
-use super::{Error, CHILD_INDEX};
+use super::{Error, CHILD_INDEX, CHILD_PATHS};
 use candid::{CandidType, Principal};
 use derive_more::{Deref, DerefMut};
 use ic::structures::{memory::VirtualMemory, BTreeMap};
+use mimic_derive::Storable;
 use serde::{Deserialize, Serialize};
 use snafu::Snafu;
 
@@ -29,10 +30,54 @@ impl ChildIndexManager {
     }
 
     // add_canister
+    // a canister added again under another path moves to that path
     pub fn add_canister(id: Principal, path: &str) {
-        let path = path.to_string();
+        Self::ensure_paths();
 
-        CHILD_INDEX.with_borrow_mut(|index| index.insert(id, path));
+        if let Some(old_path) =
+            CHILD_INDEX.with_borrow_mut(|index| index.insert(id, path.to_string()))
+        {
+            remove_path(&old_path, id);
+        }
+        add_path(path, id);
+    }
+
+    // remove_canister
+    // returns the path the canister was registered under
+    #[must_use]
+    pub fn remove_canister(id: Principal) -> Option<String> {
+        Self::ensure_paths();
+
+        let path = CHILD_INDEX.with_borrow_mut(|index| index.remove(&id))?;
+        remove_path(&path, id);
+
+        Some(path)
+    }
+
+    // by_path
+    // every child created from the path, in Principal order
+    #[must_use]
+    pub fn by_path(path: &str) -> Vec<Principal> {
+        Self::ensure_paths();
+
+        CHILD_PATHS
+            .with_borrow(|paths| paths.get(&path.to_string()))
+            .map(|children| children.0)
+            .unwrap_or_default()
+    }
+
+    // ensure_paths
+    // children added before the path index existed are only in CHILD_INDEX,
+    // so it's built from there the first time it's needed
+    fn ensure_paths() {
+        let missing = CHILD_PATHS.with_borrow(|paths| paths.is_empty())
+            && CHILD_INDEX.with_borrow(|index| !index.is_empty());
+
+        if missing {
+            for (id, path) in Self::get() {
+                add_path(&path, id);
+            }
+        }
     }
 
     // get_canister
@@ -49,6 +94,34 @@ impl ChildIndexManager {
     }
 }
 
+// add_path
+fn add_path(path: &str, id: Principal) {
+    CHILD_PATHS.with_borrow_mut(|paths| {
+        let mut children = paths.get(&path.to_string()).unwrap_or_default();
+        if let Err(i) = children.0.binary_search(&id) {
+            children.0.insert(i, id);
+            paths.insert(path.to_string(), children);
+        }
+    });
+}
+
+// remove_path
+// the entry goes once the last child of the path is removed
+fn remove_path(path: &str, id: Principal) {
+    CHILD_PATHS.with_borrow_mut(|paths| {
+        let Some(mut children) = paths.get(&path.to_string()) else {
+            return;
+        };
+        children.0.retain(|child| *child != id);
+
+        if children.0.is_empty() {
+            paths.remove(&path.to_string());
+        } else {
+            paths.insert(path.to_string(), children);
+        }
+    });
+}
+
 ///
 /// ChildIndex
 /// a map of Child Principal to Canister
@@ -74,3 +147,31 @@ impl ChildIndexStable {
         }
     }
 }
+
+///
+/// ChildPaths
+/// the children of one path, kept sorted
+///
+
+#[derive(CandidType, Clone, Debug, Default, Serialize, Deserialize, Storable)]
+pub struct ChildPaths(Vec<Principal>);
+
+///
+/// ChildPathsStable
+/// the reverse of ChildIndexStable, path to its children
+///
+
+#[derive(Deref, DerefMut)]
+pub struct ChildPathsStable {
+    state: BTreeMap<String, ChildPaths>,
+}
+
+impl ChildPathsStable {
+    // init
+    #[must_use]
+    pub fn init(memory: VirtualMemory) -> Self {
+        Self {
+            state: BTreeMap::init(memory),
+        }
+    }
+}
@@ -1,6 +1,8 @@
 pub mod app_state;
 pub mod canister_state;
 pub mod child_index;
+pub mod parent_index;
+pub mod request_cost;
 pub mod subnet_index;
 pub mod user_index;
 
@@ -8,22 +10,26 @@ pub use {
     app_state::{AppCommand, AppMode, AppState, AppStateManager},
     canister_state::{CanisterState, CanisterStateManager},
     child_index::{ChildIndex, ChildIndexManager},
+    parent_index::ParentIndexManager,
+    request_cost::{RequestCost, RequestCostManager, RequestCosts},
     subnet_index::{SubnetIndex, SubnetIndexManager},
     user_index::{User, UserIndex, UserIndexManager},
 };
 
 use candid::CandidType;
 use ic::structures::{
-    memory::{MemoryId, MemoryManager},
+    memory::{get_memory, register, MemoryError, MemoryManager, MemoryUsage},
     DefaultMemoryImpl,
 };
 use serde::{Deserialize, Serialize};
 use snafu::Snafu;
-use std::cell::RefCell;
+use std::{cell::RefCell, collections::BTreeMap};
 use {
     app_state::{AppStateError, AppStateStable},
-    canister_state::{CanisterStateError, CanisterStateStable},
-    child_index::{ChildIndexError, ChildIndexStable},
+    canister_state::{CanisterStateError, CanisterStateStable, InitArgStable},
+    child_index::{ChildIndexError, ChildIndexStable, ChildPathsStable},
+    parent_index::ParentIndexStable,
+    request_cost::RequestCostStable,
     subnet_index::{SubnetIndexError, SubnetIndexStable},
     user_index::{UserIndexError, UserIndexStable},
 };
@@ -64,10 +70,14 @@ const APP_STATE_MEMORY_ID: u8 = 1;
 // subnet
 const SUBNET_INDEX_MEMORY_ID: u8 = 2;
 const USER_INDEX_MEMORY_ID: u8 = 3;
+const REQUEST_COST_MEMORY_ID: u8 = 6;
+const PARENT_INDEX_MEMORY_ID: u8 = 8;
 
 // canister
 const CANISTER_STATE_MEMORY_ID: u8 = 4;
 const CHILD_INDEX_MEMORY_ID: u8 = 5;
+const CHILD_PATHS_MEMORY_ID: u8 = 7;
+const INIT_ARG_MEMORY_ID: u8 = 9;
 
 thread_local! {
 
@@ -89,7 +99,7 @@ thread_local! {
     ///
 
     pub(crate) static APP_STATE: RefCell<AppStateStable> = RefCell::new(AppStateStable::init(
-        MEMORY_MANAGER.with_borrow(|mm| mm.get(MemoryId::new(APP_STATE_MEMORY_ID))),
+        MEMORY_MANAGER.with_borrow(|mm| get_memory(mm, APP_STATE_MEMORY_ID, "APP_STATE")),
     ));
 
     ///
@@ -100,7 +110,7 @@ thread_local! {
     ///
 
     pub(crate) static SUBNET_INDEX: RefCell<SubnetIndexStable> = RefCell::new(SubnetIndexStable::init(
-        MEMORY_MANAGER.with_borrow(|mm| mm.get(MemoryId::new(SUBNET_INDEX_MEMORY_ID))),
+        MEMORY_MANAGER.with_borrow(|mm| get_memory(mm, SUBNET_INDEX_MEMORY_ID, "SUBNET_INDEX")),
     ));
 
     ///
@@ -111,7 +121,29 @@ thread_local! {
     ///
 
     pub(crate) static USER_INDEX: RefCell<UserIndexStable> = RefCell::new(UserIndexStable::init(
-        MEMORY_MANAGER.with_borrow(|mm| mm.get(MemoryId::new(USER_INDEX_MEMORY_ID))),
+        MEMORY_MANAGER.with_borrow(|mm| get_memory(mm, USER_INDEX_MEMORY_ID, "USER_INDEX")),
+    ));
+
+    ///
+    /// REQUEST_COST (root)
+    ///
+    /// Scope     : Subnet
+    /// Structure : BTreeMap
+    ///
+
+    pub(crate) static REQUEST_COST: RefCell<RequestCostStable> = RefCell::new(RequestCostStable::init(
+        MEMORY_MANAGER.with_borrow(|mm| get_memory(mm, REQUEST_COST_MEMORY_ID, "REQUEST_COST")),
+    ));
+
+    ///
+    /// PARENT_INDEX (root)
+    ///
+    /// Scope     : Subnet
+    /// Structure : BTreeMap
+    ///
+
+    pub(crate) static PARENT_INDEX: RefCell<ParentIndexStable> = RefCell::new(ParentIndexStable::init(
+        MEMORY_MANAGER.with_borrow(|mm| get_memory(mm, PARENT_INDEX_MEMORY_ID, "PARENT_INDEX")),
     ));
 
     ///
@@ -122,7 +154,20 @@ thread_local! {
     ///
 
     pub(crate) static CHILD_INDEX: RefCell<ChildIndexStable> = RefCell::new(ChildIndexStable::init(
-        MEMORY_MANAGER.with_borrow(|mm| mm.get(MemoryId::new(CHILD_INDEX_MEMORY_ID))),
+        MEMORY_MANAGER.with_borrow(|mm| get_memory(mm, CHILD_INDEX_MEMORY_ID, "CHILD_INDEX")),
+    ));
+
+    ///
+    /// CHILD_PATHS
+    ///
+    /// Scope     : Canister
+    /// Structure : BTreeMap
+    ///
+    /// path to children, kept in step with CHILD_INDEX by ChildIndexManager
+    ///
+
+    pub(crate) static CHILD_PATHS: RefCell<ChildPathsStable> = RefCell::new(ChildPathsStable::init(
+        MEMORY_MANAGER.with_borrow(|mm| get_memory(mm, CHILD_PATHS_MEMORY_ID, "CHILD_PATHS")),
     ));
 
     ///
@@ -133,6 +178,49 @@ thread_local! {
     ///
 
     pub(crate) static CANISTER_STATE: RefCell<CanisterStateStable> = RefCell::new(CanisterStateStable::init(
-        MEMORY_MANAGER.with_borrow(|mm| mm.get(MemoryId::new(CANISTER_STATE_MEMORY_ID))),
+        MEMORY_MANAGER.with_borrow(|mm| get_memory(mm, CANISTER_STATE_MEMORY_ID, "CANISTER_STATE")),
     ));
+
+    ///
+    /// INIT_ARG
+    ///
+    /// Scope     : Canister
+    /// Structure : Cell
+    ///
+    /// the candid-encoded init argument, apart from CANISTER_STATE as it's
+    /// only read by the canister's own code
+    ///
+
+    pub(crate) static INIT_ARG: RefCell<InitArgStable> = RefCell::new(InitArgStable::init(
+        MEMORY_MANAGER.with_borrow(|mm| get_memory(mm, INIT_ARG_MEMORY_ID, "INIT_ARG")),
+    ));
+}
+
+// memory_usage
+// the stable memory footprint of every registered structure, stores included
+#[must_use]
+pub fn memory_usage() -> BTreeMap<String, MemoryUsage> {
+    MEMORY_MANAGER.with_borrow(ic::structures::memory::usage)
+}
+
+// register_memory
+// claims the memory id of every structure up front, called from init and
+// post_upgrade so a collision fails the install rather than the first call
+// that happens to touch the second structure
+pub fn register_memory() -> Result<(), MemoryError> {
+    for (id, label) in [
+        (APP_STATE_MEMORY_ID, "APP_STATE"),
+        (SUBNET_INDEX_MEMORY_ID, "SUBNET_INDEX"),
+        (USER_INDEX_MEMORY_ID, "USER_INDEX"),
+        (REQUEST_COST_MEMORY_ID, "REQUEST_COST"),
+        (PARENT_INDEX_MEMORY_ID, "PARENT_INDEX"),
+        (CHILD_INDEX_MEMORY_ID, "CHILD_INDEX"),
+        (CHILD_PATHS_MEMORY_ID, "CHILD_PATHS"),
+        (CANISTER_STATE_MEMORY_ID, "CANISTER_STATE"),
+        (INIT_ARG_MEMORY_ID, "INIT_ARG"),
+    ] {
+        register(id, label)?;
+    }
+
+    Ok(())
 }
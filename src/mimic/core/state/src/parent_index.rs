@@ -0,0 +1,69 @@
+use super::{CHILD_INDEX, PARENT_INDEX};
+use candid::Principal;
+use derive_more::{Deref, DerefMut};
+use ic::structures::{memory::VirtualMemory, BTreeMap};
+
+///
+/// ParentIndexManager
+///
+/// root only, every canister root has created or reserved mapped to the
+/// canister that asked for it, so root can tell whether a caller is allowed
+/// to act on a canister
+///
+
+pub struct ParentIndexManager {}
+
+impl ParentIndexManager {
+    // get_parent
+    // canisters root put in its own child index before this index existed
+    // were created for root itself
+    #[must_use]
+    pub fn get_parent(id: Principal) -> Option<Principal> {
+        PARENT_INDEX
+            .with_borrow(|index| index.get(&id))
+            .or_else(|| CHILD_INDEX.with_borrow(|index| index.contains_key(&id).then(ic::api::id)))
+    }
+
+    // is_parent
+    #[must_use]
+    pub fn is_parent(id: Principal, parent_id: Principal) -> bool {
+        Self::get_parent(id) == Some(parent_id)
+    }
+
+    // contains
+    // true for any canister root made, whoever asked for it
+    #[must_use]
+    pub fn contains(id: Principal) -> bool {
+        Self::get_parent(id).is_some()
+    }
+
+    // set_parent
+    pub fn set_parent(id: Principal, parent_id: Principal) {
+        PARENT_INDEX.with_borrow_mut(|index| index.insert(id, parent_id));
+    }
+
+    // remove
+    #[must_use]
+    pub fn remove(id: Principal) -> Option<Principal> {
+        PARENT_INDEX.with_borrow_mut(|index| index.remove(&id))
+    }
+}
+
+///
+/// ParentIndexStable
+///
+
+#[derive(Deref, DerefMut)]
+pub struct ParentIndexStable {
+    state: BTreeMap<Principal, Principal>,
+}
+
+impl ParentIndexStable {
+    // init
+    #[must_use]
+    pub fn init(memory: VirtualMemory) -> Self {
+        Self {
+            state: BTreeMap::init(memory),
+        }
+    }
+}
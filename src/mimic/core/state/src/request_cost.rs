@@ -0,0 +1,70 @@
+use super::REQUEST_COST;
+use candid::CandidType;
+use derive_more::{Deref, DerefMut};
+use ic::structures::{memory::VirtualMemory, BTreeMap};
+use mimic_derive::Storable;
+use serde::{Deserialize, Serialize};
+
+///
+/// RequestCostManager
+///
+
+pub struct RequestCostManager {}
+
+impl RequestCostManager {
+    // get
+    #[must_use]
+    pub fn get() -> RequestCosts {
+        REQUEST_COST.with_borrow(|costs| costs.iter().collect())
+    }
+
+    // record
+    // adds the cycles spent on one request to the total for its kind
+    #[must_use]
+    pub fn record(kind: &str, cycles: u128) -> RequestCost {
+        REQUEST_COST.with_borrow_mut(|costs| {
+            let mut cost = costs.get(&kind.to_string()).unwrap_or_default();
+            cost.calls += 1;
+            cost.cycles = cost.cycles.saturating_add(cycles);
+            costs.insert(kind.to_string(), cost);
+
+            cost
+        })
+    }
+}
+
+///
+/// RequestCost
+///
+
+#[derive(CandidType, Clone, Copy, Debug, Default, Serialize, Deserialize, Storable)]
+pub struct RequestCost {
+    pub calls: u64,
+    pub cycles: u128,
+}
+
+///
+/// RequestCosts
+/// a map of RequestKind to its accumulated cost
+///
+
+pub type RequestCosts = Vec<(String, RequestCost)>;
+
+///
+/// RequestCostStable
+///
+
+#[derive(Deref, DerefMut)]
+pub struct RequestCostStable {
+    state: BTreeMap<String, RequestCost>,
+}
+
+impl RequestCostStable {
+    // init
+    #[must_use]
+    pub fn init(memory: VirtualMemory) -> Self {
+        Self {
+            state: BTreeMap::init(memory),
+        }
+    }
+}
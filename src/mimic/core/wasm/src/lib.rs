@@ -1,9 +1,9 @@
 use candid::CandidType;
-use ic::{log, Log};
+use ic::{helper::get_wasm_hash, log, Log};
 use serde::{Deserialize, Serialize};
 use snafu::Snafu;
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     sync::{LazyLock, Mutex},
 };
 
@@ -11,10 +11,36 @@ use std::{
 /// WASM_FILES
 /// use Mutex to ensure thread safety for mutable access
 ///
+/// each path holds its wasm by version, the highest version is the one
+/// that gets installed unless another is asked for
+///
+/// this is heap memory filled from the wasm compiled into root, so the
+/// versions only last as long as root's build, a rollback can only go back
+/// to a version that was added in the same build
+///
 
-pub static WASM_FILES: LazyLock<Mutex<HashMap<&'static str, &'static [u8]>>> =
+pub static WASM_FILES: LazyLock<Mutex<HashMap<&'static str, WasmVersions>>> =
     LazyLock::new(|| Mutex::new(HashMap::new()));
 
+///
+/// WasmVersions
+/// the wasm of one path keyed by version
+///
+
+pub type WasmVersions = BTreeMap<u32, &'static [u8]>;
+
+///
+/// WASM_MAGIC
+/// the module header, the magic number then version 1
+///
+/// GZIP_MAGIC
+/// the management canister also takes gzipped modules, those can only be
+/// checked once unpacked so the gzip header is all that's looked at
+///
+
+const WASM_MAGIC: [u8; 8] = *b"\0asm\x01\0\0\0";
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
 ///
 /// Error
 ///
@@ -24,8 +50,19 @@ pub enum Error {
     #[snafu(display("mutex lock failed"))]
     LockFailed,
 
+    #[snafu(display("invalid wasm for path {path}: {reason}"))]
+    Invalid { path: String, reason: String },
+
+    #[snafu(display(
+        "no version before {version} for path {path}, only versions added since root was last installed are kept"
+    ))]
+    NoPreviousVersion { path: String, version: u32 },
+
     #[snafu(display("wasm not found for path {path}"))]
     WasmNotFound { path: String },
+
+    #[snafu(display("wasm version {version} not found for path {path}"))]
+    WasmVersionNotFound { path: String, version: u32 },
 }
 
 ///
@@ -36,31 +73,115 @@ pub struct WasmManager {}
 
 impl WasmManager {
     // get_wasm
+    // the latest version for the path
     pub fn get_wasm(path: &str) -> Result<&'static [u8], Error> {
-        let file = WASM_FILES
-            .lock()
-            .map_err(|_| Error::LockFailed)?
+        let files = WASM_FILES.lock().map_err(|_| Error::LockFailed)?;
+        let file = files
             .get(path)
-            .copied()
+            .and_then(|versions| versions.last_key_value())
+            .map(|(_, wasm)| *wasm)
             .ok_or_else(|| Error::WasmNotFound {
                 path: path.to_string(),
             })?;
+        check_wasm(path, file)?;
 
         Ok(file)
     }
 
+    // get_wasm_version
+    pub fn get_wasm_version(path: &str, version: u32) -> Result<&'static [u8], Error> {
+        let files = WASM_FILES.lock().map_err(|_| Error::LockFailed)?;
+        let file = files
+            .get(path)
+            .and_then(|versions| versions.get(&version))
+            .copied()
+            .ok_or_else(|| Error::WasmVersionNotFound {
+                path: path.to_string(),
+                version,
+            })?;
+        check_wasm(path, file)?;
+
+        Ok(file)
+    }
+
+    // versions
+    // in ascending order, empty if nothing was added for the path
+    pub fn versions(path: &str) -> Result<Vec<u32>, Error> {
+        let files = WASM_FILES.lock().map_err(|_| Error::LockFailed)?;
+        let versions = files
+            .get(path)
+            .map(|versions| versions.keys().copied().collect())
+            .unwrap_or_default();
+
+        Ok(versions)
+    }
+
+    // version_of
+    // finds which version a module hash belongs to, as that's all a
+    // canister reports about its code
+    pub fn version_of(path: &str, module_hash: &[u8]) -> Result<Option<u32>, Error> {
+        let files = WASM_FILES.lock().map_err(|_| Error::LockFailed)?;
+        let version = files.get(path).and_then(|versions| {
+            versions
+                .iter()
+                .find(|(_, wasm)| get_wasm_hash(wasm) == module_hash)
+                .map(|(version, _)| *version)
+        });
+
+        Ok(version)
+    }
+
+    // previous_version
+    // the highest version below the one given
+    pub fn previous_version(path: &str, version: u32) -> Result<u32, Error> {
+        let files = WASM_FILES.lock().map_err(|_| Error::LockFailed)?;
+        let previous = files
+            .get(path)
+            .and_then(|versions| versions.range(..version).next_back())
+            .map(|(version, _)| *version)
+            .ok_or_else(|| Error::NoPreviousVersion {
+                path: path.to_string(),
+                version,
+            })?;
+
+        Ok(previous)
+    }
+
     // add_wasm
+    // adds the wasm as the next version for the path, starting at 1
+    pub fn add_wasm(path: &'static str, wasm: &'static [u8]) -> Result<u32, Error> {
+        let version = WASM_FILES
+            .lock()
+            .map_err(|_| Error::LockFailed)?
+            .get(path)
+            .and_then(|versions| versions.last_key_value())
+            .map_or(1, |(version, _)| version + 1);
+
+        Self::add_wasm_version(path, version, wasm)?;
+
+        Ok(version)
+    }
+
+    // add_wasm_version
+    // replaces the wasm if the version is already there
     #[allow(clippy::cast_precision_loss)]
-    pub fn add_wasm(path: &'static str, wasm: &'static [u8]) -> Result<(), Error> {
+    pub fn add_wasm_version(
+        path: &'static str,
+        version: u32,
+        wasm: &'static [u8],
+    ) -> Result<(), Error> {
         WASM_FILES
             .lock()
             .map_err(|_| Error::LockFailed)?
-            .insert(path, wasm);
+            .entry(path)
+            .or_default()
+            .insert(version, wasm);
 
         log!(
             Log::Ok,
-            "add_wasm: {} ({:.2} KB)",
+            "add_wasm: {} v{} ({:.2} KB)",
             path,
+            version,
             wasm.len() as f64 / 1000.0
         );
 
@@ -68,14 +189,79 @@ impl WasmManager {
     }
 
     // info
-    pub fn info() -> Result<Vec<(String, usize)>, Error> {
-        let info: Vec<(String, usize)> = WASM_FILES
+    // path, version and size of every wasm held
+    pub fn info() -> Result<Vec<(String, u32, usize)>, Error> {
+        let info: Vec<(String, u32, usize)> = WASM_FILES
             .lock()
             .map_err(|_| Error::LockFailed)?
             .iter()
-            .map(|(k, v)| ((*k).to_string(), v.len()))
+            .flat_map(|(path, versions)| {
+                versions
+                    .iter()
+                    .map(|(version, wasm)| ((*path).to_string(), *version, wasm.len()))
+            })
             .collect();
 
         Ok(info)
     }
 }
+
+// check_wasm
+// a cheap look at the header so a bad blob fails here with the path rather
+// than inside the management canister
+fn check_wasm(path: &str, wasm: &[u8]) -> Result<(), Error> {
+    let reason = if wasm.is_empty() {
+        "empty"
+    } else if wasm.starts_with(&WASM_MAGIC) || wasm.starts_with(&GZIP_MAGIC) {
+        return Ok(());
+    } else if wasm.starts_with(&WASM_MAGIC[..4]) {
+        "unsupported wasm version"
+    } else {
+        "missing \\0asm header"
+    };
+
+    Err(Error::Invalid {
+        path: path.to_string(),
+        reason: reason.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wasm_versions() {
+        const PATH: &str = "test::wasm::Versions";
+
+        const ONE: &[u8] = b"\0asm\x01\0\0\0one";
+        const TWO: &[u8] = b"\0asm\x01\0\0\0two";
+        const FIVE: &[u8] = b"\0asm\x01\0\0\0five";
+
+        assert_eq!(WasmManager::add_wasm(PATH, ONE).unwrap(), 1);
+        assert_eq!(WasmManager::add_wasm(PATH, TWO).unwrap(), 2);
+        WasmManager::add_wasm_version(PATH, 5, FIVE).unwrap();
+
+        assert_eq!(WasmManager::versions(PATH).unwrap(), vec![1, 2, 5]);
+        assert_eq!(WasmManager::get_wasm(PATH).unwrap(), FIVE);
+        assert_eq!(WasmManager::get_wasm_version(PATH, 2).unwrap(), TWO);
+        assert!(WasmManager::get_wasm_version(PATH, 3).is_err());
+
+        // rollback lookups
+        let hash = get_wasm_hash(FIVE);
+        assert_eq!(WasmManager::version_of(PATH, &hash).unwrap(), Some(5));
+        assert_eq!(WasmManager::previous_version(PATH, 5).unwrap(), 2);
+        assert!(WasmManager::previous_version(PATH, 1).is_err());
+    }
+
+    #[test]
+    fn test_check_wasm() {
+        assert!(check_wasm("a", b"\0asm\x01\0\0\0").is_ok());
+        assert!(check_wasm("a", &[0x1f, 0x8b, 0x08]).is_ok());
+
+        let bad: [&[u8]; 4] = [b"", b"\0asm\x02\0\0\0", b"<html>", b"\0as"];
+        for bad in bad {
+            assert!(matches!(check_wasm("a", bad), Err(Error::Invalid { .. })));
+        }
+    }
+}
@@ -1,7 +1,12 @@
-use crate::{DebugContext, Error, Resolver};
-use db::{DataKey, Db};
-use orm::traits::Entity;
-use std::{fmt::Display, marker::PhantomData};
+use crate::{
+    index::{index_bounds, index_keys, primary_key},
+    save::SaveError,
+    DebugContext, Error, Resolver,
+};
+use core_state::CanisterStateManager;
+use db::{DataKey, DataValue, Db};
+use orm::{traits::Entity, types::FieldPredicate};
+use std::{collections::BTreeSet, fmt::Display, marker::PhantomData, ops::Bound};
 
 ///
 /// DeleteBuilder
@@ -13,6 +18,8 @@ where
 {
     db: &'a Db,
     debug: DebugContext,
+    cascade: bool,
+    dry_run: bool,
     phantom: PhantomData<E>,
 }
 
@@ -26,10 +33,30 @@ where
         Self {
             db,
             debug: DebugContext::default(),
+            cascade: false,
+            dry_run: false,
             phantom: PhantomData,
         }
     }
 
+    // cascade
+    // also deletes the rows that hold an owned relation to a deleted row,
+    // and so on down, the returned keys include every row removed
+    #[must_use]
+    pub const fn cascade(mut self) -> Self {
+        self.cascade = true;
+        self
+    }
+
+    // dry_run
+    // returns the keys that would be deleted, cascades included, without
+    // removing anything
+    #[must_use]
+    pub const fn dry_run(mut self) -> Self {
+        self.dry_run = true;
+        self
+    }
+
     // debug
     #[must_use]
     pub fn debug(mut self) -> Self {
@@ -37,6 +64,15 @@ where
         self
     }
 
+    // debug_sink
+    // enables debug output and routes it to the sink instead of println
+    #[must_use]
+    pub fn debug_sink<F: Fn(&str) + 'static>(mut self, sink: F) -> Self {
+        self.debug.enable();
+        self.debug.set_sink(sink);
+        self
+    }
+
     // one
     pub fn one<T: Display>(self, ck: &[T]) -> Result<DeleteBuilderResult, Error> {
         let key: Vec<String> = ck.iter().map(ToString::to_string).collect();
@@ -44,6 +80,19 @@ where
 
         executor.execute()
     }
+
+    // filter_field
+    // deletes every row of the entity whose field matches the predicate,
+    // like the load filter this is a scan and filter, not an index
+    pub fn filter_field(
+        self,
+        field: &str,
+        predicate: &FieldPredicate,
+    ) -> Result<DeleteBuilderResult, Error> {
+        let executor = DeleteBuilderExecutor::new(self, Vec::new());
+
+        executor.execute_filter(field, predicate)
+    }
 }
 
 ///
@@ -59,6 +108,8 @@ where
 {
     db: &'a Db,
     debug: DebugContext,
+    cascade: bool,
+    dry_run: bool,
     resolver: Resolver,
     keys: Vec<Vec<String>>,
     phantom: PhantomData<E>,
@@ -74,38 +125,186 @@ where
         Self {
             db: prev.db,
             debug: prev.debug,
-            resolver: Resolver::new(&E::path()),
+            cascade: prev.cascade,
+            dry_run: prev.dry_run,
+            resolver: Resolver::for_entity::<E>(),
             keys,
             phantom: PhantomData,
         }
     }
 
     // execute
+    // read only is reported as a SaveError, a delete is a write like any other
     pub fn execute(&self) -> Result<DeleteBuilderResult, Error> {
+        if CanisterStateManager::is_read_only() {
+            Err(SaveError::ReadOnly)?;
+        }
+
         let mut results = Vec::new();
+        let mut visited = BTreeSet::new();
         ic::println!("delete: keys {:?}", &self.keys);
 
         for key in &self.keys {
-            // Attempt to remove the item from the store
             let data_key = self.resolver.data_key(key)?;
-            let store_path = self.resolver.store()?;
+            self.delete_key(&self.resolver, data_key, &mut visited, &mut results)?;
+        }
+
+        self.debug.println(&format!("deleted keys {results:?}"));
+
+        Ok(DeleteBuilderResult::new(results))
+    }
+
+    // execute_filter
+    // the matched rows and their index rows are removed in one go
+    pub fn execute_filter(
+        &self,
+        field: &str,
+        predicate: &FieldPredicate,
+    ) -> Result<DeleteBuilderResult, Error> {
+        if CanisterStateManager::is_read_only() {
+            Err(SaveError::ReadOnly)?;
+        }
 
+        let store_path = self.resolver.store()?;
+        let start = self.resolver.data_key(&[])?;
+        let end = start.create_upper_bound();
+        let rows: Vec<(DataKey, DataValue)> = self.db.with_store(&store_path, |store| {
+            Ok(store.data.range(start..=end).collect())
+        })?;
+
+        let mut matched = Vec::new();
+        for (key, value) in rows {
+            let entity: E = orm::deserialize(&value.data)?;
+            if entity.field_matches(field, predicate) {
+                matched.push((key, value));
+            }
+        }
+
+        let indexes = self.resolver.indexes()?;
+        let mut removed = Vec::new();
+        for (key, value) in &matched {
+            removed.push(key.clone());
+            removed.extend(index_keys(
+                &self.resolver.entity,
+                &indexes,
+                &value.data,
+                key,
+            )?);
+        }
+
+        if !self.dry_run {
             self.db.with_store_mut(&store_path, |store| {
-                if store.remove(&data_key).is_none() {
-                    ic::println!("key {data_key:?} not found");
+                for key in &removed {
+                    store.remove(key);
                 }
 
                 Ok(())
             })?;
+        }
 
-            // If successful, push the key to results
-            results.push(data_key.clone());
+        let mut results: Vec<DataKey> = matched.into_iter().map(|(key, _)| key).collect();
+        if self.cascade {
+            let mut visited: BTreeSet<DataKey> = results.iter().cloned().collect();
+            for key in results.clone() {
+                self.delete_children(&self.resolver, &key, &mut visited, &mut results)?;
+            }
         }
 
         self.debug.println(&format!("deleted keys {results:?}"));
 
         Ok(DeleteBuilderResult::new(results))
     }
+
+    // delete_key
+    // visited stops an owned relation cycle from going round forever
+    fn delete_key(
+        &self,
+        resolver: &Resolver,
+        data_key: DataKey,
+        visited: &mut BTreeSet<DataKey>,
+        results: &mut Vec<DataKey>,
+    ) -> Result<(), Error> {
+        if !visited.insert(data_key.clone()) {
+            return Ok(());
+        }
+
+        // Attempt to remove the item from the store
+        let indexes = resolver.indexes()?;
+        let store_path = resolver.store()?;
+        let removed = if self.dry_run {
+            self.db
+                .with_store(&store_path, |store| Ok(store.get(&data_key)))?
+        } else {
+            self.db
+                .with_store_mut(&store_path, |store| Ok(store.remove(&data_key)))?
+        };
+
+        // clean up the index rows that pointed at the removed row
+        match removed {
+            Some(_) if self.dry_run => {}
+            Some(value) => {
+                let index_keys = index_keys(&resolver.entity, &indexes, &value.data, &data_key)?;
+
+                self.db.with_store_mut(&store_path, |store| {
+                    for index_key in &index_keys {
+                        store.remove(index_key);
+                    }
+
+                    Ok(())
+                })?;
+            }
+            None => ic::println!("key {data_key:?} not found"),
+        }
+
+        // If successful, push the key to results
+        results.push(data_key.clone());
+
+        if self.cascade {
+            self.delete_children(resolver, &data_key, visited, results)?;
+        }
+
+        Ok(())
+    }
+
+    // delete_children
+    // owned relations are indexed, so the children are found with an index scan
+    fn delete_children(
+        &self,
+        resolver: &Resolver,
+        data_key: &DataKey,
+        visited: &mut BTreeSet<DataKey>,
+        results: &mut Vec<DataKey>,
+    ) -> Result<(), Error> {
+        // relations hold the single primary key of the row
+        let Some(id) = data_key
+            .parts()
+            .last()
+            .and_then(|(_, values)| values.first())
+        else {
+            return Ok(());
+        };
+        let value = orm::serialize(id)?;
+
+        for (child, field) in resolver.owned_children()? {
+            let child_resolver = Resolver::new(&child);
+            let store_path = child_resolver.store()?;
+            let (start, end) = index_bounds(&child, &field, &value);
+
+            let child_keys: Vec<DataKey> = self.db.with_store(&store_path, |store| {
+                Ok(store
+                    .data
+                    .range((Bound::Included(start), Bound::Excluded(end)))
+                    .map(|(key, _)| primary_key(&key))
+                    .collect())
+            })?;
+
+            for child_key in child_keys {
+                self.delete_key(&child_resolver, child_key, visited, results)?;
+            }
+        }
+
+        Ok(())
+    }
 }
 
 ///
@@ -0,0 +1,164 @@
+use crate::{Error, Resolver};
+use candid::CandidType;
+use db::{DataKey, Reindex};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use snafu::Snafu;
+
+///
+/// IndexError
+///
+
+#[derive(CandidType, Debug, Serialize, Deserialize, Snafu)]
+pub enum IndexError {
+    #[snafu(display("no index on field: {field}"))]
+    IndexNotFound { field: String },
+}
+
+///
+/// Index Keys
+///
+/// index rows live in the same store as the entity, keyed by
+/// (entity#field, value) followed by the parts of the primary data key,
+/// so every row sharing a value sits in one contiguous range
+///
+/// a DataKey is capped at 255 bytes, so values longer than
+/// MAX_INDEX_VALUE_LEN are stored as a # followed by their sha256, the #
+/// keeps them apart from short values that happen to be 32 bytes long
+///
+
+pub const MAX_INDEX_VALUE_LEN: usize = 32;
+
+// index_key
+#[must_use]
+pub fn index_key(entity: &str, field: &str, value: &[u8], key: &DataKey) -> DataKey {
+    let mut parts = vec![index_part(entity, field, value)];
+    parts.extend_from_slice(key.parts());
+
+    DataKey::new(parts)
+}
+
+// index_keys
+// builds the index keys for every indexed field found in the serialized entity
+pub fn index_keys(
+    entity: &str,
+    fields: &[String],
+    data: &[u8],
+    key: &DataKey,
+) -> Result<Vec<DataKey>, Error> {
+    if fields.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let names: Vec<&str> = fields.iter().map(String::as_str).collect();
+    let slices = orm::field_slices(data, &names)?;
+
+    let keys = fields
+        .iter()
+        .filter_map(|field| {
+            slices
+                .get(field)
+                .map(|value| index_key(entity, field, value, key))
+        })
+        .collect();
+
+    Ok(keys)
+}
+
+// index_bounds
+// the lower bound sorts before, and the upper bound after, every key for the value
+#[must_use]
+pub fn index_bounds(entity: &str, field: &str, value: &[u8]) -> (DataKey, DataKey) {
+    let (part, mut values) = index_part(entity, field, value);
+    let start = DataKey::new(vec![(part.clone(), values.clone())]);
+
+    values.push(String::new());
+    let end = DataKey::new(vec![(part, values)]);
+
+    (start, end)
+}
+
+// primary_key
+// strips the index part to get back to the data key of the row
+#[must_use]
+pub fn primary_key(index_key: &DataKey) -> DataKey {
+    DataKey::new(index_key.parts().iter().skip(1).cloned().collect())
+}
+
+// sequence_key
+// the counter row for Sequential keys, like the index rows it sorts outside
+// the entity's own range
+#[must_use]
+pub fn sequence_key(entity: &str) -> DataKey {
+    DataKey::new(vec![(format!("{entity}#"), Vec::new())])
+}
+
+///
+/// SchemaReindex
+/// the index rows the schema declares for a row, the entity is the last
+/// part of its key
+///
+
+pub struct SchemaReindex;
+
+impl Reindex for SchemaReindex {
+    fn index_keys(&self, key: &DataKey, data: &[u8]) -> Result<Vec<DataKey>, String> {
+        let entity = key.parts().last().map_or("", |(path, _)| path.as_str());
+        let fields = Resolver::new(entity).indexes().map_err(|e| e.to_string())?;
+
+        index_keys(entity, &fields, data, key).map_err(|e| e.to_string())
+    }
+}
+
+// index_part
+fn index_part(entity: &str, field: &str, value: &[u8]) -> (String, Vec<String>) {
+    let value = if value.len() > MAX_INDEX_VALUE_LEN {
+        format!("#{}", hex::encode(Sha256::digest(value)))
+    } else {
+        hex::encode(value)
+    };
+
+    (format!("{entity}#{field}"), vec![value])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data_key(id: &str) -> DataKey {
+        DataKey::new(vec![("test::Entity".to_string(), vec![id.to_string()])])
+    }
+
+    #[test]
+    fn test_index_bounds() {
+        let key = index_key("test::Entity", "name", b"ab", &data_key("1"));
+        let (start, end) = index_bounds("test::Entity", "name", b"ab");
+        assert!(start < key && key < end);
+
+        // neighbouring values fall outside the bounds
+        for value in [&b"a"[..], b"abc", b"b"] {
+            let other = index_key("test::Entity", "name", value, &data_key("1"));
+            assert!(other < start || other >= end);
+        }
+
+        assert_eq!(primary_key(&key), data_key("1"));
+    }
+
+    #[test]
+    fn test_index_long_value() {
+        let long = vec![b'a'; 300];
+        let key = index_key("test::Entity", "name", &long, &data_key("1"));
+        assert!(key.parts()[0].1[0].len() < 100);
+
+        // values sharing a long prefix still get their own range
+        let mut other = long.clone();
+        other.push(b'b');
+        let (start, end) = index_bounds("test::Entity", "name", &other);
+        assert!(key < start || key >= end);
+
+        // a short value is never mistaken for a hashed one
+        let digest = Sha256::digest(&long);
+        let short = index_key("test::Entity", "name", &digest, &data_key("1"));
+        assert_ne!(short, key);
+    }
+}
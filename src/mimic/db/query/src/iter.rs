@@ -3,11 +3,20 @@ use crate::{
     Error,
 };
 use candid::CandidType;
-use db::{DataKey, DataRow};
-use orm::traits::Entity;
+use db::{DataKey, DataRow, Db};
+use orm::{traits::Entity, types::FieldPredicate};
 use serde::{Deserialize, Serialize};
 use snafu::Snafu;
-use std::iter;
+use std::{collections::VecDeque, iter, marker::PhantomData, ops::Bound};
+use types::Timestamp;
+
+///
+/// STREAM_BATCH_ROWS
+///
+/// how many raw rows a RowStream reads from the store per borrow
+///
+
+pub const STREAM_BATCH_ROWS: usize = 100;
 
 ///
 /// IterError
@@ -48,6 +57,7 @@ where
         limit: Option<u32>,
         offset: u32,
         filter: Option<Filter>,
+        predicates: Vec<(String, FieldPredicate)>,
         order: Option<Order>,
     ) -> Self {
         // sorting?
@@ -63,16 +73,25 @@ where
             iter
         };
 
-        // Map the optional Filter struct to an optional closure
-        let filter_closure = filter.map(|f| {
-            let Filter { fields, text } = f;
-            Box::new(move |row: &EntityRow<E>| {
+        // Map the optional Filter struct and the field predicates to an
+        // optional closure, a row has to match all of them
+        let filter_closure = if filter.is_none() && predicates.is_empty() {
+            None
+        } else {
+            Some(Box::new(move |row: &EntityRow<E>| {
+                let entity = &row.value.entity;
+
                 // Apply the captured filter criteria to each EntityRow<E>
-                row.value
-                    .entity
-                    .fields_contain_text(fields.as_deref(), &text)
-            }) as Box<dyn Fn(&EntityRow<E>) -> bool>
-        });
+                let text_match = filter.as_ref().map_or(true, |Filter { fields, text }| {
+                    entity.fields_contain_text(fields.as_deref(), text)
+                });
+
+                text_match
+                    && predicates
+                        .iter()
+                        .all(|(field, predicate)| entity.field_matches(field, predicate))
+            }) as Box<dyn Fn(&EntityRow<E>) -> bool>)
+        };
 
         // Build IterManager
         let manager = IterManager::new(limit, offset, filter_closure);
@@ -142,6 +161,124 @@ where
     }
 }
 
+///
+/// RowStream
+///
+/// reads a key range from the store in batches and deserializes each row as
+/// the consumer asks for it, so only one batch of raw rows is held at a time
+///
+
+pub struct RowStream<'a, E>
+where
+    E: Entity,
+{
+    db: &'a Db,
+    store_path: String,
+    start: Bound<DataKey>,
+    end: DataKey,
+    buffer: VecDeque<DataRow>,
+    done: bool,
+    skip_expired: Option<Timestamp>,
+    phantom: PhantomData<E>,
+}
+
+impl<'a, E> RowStream<'a, E>
+where
+    E: Entity,
+{
+    // new
+    // skip_expired holds the time rows are checked against
+    #[must_use]
+    pub const fn new(
+        db: &'a Db,
+        store_path: String,
+        start: Bound<DataKey>,
+        end: DataKey,
+        skip_expired: Option<Timestamp>,
+    ) -> Self {
+        Self {
+            db,
+            store_path,
+            start,
+            end,
+            buffer: VecDeque::new(),
+            done: false,
+            skip_expired,
+            phantom: PhantomData,
+        }
+    }
+
+    // from_rows
+    // for loads that don't map to a range, the rows are already read
+    pub fn from_rows<I>(db: &'a Db, rows: I) -> Self
+    where
+        I: Iterator<Item = DataRow>,
+    {
+        Self {
+            db,
+            store_path: String::new(),
+            start: Bound::Unbounded,
+            end: DataKey::new(Vec::new()),
+            buffer: rows.collect(),
+            done: true,
+            skip_expired: None,
+            phantom: PhantomData,
+        }
+    }
+
+    // fill
+    // reads the next batch and moves the start past its last key
+    fn fill(&mut self) -> Result<(), Error> {
+        let start = self.start.clone();
+        let rows = self.db.with_store(&self.store_path, |store| {
+            Ok(store
+                .data
+                .range((start, Bound::Included(self.end.clone())))
+                .take(STREAM_BATCH_ROWS)
+                .map(|(key, value)| DataRow { key, value })
+                .collect::<Vec<_>>())
+        })?;
+
+        if rows.len() < STREAM_BATCH_ROWS {
+            self.done = true;
+        }
+        if let Some(row) = rows.last() {
+            self.start = Bound::Excluded(row.key.clone());
+        }
+
+        let now = self.skip_expired;
+        self.buffer.extend(
+            rows.into_iter()
+                .filter(|row| now.map_or(true, |now| !row.value.metadata.is_expired(now))),
+        );
+
+        Ok(())
+    }
+}
+
+impl<'a, E> Iterator for RowStream<'a, E>
+where
+    E: Entity,
+{
+    type Item = Result<EntityRow<E>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(row) = self.buffer.pop_front() {
+                return Some(EntityRow::try_from(row).map_err(Error::from));
+            }
+            if self.done {
+                return None;
+            }
+            if let Err(e) = self.fill() {
+                self.done = true;
+
+                return Some(Err(e));
+            }
+        }
+    }
+}
+
 ///
 /// RowIteratorDynamic
 ///
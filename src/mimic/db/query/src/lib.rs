@@ -1,4 +1,5 @@
 pub mod delete;
+pub mod index;
 pub mod iter;
 pub mod load;
 pub mod resolver;
@@ -6,14 +7,14 @@ pub mod save;
 pub mod types;
 
 pub use delete::DeleteBuilder;
-pub use iter::{RowIterator, RowIteratorDynamic};
+pub use iter::{RowIterator, RowIteratorDynamic, RowStream};
 pub use load::{LoadBuilder, LoadBuilderOptions};
 pub use resolver::Resolver;
-pub use save::{SaveBuilder, SaveMode};
+pub use save::{SaveBuilder, SaveMode, SaveOutcome, SaveStatus, ValidateAsync};
 pub use types::*;
 
 use candid::CandidType;
-use db::Db;
+use db::{DataKey, DataValue, Db, ImportPolicy, ImportSummary, Migration, MigrationProgress};
 use orm::traits::Entity;
 use serde::{Deserialize, Serialize};
 use snafu::Snafu;
@@ -41,6 +42,9 @@ pub enum Error {
 
     #[snafu(transparent)]
     Iter { source: iter::IterError },
+
+    #[snafu(transparent)]
+    Index { source: index::IndexError },
 }
 
 ///
@@ -83,13 +87,43 @@ pub fn update(db: &Db) -> SaveBuilder {
     SaveBuilder::new(db, SaveMode::Update)
 }
 
+// import
+// raw rows, ie. from Db::export, written back with their metadata and
+// index rows, see SaveBuilderExecutor::import
+pub fn import<I>(db: &Db, rows: I, policy: ImportPolicy) -> Result<ImportSummary, Error>
+where
+    I: IntoIterator<Item = (DataKey, DataValue)>,
+{
+    let builder = SaveBuilder::new(db, SaveMode::Replace);
+
+    save::SaveBuilderExecutor::new(builder, Vec::new()).import(rows.into_iter().collect(), policy)
+}
+
+// migrate
+// runs the migrations over the store, keeping the index rows in step with
+// whatever the migrations change
+pub fn migrate(
+    db: &Db,
+    store: &str,
+    migrations: &[&dyn Migration],
+    limit: usize,
+) -> Result<MigrationProgress, Error> {
+    let progress = db.migrate(store, migrations, &index::SchemaReindex, limit)?;
+
+    Ok(progress)
+}
+
 ///
 /// DebugContext
+/// output goes through the sink if one is set, otherwise to println
 ///
 
+type DebugSink = dyn Fn(&str);
+
 #[derive(Default)]
 pub struct DebugContext {
     enabled: bool,
+    sink: Option<Box<DebugSink>>,
 }
 
 impl DebugContext {
@@ -97,9 +131,98 @@ impl DebugContext {
         self.enabled = true;
     }
 
+    #[must_use]
+    pub const fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    // instructions
+    // the instruction counter for the current message, only read while
+    // enabled and always 0 outside a canister
+    #[must_use]
+    pub fn instructions(&self) -> u64 {
+        if self.enabled && cfg!(target_arch = "wasm32") {
+            ic::api::performance_counter(1)
+        } else {
+            0
+        }
+    }
+
+    // set_sink
+    pub fn set_sink<F: Fn(&str) + 'static>(&mut self, sink: F) {
+        self.sink = Some(Box::new(sink));
+    }
+
     pub fn println(&self, s: &str) {
         if self.enabled {
-            ic::println!("{s}");
+            match &self.sink {
+                Some(sink) => sink(s),
+                None => ic::println!("{s}"),
+            }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{cell::RefCell, rc::Rc};
+
+    #[test]
+    fn test_debug_context_sink() {
+        let lines = Rc::new(RefCell::new(Vec::new()));
+        let captured = Rc::clone(&lines);
+
+        let mut debug = DebugContext::default();
+        debug.set_sink(move |s| captured.borrow_mut().push(s.to_string()));
+        debug.println("ignored while disabled");
+        debug.enable();
+        debug.println("store.create: key");
+
+        assert_eq!(*lines.borrow(), vec!["store.create: key".to_string()]);
+    }
+
+    #[test]
+    fn test_error_source_chain() {
+        fn is_std_error<E: std::error::Error + Send + Sync + 'static>(_: &E) {}
+
+        let source = orm::deserialize::<u64>(&[0xff]).unwrap_err();
+        let source_text = source.to_string();
+        let inner_text = std::error::Error::source(&source).map(ToString::to_string);
+        let err = Error::from(source);
+        is_std_error(&err);
+
+        // the transparent variant keeps both the message and the source
+        assert_eq!(err.to_string(), source_text);
+        assert!(inner_text.is_some());
+        assert_eq!(
+            std::error::Error::source(&err).map(ToString::to_string),
+            inner_text
+        );
+    }
+
+    #[test]
+    fn test_validation_errors() {
+        let mut errs = ::types::ErrorVec::new();
+        errs.add_validator("validator::number::Lt", "too big");
+        let mut errors = ::types::FieldErrors::new();
+        errors.add_vec("level", errs);
+
+        let save = save::SaveError::Validation {
+            key: db::DataKey::new(Vec::new()),
+            path: "test::Entity".to_string(),
+            errors,
+        };
+        let save_text = save.to_string();
+        assert!(save_text.ends_with("level: too big (validator::number::Lt)"));
+
+        let err = Error::from(save);
+        assert_eq!(err.to_string(), save_text);
+        assert!(matches!(
+            err,
+            Error::Save {
+                source: save::SaveError::Validation { .. }
+            }
+        ));
+    }
+}
@@ -1,14 +1,31 @@
 use crate::{
-    iter::{RowIterator, RowIteratorDynamic},
-    types::{EntityRow, Filter, LoadMethod, Order},
+    index::{index_bounds, primary_key, IndexError},
+    iter::{RowIterator, RowIteratorDynamic, RowStream},
+    types::{Aggregate, EntityRow, Filter, LoadMethod, LoadPage, Order, ProjectedRow, QueryRow},
     DebugContext, Error, Resolver,
 };
 use candid::CandidType;
-use db::{DataKey, DataRow, Db};
-use orm::traits::Entity;
-use serde::{Deserialize, Serialize};
+use db::{DataKey, DataRow, Db, Metadata, Store};
+use orm::{
+    traits::{Entity, NumToPrimitive},
+    types::{FieldPredicate, SortDirection},
+    Value,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use snafu::Snafu;
-use std::marker::PhantomData;
+use std::{marker::PhantomData, ops::Bound};
+use types::{Decimal, Timestamp};
+
+///
+/// MAX_ORDER_ROWS
+///
+/// ordering has to deserialize and sort every loaded row in memory before the
+/// offset and limit are applied, so it costs O(n log n) on the whole result
+/// set and the number of rows that can be ordered is capped, the load fails
+/// as soon as it reads one row past the cap
+///
+
+pub const MAX_ORDER_ROWS: usize = 10_000;
 
 ///
 /// LoadError
@@ -16,6 +33,15 @@ use std::marker::PhantomData;
 
 #[derive(CandidType, Debug, Serialize, Deserialize, Snafu)]
 pub enum LoadError {
+    #[snafu(display("filtering not allowed on aggregates"))]
+    AggregateFilterNotAllowed,
+
+    #[snafu(display("field '{field}' is not numeric"))]
+    AggregateNotNumeric { field: String },
+
+    #[snafu(display("filtering not allowed on counts"))]
+    CountFilterNotAllowed,
+
     #[snafu(display("filtering not allowed on dynamic loads"))]
     FilterNotAllowed,
 
@@ -25,8 +51,17 @@ pub enum LoadError {
     #[snafu(display("no results found"))]
     NoResultsFound,
 
+    #[snafu(display("cannot order more than {max} rows"))]
+    OrderTooManyRows { max: usize },
+
+    #[snafu(display("filtering and ordering not allowed on paged loads"))]
+    PageNotAllowed,
+
     #[snafu(display("range queries not allowed on composite keys"))]
     RangeNotAllowed,
+
+    #[snafu(display("filtering and ordering not allowed on streams"))]
+    StreamNotAllowed,
 }
 
 ///
@@ -63,21 +98,30 @@ where
         self
     }
 
+    // debug_sink
+    // enables debug output and routes it to the sink instead of println
+    #[must_use]
+    pub fn debug_sink<F: Fn(&str) + 'static>(mut self, sink: F) -> Self {
+        self.debug.enable();
+        self.debug.set_sink(sink);
+        self
+    }
+
     // method
     #[must_use]
-    pub const fn method(self, method: LoadMethod) -> LoadBuilderOptions<'a, E> {
+    pub fn method(self, method: LoadMethod) -> LoadBuilderOptions<'a, E> {
         self.build_options(method)
     }
 
     // all
     #[must_use]
-    pub const fn all(self) -> LoadBuilderOptions<'a, E> {
+    pub fn all(self) -> LoadBuilderOptions<'a, E> {
         self.build_options(LoadMethod::All)
     }
 
     // only
     #[must_use]
-    pub const fn only(self) -> LoadBuilderOptions<'a, E> {
+    pub fn only(self) -> LoadBuilderOptions<'a, E> {
         self.build_options(LoadMethod::One(Vec::new()))
     }
 
@@ -88,6 +132,24 @@ where
         self.build_options(LoadMethod::One(ck_str))
     }
 
+    // get
+    // the typed counterpart to save, a missing row is None rather than an error
+    pub fn get<T: ToString>(self, ck: &[T]) -> Result<Option<EntityRow<E>>, Error> {
+        let ck_str: Vec<String> = ck.iter().map(ToString::to_string).collect();
+        let executor =
+            LoadBuilderExecutor::new(self.build_options(LoadMethod::One(ck_str.clone())));
+
+        executor.get(&ck_str)
+    }
+
+    // from_keys
+    // one row per composite key in input order, None for a dangling key
+    pub fn from_keys(self, cks: &[Vec<String>]) -> Result<Vec<Option<EntityRow<E>>>, Error> {
+        let executor = LoadBuilderExecutor::new(self.build_options(LoadMethod::Many(cks.to_vec())));
+
+        executor.from_keys(cks)
+    }
+
     // many
     #[must_use]
     pub fn many(self, cks: &[Vec<String>]) -> LoadBuilderOptions<'a, E> {
@@ -113,8 +175,23 @@ where
         Ok(self.build_options(LoadMethod::Prefix(prefix)))
     }
 
+    // index
+    // looks up rows by the value of an indexed field instead of scanning
+    pub fn index<T: Serialize>(
+        self,
+        field: &str,
+        value: &T,
+    ) -> Result<LoadBuilderOptions<'a, E>, Error> {
+        let value = orm::serialize(value)?;
+
+        Ok(self.build_options(LoadMethod::Index {
+            field: field.to_string(),
+            value,
+        }))
+    }
+
     // build_options
-    const fn build_options(self, method: LoadMethod) -> LoadBuilderOptions<'a, E> {
+    fn build_options(self, method: LoadMethod) -> LoadBuilderOptions<'a, E> {
         LoadBuilderOptions::new(self, method)
     }
 }
@@ -132,8 +209,11 @@ where
     method: LoadMethod,
     offset: u32,
     limit: Option<u32>,
+    cursor: Option<DataKey>,
     filter: Option<Filter>,
+    predicates: Vec<(String, FieldPredicate)>,
     order: Option<Order>,
+    skip_expired: bool,
     phantom: PhantomData<E>,
 }
 
@@ -142,15 +222,18 @@ where
     E: Entity + 'static,
 {
     #[must_use]
-    pub const fn new(prev: LoadBuilder<'a, E>, method: LoadMethod) -> Self {
+    pub fn new(prev: LoadBuilder<'a, E>, method: LoadMethod) -> Self {
         Self {
             db: prev.db,
             debug: prev.debug,
             method,
             offset: 0,
             limit: None,
+            cursor: None,
             filter: None,
+            predicates: Vec::new(),
             order: None,
+            skip_expired: false,
             phantom: PhantomData,
         }
     }
@@ -176,6 +259,21 @@ where
         self
     }
 
+    // cursor
+    // used by page, only rows with a key after the cursor are returned
+    #[must_use]
+    pub fn cursor(mut self, cursor: DataKey) -> Self {
+        self.cursor = Some(cursor);
+        self
+    }
+
+    // cursor_option
+    #[must_use]
+    pub fn cursor_option(mut self, cursor: Option<DataKey>) -> Self {
+        self.cursor = cursor;
+        self
+    }
+
     // filter
     #[must_use]
     pub fn filter<T: Into<Filter>>(mut self, filter: T) -> Self {
@@ -190,6 +288,15 @@ where
         self
     }
 
+    // filter_field
+    // rows are loaded and deserialized, and only returned if the field
+    // matches the predicate, this is a scan and filter, not an index
+    #[must_use]
+    pub fn filter_field(mut self, field: &str, predicate: FieldPredicate) -> Self {
+        self.predicates.push((field.to_string(), predicate));
+        self
+    }
+
     // order
     #[must_use]
     pub fn order<T: Into<Order>>(mut self, order: T) -> Self {
@@ -204,6 +311,24 @@ where
         self
     }
 
+    // order_by
+    // adds a field to the order, so chained calls sort by each field in turn
+    #[must_use]
+    pub fn order_by(mut self, field: &str, direction: SortDirection) -> Self {
+        self.order
+            .get_or_insert_with(Order::default)
+            .push((field.to_string(), direction));
+        self
+    }
+
+    // skip_expired
+    // rows past their expiry are treated as absent
+    #[must_use]
+    pub const fn skip_expired(mut self) -> Self {
+        self.skip_expired = true;
+        self
+    }
+
     // execute
     pub fn execute(self) -> Result<RowIterator<E>, Error> {
         let executor = LoadBuilderExecutor::new(self);
@@ -212,6 +337,15 @@ where
         Ok(iter)
     }
 
+    // stream
+    // rows are deserialized lazily as the iterator advances, so large
+    // scans stay within the heap budget
+    pub fn stream(self) -> Result<impl Iterator<Item = Result<EntityRow<E>, Error>> + 'a, Error> {
+        let executor = LoadBuilderExecutor::new(self);
+
+        executor.stream()
+    }
+
     // execute_dyn
     pub fn execute_dyn(self) -> Result<RowIteratorDynamic, Error> {
         let executor = LoadBuilderExecutor::new(self);
@@ -219,6 +353,84 @@ where
 
         Ok(iter)
     }
+
+    // project
+    // only the requested fields of each row are deserialized, like execute_dyn
+    // rows are in DataKey order and filters are not allowed
+    pub fn project(self, fields: &[&str]) -> Result<Vec<ProjectedRow>, Error> {
+        let rows = self
+            .execute_dyn()?
+            .map(|row| {
+                let fields = orm::deserialize_fields(&row.value.data, fields)?;
+
+                Ok(ProjectedRow {
+                    key: row.key,
+                    fields,
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        Ok(rows)
+    }
+
+    // project_as
+    // deserializes each row into a lightweight struct, serde skips over any
+    // entity fields that aren't on the struct
+    pub fn project_as<P>(self) -> Result<Vec<(DataKey, P)>, Error>
+    where
+        P: DeserializeOwned,
+    {
+        let rows = self
+            .execute_dyn()?
+            .map(|row| Ok((row.key, orm::deserialize::<P>(&row.value.data)?)))
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        Ok(rows)
+    }
+
+    // page
+    pub fn page(self) -> Result<LoadPage, Error> {
+        let executor = LoadBuilderExecutor::new(self);
+        let page = executor.page()?;
+
+        Ok(page)
+    }
+
+    // count
+    pub fn count(self) -> Result<u64, Error> {
+        let executor = LoadBuilderExecutor::new(self);
+        let count = executor.count()?;
+
+        Ok(count)
+    }
+
+    // aggregate
+    pub fn aggregate(self, field: &str) -> Result<Aggregate, Error> {
+        let executor = LoadBuilderExecutor::new(self);
+        let aggregate = executor.aggregate(field)?;
+
+        Ok(aggregate)
+    }
+
+    // sum
+    pub fn sum(self, field: &str) -> Result<f64, Error> {
+        Ok(self.aggregate(field)?.sum)
+    }
+
+    // min
+    pub fn min(self, field: &str) -> Result<Option<f64>, Error> {
+        Ok(self.aggregate(field)?.min)
+    }
+
+    // max
+    pub fn max(self, field: &str) -> Result<Option<f64>, Error> {
+        Ok(self.aggregate(field)?.max)
+    }
+
+    // avg
+    pub fn avg(self, field: &str) -> Result<Option<f64>, Error> {
+        Ok(self.aggregate(field)?.avg())
+    }
 }
 
 ///
@@ -234,8 +446,12 @@ where
     method: LoadMethod,
     limit: Option<u32>,
     offset: u32,
+    cursor: Option<DataKey>,
     filter: Option<Filter>,
+    predicates: Vec<(String, FieldPredicate)>,
     order: Option<Order>,
+    skip_expired: bool,
+    now: Timestamp,
     resolver: Resolver,
     phantom: PhantomData<E>,
 }
@@ -253,9 +469,13 @@ where
             method: prev.method,
             limit: prev.limit,
             offset: prev.offset,
+            cursor: prev.cursor,
             filter: prev.filter,
+            predicates: prev.predicates,
             order: prev.order,
-            resolver: Resolver::new(&E::path()),
+            skip_expired: prev.skip_expired,
+            now: Timestamp::now(),
+            resolver: Resolver::for_entity::<E>(),
             phantom: PhantomData,
         }
     }
@@ -263,8 +483,10 @@ where
     // execute
     // convert into EntityRows and return a RowIterator
     pub fn execute(self) -> Result<RowIterator<E>, Error> {
+        // ordering is done in memory so is capped
+        let max_rows = self.order.is_some().then_some(MAX_ORDER_ROWS);
         let iter = self
-            .do_execute()?
+            .do_execute_max(max_rows)?
             .map(TryFrom::try_from)
             .collect::<Result<Vec<EntityRow<E>>, _>>()?;
 
@@ -275,14 +497,101 @@ where
             self.limit,
             self.offset,
             self.filter,
+            self.predicates,
             self.order,
         ))
     }
 
+    // get
+    pub fn get(self, ck: &[String]) -> Result<Option<EntityRow<E>>, Error> {
+        let key = self.resolver.data_key(ck)?;
+        self.debug.println(&format!("store.get: {key}"));
+
+        let store_path = self.resolver.store()?;
+        let value = self
+            .db
+            .with_store(&store_path, |store| Ok(store.data.get(&key)))?;
+
+        let row = value
+            .map(|value| EntityRow::try_from(DataRow { key, value }))
+            .transpose()?;
+
+        Ok(row)
+    }
+
+    // from_keys
+    // every key is looked up within a single store borrow
+    pub fn from_keys(self, cks: &[Vec<String>]) -> Result<Vec<Option<EntityRow<E>>>, Error> {
+        let keys = cks
+            .iter()
+            .map(|ck| self.resolver.data_key(ck))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.debug
+            .println(&format!("store.get (many): {} keys", keys.len()));
+
+        let store_path = self.resolver.store()?;
+        let rows = self.db.with_store(&store_path, |store| {
+            Ok(keys
+                .into_iter()
+                .map(|key| store.data.get(&key).map(|value| DataRow { key, value }))
+                .collect::<Vec<_>>())
+        })?;
+
+        let rows = rows
+            .into_iter()
+            .map(|row| row.map(EntityRow::try_from).transpose())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(rows)
+    }
+
+    // stream
+    // range based methods read the store in batches from the cursor onwards,
+    // filters and ordering need every row so are not allowed
+    pub fn stream(self) -> Result<impl Iterator<Item = Result<EntityRow<E>, Error>> + 'a, Error> {
+        if self.is_filtered() || self.order.is_some() {
+            Err(LoadError::StreamNotAllowed)?;
+        }
+
+        let bounds = match &self.method {
+            LoadMethod::All => Some(self.prefix_bounds(&[])?),
+            LoadMethod::Prefix(prefix) => Some(self.prefix_bounds(prefix)?),
+            LoadMethod::Range(start, end) => Some(self.range_bounds(start, end)?),
+            LoadMethod::One(_) | LoadMethod::Many(_) | LoadMethod::Index { .. } => None,
+        };
+
+        let stream = match bounds {
+            Some((start, end)) => {
+                self.debug
+                    .println(&format!("store.range (stream): {start} -> {end}"));
+
+                RowStream::new(
+                    self.db,
+                    self.resolver.store()?,
+                    self.start_bound(start),
+                    end,
+                    self.skip_expired.then_some(self.now),
+                )
+            }
+            None => {
+                let cursor = self.cursor.clone();
+                let rows = self
+                    .do_execute()?
+                    .filter(move |row| cursor.as_ref().map_or(true, |cursor| row.key > *cursor));
+
+                RowStream::from_rows(self.db, rows)
+            }
+        };
+
+        Ok(stream
+            .skip(self.offset as usize)
+            .take(self.limit.map_or(usize::MAX, |limit| limit as usize)))
+    }
+
     // execute_dyn
     // cannot currently use filter here
     pub fn execute_dyn(self) -> Result<RowIteratorDynamic, Error> {
-        if self.filter.is_some() {
+        if self.is_filtered() {
             Err(Error::from(LoadError::FilterNotAllowed))?;
         }
         let iter = self.do_execute()?;
@@ -290,11 +599,112 @@ where
         Ok(RowIteratorDynamic::new(iter, self.limit, self.offset))
     }
 
+    // page
+    // range based methods read the store from the cursor onwards, so skipped
+    // rows before the cursor are never scanned
+    pub fn page(self) -> Result<LoadPage, Error> {
+        if self.is_filtered() || self.order.is_some() {
+            Err(LoadError::PageNotAllowed)?;
+        }
+
+        let page = match &self.method {
+            LoadMethod::All => {
+                let (start, end) = self.prefix_bounds(&[])?;
+                self.page_range(start, end)?
+            }
+            LoadMethod::Prefix(prefix) => {
+                let (start, end) = self.prefix_bounds(prefix)?;
+                self.page_range(start, end)?
+            }
+            LoadMethod::Range(start, end) => {
+                let (start, end) = self.range_bounds(start, end)?;
+                self.page_range(start, end)?
+            }
+            LoadMethod::One(_) | LoadMethod::Many(_) | LoadMethod::Index { .. } => {
+                self.paginate(self.do_execute()?)
+            }
+        };
+
+        Ok(page)
+    }
+
+    // count
+    // counts rows without deserializing them into entities, so text filters
+    // can't be used, offset and limit are applied to the total
+    pub fn count(self) -> Result<u64, Error> {
+        if self.is_filtered() {
+            Err(LoadError::CountFilterNotAllowed)?;
+        }
+
+        let total = match &self.method {
+            LoadMethod::All => {
+                let (start, end) = self.prefix_bounds(&[])?;
+                self.count_range(start, end)?
+            }
+            LoadMethod::Prefix(prefix) => {
+                let (start, end) = self.prefix_bounds(prefix)?;
+                self.count_range(start, end)?
+            }
+            LoadMethod::Range(start, end) => {
+                let (start, end) = self.range_bounds(start, end)?;
+                self.count_range(start, end)?
+            }
+            LoadMethod::One(ck) => self.count_keys(std::slice::from_ref(ck))?,
+            LoadMethod::Many(cks) => self.count_keys(cks)?,
+            LoadMethod::Index { field, value } => self.count_index(field, value)?,
+        };
+
+        let count = total.saturating_sub(u64::from(self.offset));
+        let count = self
+            .limit
+            .map_or(count, |limit| count.min(u64::from(limit)));
+
+        Ok(count)
+    }
+
+    // aggregate
+    // range based methods stream rows straight from the store, only the
+    // requested field of each row is deserialized
+    pub fn aggregate(self, field: &str) -> Result<Aggregate, Error> {
+        if self.is_filtered() {
+            Err(LoadError::AggregateFilterNotAllowed)?;
+        }
+
+        let aggregate = match &self.method {
+            LoadMethod::All => {
+                let (start, end) = self.prefix_bounds(&[])?;
+                self.aggregate_range(field, start, end)?
+            }
+            LoadMethod::Prefix(prefix) => {
+                let (start, end) = self.prefix_bounds(prefix)?;
+                self.aggregate_range(field, start, end)?
+            }
+            LoadMethod::Range(start, end) => {
+                let (start, end) = self.range_bounds(start, end)?;
+                self.aggregate_range(field, start, end)?
+            }
+            LoadMethod::One(_) | LoadMethod::Many(_) | LoadMethod::Index { .. } => {
+                self.aggregate_rows(field, self.do_execute()?)?
+            }
+        };
+
+        Ok(aggregate)
+    }
+
     // do_execute
     fn do_execute(&self) -> Result<Box<dyn Iterator<Item = DataRow>>, Error> {
+        self.do_execute_max(None)
+    }
+
+    // do_execute_max
+    // fails with OrderTooManyRows once more than max_rows rows are read
+    fn do_execute_max(
+        &self,
+        max_rows: Option<usize>,
+    ) -> Result<Box<dyn Iterator<Item = DataRow>>, Error> {
         let rows = match &self.method {
             LoadMethod::All => self
-                .load_prefix(&Vec::new())
+                .load_prefix(&Vec::new(), max_rows)
                 .map(|iter| Box::new(iter) as Box<dyn Iterator<Item = DataRow>>),
 
             LoadMethod::One(ck) => self
@@ -302,18 +712,30 @@ where
                 .map(|iter| Box::new(iter) as Box<dyn Iterator<Item = DataRow>>),
 
             LoadMethod::Many(cks) => self
-                .load_many(cks)
+                .load_many(cks, max_rows)
                 .map(|iter| Box::new(iter) as Box<dyn Iterator<Item = DataRow>>),
 
             LoadMethod::Prefix(ck) => self
-                .load_prefix(ck)
+                .load_prefix(ck, max_rows)
                 .map(|iter| Box::new(iter) as Box<dyn Iterator<Item = DataRow>>),
 
             LoadMethod::Range(start, end) => self
-                .load_range(start, end)
+                .load_range(start, end, max_rows)
+                .map(|iter| Box::new(iter) as Box<dyn Iterator<Item = DataRow>>),
+
+            LoadMethod::Index { field, value } => self
+                .load_index(field, value, max_rows)
                 .map(|iter| Box::new(iter) as Box<dyn Iterator<Item = DataRow>>),
         }?;
 
+        // skip expired
+        if self.skip_expired {
+            let now = self.now;
+            return Ok(Box::new(
+                rows.filter(move |row| !row.value.metadata.is_expired(now)),
+            ));
+        }
+
         Ok(rows)
     }
 
@@ -329,28 +751,305 @@ where
     }
 
     // load_many
-    fn load_many(&self, cks: &[Vec<String>]) -> Result<impl Iterator<Item = DataRow>, Error> {
+    fn load_many(
+        &self,
+        cks: &[Vec<String>],
+        max_rows: Option<usize>,
+    ) -> Result<impl Iterator<Item = DataRow>, Error> {
+        Self::check_max_rows(cks.len(), max_rows)?;
         let rows: Result<Vec<_>, _> = cks.iter().map(|ck| self.by_ck(ck)).collect();
 
         rows.map(Vec::into_iter)
     }
 
     // load_prefix
-    fn load_prefix(&self, prefix: &[String]) -> Result<impl Iterator<Item = DataRow>, Error> {
-        let start = E::composite_key(prefix)?;
-        let start_sk = self.resolver.data_key(&start)?;
-        let end_sk = start_sk.create_upper_bound();
+    fn load_prefix(
+        &self,
+        prefix: &[String],
+        max_rows: Option<usize>,
+    ) -> Result<impl Iterator<Item = DataRow>, Error> {
+        let (start_sk, end_sk) = self.prefix_bounds(prefix)?;
 
-        self.by_range(start_sk, end_sk)
+        self.by_range(start_sk, end_sk, max_rows)
     }
 
     // load_range
-    // composite keys not allowed as B-Trees are one dimensional for lookups
     fn load_range(
         &self,
         start: &[String],
         end: &[String],
+        max_rows: Option<usize>,
+    ) -> Result<impl Iterator<Item = DataRow>, Error> {
+        let (start_sk, end_sk) = self.range_bounds(start, end)?;
+
+        // create iter over entire alphabetical range
+        let iter = self.by_range(start_sk, end_sk, max_rows)?;
+
+        Ok(iter)
+    }
+
+    // load_index
+    // the keys are checked against max_rows before any row is loaded
+    fn load_index(
+        &self,
+        field: &str,
+        value: &[u8],
+        max_rows: Option<usize>,
     ) -> Result<impl Iterator<Item = DataRow>, Error> {
+        let keys = self.index_lookup(field, value)?;
+        Self::check_max_rows(keys.len(), max_rows)?;
+        let rows: Result<Vec<_>, _> = keys.into_iter().map(|key| self.by_key(key)).collect();
+
+        rows.map(Vec::into_iter)
+    }
+
+    // check_max_rows
+    fn check_max_rows(rows: usize, max_rows: Option<usize>) -> Result<(), Error> {
+        match max_rows {
+            Some(max) if rows > max => Err(LoadError::OrderTooManyRows { max })?,
+            _ => Ok(()),
+        }
+    }
+
+    ///
+    /// PRIVATE PAGE METHODS
+    ///
+
+    // page_range
+    fn page_range(&self, start: DataKey, end: DataKey) -> Result<LoadPage, Error> {
+        self.debug
+            .println(&format!("store.range (page): {start} -> {end}"));
+
+        let store_path = self.resolver.store()?;
+        let page = self.db.with_store(&store_path, |store| {
+            let iter = store
+                .data
+                .range((self.start_bound(start), Bound::Included(end)))
+                .map(|(key, value)| DataRow { key, value });
+
+            Ok(self.paginate(iter))
+        })?;
+
+        Ok(page)
+    }
+
+    // paginate
+    // skips the offset, then takes up to limit rows after the cursor, and
+    // peeks one more row to know if there is a next page
+    fn paginate<I>(&self, iter: I) -> LoadPage
+    where
+        I: Iterator<Item = DataRow>,
+    {
+        let mut iter = iter
+            .filter(|row| {
+                self.cursor
+                    .as_ref()
+                    .map_or(true, |cursor| row.key > *cursor)
+                    && self.is_live(&row.value.metadata)
+            })
+            .skip(self.offset as usize)
+            .peekable();
+
+        let mut rows = Vec::new();
+        while self.limit.map_or(true, |limit| rows.len() < limit as usize) {
+            match iter.next() {
+                Some(row) => rows.push(QueryRow::from(row)),
+                None => break,
+            }
+        }
+
+        let next_cursor = match iter.peek() {
+            Some(_) => rows.last().map(|row| row.key.clone()),
+            None => None,
+        };
+
+        LoadPage { rows, next_cursor }
+    }
+
+    ///
+    /// PRIVATE COUNT METHODS
+    ///
+
+    // count_range
+    // the stable BTreeMap range has no key only form, so every value in the
+    // range is still read and decoded, it just never becomes an entity
+    fn count_range(&self, start: DataKey, end: DataKey) -> Result<u64, Error> {
+        self.debug
+            .println(&format!("store.range (count): {start} -> {end}"));
+
+        let store_path = self.resolver.store()?;
+        let count = self.db.with_store(&store_path, |store| {
+            let iter = store
+                .data
+                .range((self.start_bound(start), Bound::Included(end)));
+
+            if self.skip_expired {
+                Ok(iter
+                    .filter(|(_, value)| self.is_live(&value.metadata))
+                    .count())
+            } else {
+                Ok(iter.count())
+            }
+        })?;
+
+        Ok(count as u64)
+    }
+
+    // count_keys
+    fn count_keys(&self, cks: &[Vec<String>]) -> Result<u64, Error> {
+        let keys = cks
+            .iter()
+            .map(|ck| self.resolver.data_key(ck))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let store_path = self.resolver.store()?;
+        let count = self.db.with_store(&store_path, |store| {
+            Ok(keys
+                .iter()
+                .filter(|key| {
+                    self.cursor.as_ref().map_or(true, |cursor| *key > cursor)
+                        && self.contains_live(store, key)
+                })
+                .count())
+        })?;
+
+        Ok(count as u64)
+    }
+
+    // count_index
+    fn count_index(&self, field: &str, value: &[u8]) -> Result<u64, Error> {
+        let keys = self.index_lookup(field, value)?;
+
+        let store_path = self.resolver.store()?;
+        let count = self.db.with_store(&store_path, |store| {
+            Ok(keys
+                .iter()
+                .filter(|key| {
+                    self.cursor.as_ref().map_or(true, |cursor| *key > cursor)
+                        && self.contains_live(store, key)
+                })
+                .count())
+        })?;
+
+        Ok(count as u64)
+    }
+
+    ///
+    /// PRIVATE AGGREGATE METHODS
+    ///
+
+    // aggregate_range
+    fn aggregate_range(
+        &self,
+        field: &str,
+        start: DataKey,
+        end: DataKey,
+    ) -> Result<Aggregate, Error> {
+        self.debug
+            .println(&format!("store.range (aggregate): {start} -> {end}"));
+
+        let store_path = self.resolver.store()?;
+        let aggregate = self.db.with_store(&store_path, |store| {
+            let iter = store
+                .data
+                .range((self.start_bound(start), Bound::Included(end)))
+                .map(|(key, value)| DataRow { key, value });
+
+            Ok(self.aggregate_rows(field, iter))
+        })??;
+
+        Ok(aggregate)
+    }
+
+    // aggregate_rows
+    // applies the cursor, offset and limit then accumulates one row at a time
+    fn aggregate_rows<I>(&self, field: &str, iter: I) -> Result<Aggregate, Error>
+    where
+        I: Iterator<Item = DataRow>,
+    {
+        let iter = iter
+            .filter(|row| {
+                self.cursor
+                    .as_ref()
+                    .map_or(true, |cursor| row.key > *cursor)
+                    && self.is_live(&row.value.metadata)
+            })
+            .skip(self.offset as usize)
+            .take(self.limit.map_or(usize::MAX, |limit| limit as usize));
+
+        let mut aggregate = Aggregate::default();
+        for row in iter {
+            let mut fields = orm::deserialize_fields(&row.value.data, &[field])?;
+            if let Some(value) = fields.remove(field) {
+                if let Some(n) = Self::numeric(field, &value)? {
+                    aggregate.push(n);
+                }
+            }
+        }
+
+        Ok(aggregate)
+    }
+
+    // numeric
+    // numbers that were serialized as text, ie. decimals without serde-float,
+    // are parsed back before conversion
+    fn numeric(field: &str, value: &Value) -> Result<Option<f64>, Error> {
+        let not_numeric = || LoadError::AggregateNotNumeric {
+            field: field.to_string(),
+        };
+
+        let n = match value {
+            Value::Null => return Ok(None),
+            Value::Integer(int) => i128::from(*int).to_f64(),
+            Value::Float(float) => float.to_f64(),
+            Value::Text(text) => text.parse::<Decimal>().map_err(|_| not_numeric())?.to_f64(),
+            Value::Tag(_, inner) => return Self::numeric(field, inner),
+            _ => None,
+        };
+
+        n.map(Some).ok_or_else(|| not_numeric().into())
+    }
+
+    ///
+    /// HELPERS
+    ///
+
+    // is_filtered
+    // filters need the deserialized entity
+    fn is_filtered(&self) -> bool {
+        self.filter.is_some() || !self.predicates.is_empty()
+    }
+
+    // is_live
+    fn is_live(&self, metadata: &Metadata) -> bool {
+        !self.skip_expired || !metadata.is_expired(self.now)
+    }
+
+    // contains_live
+    // only loads the value when expired rows have to be skipped
+    fn contains_live(&self, store: &Store, key: &DataKey) -> bool {
+        if self.skip_expired {
+            store
+                .data
+                .get(key)
+                .is_some_and(|value| self.is_live(&value.metadata))
+        } else {
+            store.data.contains_key(key)
+        }
+    }
+
+    // prefix_bounds
+    fn prefix_bounds(&self, prefix: &[String]) -> Result<(DataKey, DataKey), Error> {
+        let start = E::composite_key(prefix)?;
+        let start_sk = self.resolver.data_key(&start)?;
+        let end_sk = start_sk.create_upper_bound();
+
+        Ok((start_sk, end_sk))
+    }
+
+    // range_bounds
+    // composite keys not allowed as B-Trees are one dimensional for lookups
+    fn range_bounds(&self, start: &[String], end: &[String]) -> Result<(DataKey, DataKey), Error> {
         let start = E::composite_key(start)?;
         if start.len() != 1 {
             Err(LoadError::RangeNotAllowed)?;
@@ -360,39 +1059,76 @@ where
         let end = E::composite_key(end)?;
         let end_sk = self.resolver.data_key(&end)?;
 
-        // create iter over entire alphabetical range
-        let iter = self.by_range(start_sk, end_sk)?;
-
-        Ok(iter)
+        Ok((start_sk, end_sk))
     }
 
-    ///
-    /// HELPERS
-    ///
+    // start_bound
+    // the cursor replaces the start of the range if it's further along
+    fn start_bound(&self, start: DataKey) -> Bound<DataKey> {
+        match &self.cursor {
+            Some(cursor) if *cursor >= start => Bound::Excluded(cursor.clone()),
+            _ => Bound::Included(start),
+        }
+    }
 
     // by_range
+    // stops reading as soon as there are more than max_rows live rows
     fn by_range(
         &self,
         start: DataKey,
         end: DataKey,
+        max_rows: Option<usize>,
     ) -> Result<impl Iterator<Item = DataRow>, Error> {
         self.debug
             .println(&format!("store.range: {start} -> {end}"));
 
         // iterate range
         let mut results = Vec::new();
+        let mut live = 0;
         let store_path = self.resolver.store()?;
         self.db.with_store(&store_path, |store| {
             for (key, value) in store.data.range(start..=end) {
+                if self.is_live(&value.metadata) {
+                    live += 1;
+                    if max_rows.is_some_and(|max| live > max) {
+                        break;
+                    }
+                }
                 results.push(DataRow { key, value });
             }
 
             Ok(())
         })?;
+        Self::check_max_rows(live, max_rows)?;
 
         Ok(results.into_iter())
     }
 
+    // index_lookup
+    // range scan over the index rows for the value, returning the primary keys
+    fn index_lookup(&self, field: &str, value: &[u8]) -> Result<Vec<DataKey>, Error> {
+        if !self.resolver.indexes()?.iter().any(|f| f == field) {
+            Err(IndexError::IndexNotFound {
+                field: field.to_string(),
+            })?;
+        }
+
+        let (start, end) = index_bounds(&self.resolver.entity, field, value);
+        self.debug
+            .println(&format!("store.range (index): {start} -> {end}"));
+
+        let store_path = self.resolver.store()?;
+        let keys = self.db.with_store(&store_path, |store| {
+            Ok(store
+                .data
+                .range((Bound::Included(start), Bound::Excluded(end)))
+                .map(|(key, _)| primary_key(&key))
+                .collect())
+        })?;
+
+        Ok(keys)
+    }
+
     // by_ck
     fn by_ck(&self, ck: &[String]) -> Result<DataRow, Error> {
         let key = self.resolver.data_key(ck)?;
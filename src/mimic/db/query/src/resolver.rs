@@ -2,7 +2,8 @@ use crate::Error;
 use candid::CandidType;
 use core_schema::get_schema;
 use db::DataKey;
-use orm_schema::node::Entity;
+use orm::traits::Path;
+use orm_schema::node::{Entity, Item};
 use serde::{Deserialize, Serialize};
 use snafu::Snafu;
 
@@ -15,6 +16,11 @@ pub enum ResolverError {
     #[snafu(display("entity not found: {path}"))]
     EntityNotFound { path: String },
 
+    #[snafu(display(
+        "relation '{field}' can't be checked, {path} has sort keys that one ulid can't make"
+    ))]
+    RelationHasSortKeys { field: String, path: String },
+
     #[snafu(transparent)]
     Schema { source: core_schema::Error },
 }
@@ -32,7 +38,8 @@ impl ResolverError {
 /// Resolver
 ///
 /// based on the entity path knows the store and how to generate
-/// a sort key
+/// a sort key, the save, load and delete builders all go through it
+/// so application code can use it to derive the same keys
 ///
 
 pub struct Resolver {
@@ -40,6 +47,8 @@ pub struct Resolver {
 }
 
 impl Resolver {
+    // new
+    // entity is the schema path, ie. E::path()
     #[must_use]
     pub fn new(entity: &str) -> Self {
         Self {
@@ -47,7 +56,14 @@ impl Resolver {
         }
     }
 
+    // for_entity
+    #[must_use]
+    pub fn for_entity<E: Path>() -> Self {
+        Self::new(&E::path())
+    }
+
     // store
+    // the path of the store the entity's rows live in
     pub fn store(&self) -> Result<String, Error> {
         let schema = get_schema().map_err(ResolverError::from)?;
         let entity = schema
@@ -57,7 +73,91 @@ impl Resolver {
         Ok(entity.store.clone())
     }
 
+    // indexes
+    // the fields that have a secondary index declared in the schema
+    pub fn indexes(&self) -> Result<Vec<String>, Error> {
+        let schema = get_schema().map_err(ResolverError::from)?;
+        let entity = schema
+            .get_node::<Entity>(&self.entity)
+            .ok_or_else(|| ResolverError::entity_not_found(&self.entity))?;
+
+        Ok(entity.indexes.iter().map(|i| i.field.clone()).collect())
+    }
+
+    // unique_indexes
+    // the indexed fields that no two rows can share a value in
+    pub fn unique_indexes(&self) -> Result<Vec<String>, Error> {
+        let schema = get_schema().map_err(ResolverError::from)?;
+        let entity = schema
+            .get_node::<Entity>(&self.entity)
+            .ok_or_else(|| ResolverError::entity_not_found(&self.entity))?;
+
+        Ok(entity
+            .indexes
+            .iter()
+            .filter(|i| i.unique)
+            .map(|i| i.field.clone())
+            .collect())
+    }
+
+    // relations
+    // (field, target entity) for each relation field with check_relation set,
+    // a target with sort keys is an error as one ulid can't make its key
+    pub fn relations(&self) -> Result<Vec<(String, String)>, Error> {
+        let schema = get_schema().map_err(ResolverError::from)?;
+        let entity = schema
+            .get_node::<Entity>(&self.entity)
+            .ok_or_else(|| ResolverError::entity_not_found(&self.entity))?;
+
+        let mut relations = Vec::new();
+        for field in entity.fields.fields.iter().filter(|f| f.check_relation) {
+            let Item::Relation(rel) = &field.value.item else {
+                continue;
+            };
+            let target = schema
+                .get_node::<Entity>(&rel.path)
+                .ok_or_else(|| ResolverError::entity_not_found(&rel.path))?;
+            if !target.sort_keys.is_empty() {
+                Err(ResolverError::RelationHasSortKeys {
+                    field: field.name.clone(),
+                    path: rel.path.clone(),
+                })?;
+            }
+
+            relations.push((field.name.clone(), rel.path.clone()));
+        }
+
+        Ok(relations)
+    }
+
+    // owned_children
+    // (child entity, field) for every owned relation that points at this entity
+    pub fn owned_children(&self) -> Result<Vec<(String, String)>, Error> {
+        let schema = get_schema().map_err(ResolverError::from)?;
+
+        let children = schema
+            .get_nodes::<Entity>()
+            .flat_map(|(path, child)| {
+                child
+                    .fields
+                    .fields
+                    .iter()
+                    .filter_map(move |field| match &field.value.item {
+                        Item::Relation(rel) if field.owned && rel.path == self.entity => {
+                            Some((path.to_string(), field.name.clone()))
+                        }
+                        _ => None,
+                    })
+            })
+            .collect();
+
+        Ok(children)
+    }
+
     // data_key
+    // the composite key (sort key values then primary key values, as
+    // composite_key_dyn returns them) as the DataKey used in the store,
+    // a short ck gives the prefix that every longer key starts with
     pub fn data_key(&self, ck: &[String]) -> Result<DataKey, Error> {
         let chain_format = self.chain_format()?;
 
@@ -81,8 +181,9 @@ impl Resolver {
     }
 
     // chain_format
-    // returns the data used to format the sort key
-    fn chain_format(&self) -> Result<Vec<(String, usize)>, Error> {
+    // returns the data used to format the sort key, one (part, number of keys)
+    // per entity in the sort key chain ending with this entity
+    pub fn chain_format(&self) -> Result<Vec<(String, usize)>, Error> {
         let schema = get_schema().map_err(ResolverError::from)?;
 
         //
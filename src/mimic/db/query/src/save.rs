@@ -1,14 +1,21 @@
 use crate::{
+    index::{index_bounds, index_keys, primary_key, sequence_key},
     types::{EntityRow, QueryRow},
     DebugContext, Error, Resolver,
 };
 use candid::CandidType;
-use db::{DataKey, DataRow, DataValue, Db, Metadata};
-use orm::traits::{Entity, EntityDynamic};
+use core_state::CanisterStateManager;
+use db::{DataKey, DataRow, DataValue, Db, ImportPolicy, ImportSummary, Metadata};
+use orm::{
+    traits::{Entity, EntityDynamic},
+    types::KeyStrategy,
+    Value,
+};
 use serde::{Deserialize, Serialize};
 use snafu::Snafu;
-use std::mem;
+use std::{fmt, mem, ops::Bound, time::Duration};
 use strum::Display;
+use types::{ErrorTree, FieldErrors};
 
 ///
 /// SaveError
@@ -16,6 +23,15 @@ use strum::Display;
 
 #[derive(CandidType, Debug, Serialize, Deserialize, Snafu)]
 pub enum SaveError {
+    #[snafu(display("condition failed on {field}: {key}"))]
+    ConditionFailed { key: DataKey, field: String },
+
+    #[snafu(display("dangling reference in {field}: {target_key}"))]
+    DanglingReference { field: String, target_key: DataKey },
+
+    #[snafu(display("field not found: {field}"))]
+    FieldNotFound { field: String },
+
     #[snafu(display("key exists: {key}"))]
     KeyExists { key: DataKey },
 
@@ -24,6 +40,19 @@ pub enum SaveError {
 
     #[snafu(display("no results found"))]
     NoResultsFound,
+
+    #[snafu(display("canister is read only"))]
+    ReadOnly,
+
+    #[snafu(display("unique violation on {field}: {value}"))]
+    UniqueViolation { field: String, value: String },
+
+    #[snafu(display("validation failed for {path} ({key}): {errors}"))]
+    Validation {
+        key: DataKey,
+        path: String,
+        errors: FieldErrors,
+    },
 }
 
 ///
@@ -34,20 +63,109 @@ pub enum SaveError {
 /// Update  : will only change an existing row
 ///
 
-#[derive(Display)]
+#[derive(CandidType, Clone, Copy, Debug, Display, Eq, PartialEq, Serialize, Deserialize)]
 pub enum SaveMode {
     Create,
     Replace,
     Update,
 }
 
+///
+/// SaveOutcome
+///
+/// what a save did to one row, created tells an upsert (a Replace) that
+/// wrote a new row apart from one that overwrote an existing row
+///
+
+#[derive(CandidType, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SaveOutcome {
+    pub key: DataKey,
+    pub mode: SaveMode,
+    pub created: bool,
+}
+
+///
+/// SaveStatus
+///
+/// PartialComplete : the instruction budget ran out, the entities that
+/// weren't saved are handed back untouched so they can be saved in a
+/// later message
+///
+
+pub enum SaveStatus {
+    Complete,
+    PartialComplete(Vec<Box<dyn EntityDynamic>>),
+}
+
+impl SaveStatus {
+    #[must_use]
+    pub const fn is_complete(&self) -> bool {
+        matches!(self, Self::Complete)
+    }
+}
+
+///
+/// SaveTimings
+///
+/// instructions spent in each phase of one save, the counter is only read
+/// in debug mode so otherwise every lap is 0
+///
+
+#[derive(Debug, Default)]
+struct SaveTimings {
+    mark: u64,
+    prepare: u64,
+    sanitize: u64,
+    validate: u64,
+    serialize: u64,
+    checks: u64,
+    write: u64,
+}
+
+impl SaveTimings {
+    fn start(debug: &DebugContext) -> Self {
+        Self {
+            mark: debug.instructions(),
+            ..Self::default()
+        }
+    }
+
+    // lap
+    // instructions since the previous lap
+    fn lap(&mut self, debug: &DebugContext) -> u64 {
+        let now = debug.instructions();
+        let lap = now.saturating_sub(self.mark);
+        self.mark = now;
+
+        lap
+    }
+}
+
+impl fmt::Display for SaveTimings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "prepare {} sanitize {} validate {} serialize {} checks {} write {}",
+            self.prepare, self.sanitize, self.validate, self.serialize, self.checks, self.write
+        )
+    }
+}
+
 ///
 /// SaveOptions
 ///
+/// instruction_budget : opt in, a batch stops before the next entity once
+/// the message has used this many instructions and the rest are handed back
+/// as SaveStatus::PartialComplete, the default None saves the whole batch
+///
 
 pub struct SaveOptions {
     pub sanitize: bool,
     pub validate: bool,
+    pub skip_unchanged: bool,
+    pub check_relations: bool,
+    pub preserve_ids: bool,
+    pub instruction_budget: Option<u64>,
 }
 
 impl Default for SaveOptions {
@@ -55,10 +173,45 @@ impl Default for SaveOptions {
         Self {
             sanitize: true,
             validate: true,
+            skip_unchanged: false,
+            check_relations: true,
+            preserve_ids: false,
+            instruction_budget: None,
         }
     }
 }
 
+///
+/// DEFAULT_INSTRUCTION_BUDGET
+/// three quarters of the 40B an update message may use, leaving the rest
+/// for whatever the caller does after the save, a budget to opt in with
+///
+
+pub const DEFAULT_INSTRUCTION_BUDGET: u64 = 30_000_000_000;
+
+///
+/// ValidateAsync
+///
+/// a validation step that can read the db, ie. to check a referenced row
+/// exists, only the *_async save methods run it so other saves stay sync
+///
+
+#[allow(async_fn_in_trait)]
+pub trait ValidateAsync {
+    async fn validate_async(&self, db: &Db, entity: &dyn EntityDynamic) -> Result<(), ErrorTree>;
+}
+
+///
+/// SaveCondition
+///
+/// the value a field of the stored row has to hold for the save to go ahead
+///
+
+pub struct SaveCondition {
+    pub field: String,
+    pub expected: Value,
+}
+
 ///
 /// SaveBuilderConfig
 ///
@@ -67,6 +220,8 @@ pub struct SaveBuilderConfig {
     mode: SaveMode,
     debug: DebugContext,
     options: SaveOptions,
+    ttl: Option<Duration>,
+    condition: Option<SaveCondition>,
 }
 
 ///
@@ -88,6 +243,8 @@ impl<'a> SaveBuilder<'a> {
                 mode,
                 debug: DebugContext::default(),
                 options: SaveOptions::default(),
+                ttl: None,
+                condition: None,
             },
         }
     }
@@ -99,6 +256,74 @@ impl<'a> SaveBuilder<'a> {
         self
     }
 
+    // debug_sink
+    // enables debug output and routes it to the sink instead of println
+    #[must_use]
+    pub fn debug_sink<F: Fn(&str) + 'static>(mut self, sink: F) -> Self {
+        self.config.debug.enable();
+        self.config.debug.set_sink(sink);
+        self
+    }
+
+    // skip_unchanged
+    // an Update or Replace that leaves the row as it was returns the stored
+    // row without writing to the store
+    #[must_use]
+    pub const fn skip_unchanged(mut self) -> Self {
+        self.config.options.skip_unchanged = true;
+        self
+    }
+
+    // skip_relation_check
+    // saves without looking up the rows that check_relation fields point to
+    #[must_use]
+    pub const fn skip_relation_check(mut self) -> Self {
+        self.config.options.check_relations = false;
+        self
+    }
+
+    // preserve_ids
+    // a Create keeps the primary keys it was given instead of generating
+    // them, for importing rows that already have ids, Sequential counters
+    // aren't advanced past the imported keys
+    #[must_use]
+    pub const fn preserve_ids(mut self) -> Self {
+        self.config.options.preserve_ids = true;
+        self
+    }
+
+    // instruction_budget
+    // None lets the batch run until it's done or the message traps
+    #[must_use]
+    pub const fn instruction_budget(mut self, budget: Option<u64>) -> Self {
+        self.config.options.instruction_budget = budget;
+        self
+    }
+
+    // with_ttl
+    // saved rows expire this long after now, an Update without a ttl
+    // keeps the expiry the row already had
+    #[must_use]
+    pub const fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.config.ttl = Some(ttl);
+        self
+    }
+
+    // replace_if
+    // turns the save into a Replace that only goes ahead when the stored
+    // row's field equals expected, a missing row never matches
+    pub fn replace_if<T: Serialize>(mut self, field: &str, expected: &T) -> Result<Self, Error> {
+        let expected: Value = orm::deserialize(&orm::serialize(expected)?)?;
+
+        self.config.mode = SaveMode::Replace;
+        self.config.condition = Some(SaveCondition {
+            field: field.to_string(),
+            expected,
+        });
+
+        Ok(self)
+    }
+
     // from_data
     pub fn from_data<E: Entity + 'static>(self, data: &[u8]) -> Result<SaveBuilderResult, Error> {
         let entity: E = orm::deserialize(data)?;
@@ -143,12 +368,86 @@ impl<'a> SaveBuilder<'a> {
         self.execute(entities)
     }
 
+    // patch
+    // loads the stored row, replaces one field and saves it back as an Update,
+    // so the patched entity is sanitized and validated like any other save
+    pub fn patch<E, K, T>(
+        mut self,
+        ck: &[K],
+        field: &str,
+        value: &T,
+    ) -> Result<SaveBuilderResult, Error>
+    where
+        E: Entity + 'static,
+        K: ToString,
+        T: Serialize,
+    {
+        let ck: Vec<String> = ck.iter().map(ToString::to_string).collect();
+        let resolver = Resolver::for_entity::<E>();
+        let key = resolver.data_key(&ck)?;
+        let store_path = resolver.store()?;
+        let old = self
+            .db
+            .with_store(&store_path, |store| Ok(store.get(&key)))?
+            .ok_or_else(|| SaveError::KeyNotFound { key: key.clone() })?;
+
+        // swap the field's value in the generic map
+        let mut map: Value = orm::deserialize(&old.data)?;
+        let field_value = map
+            .as_map_mut()
+            .and_then(|entries| entries.iter_mut().find(|(k, _)| k.as_text() == Some(field)))
+            .ok_or_else(|| SaveError::FieldNotFound {
+                field: field.to_string(),
+            })?;
+        field_value.1 = orm::deserialize(&orm::serialize(value)?)?;
+
+        // deserializing back into the entity checks the value's type
+        let entity: E = orm::deserialize(&orm::serialize(&map)?)?;
+        self.config.mode = SaveMode::Update;
+
+        self.execute(vec![Box::new(entity)])
+    }
+
+    // from_entity_async
+    pub async fn from_entity_async<E, V>(
+        self,
+        entity: E,
+        validator: &V,
+    ) -> Result<SaveBuilderResult, Error>
+    where
+        E: EntityDynamic + 'static,
+        V: ValidateAsync,
+    {
+        self.execute_async(vec![Box::new(entity)], validator).await
+    }
+
+    // from_entities_dynamic_async
+    pub async fn from_entities_dynamic_async<V: ValidateAsync>(
+        self,
+        entities: Vec<Box<dyn EntityDynamic>>,
+        validator: &V,
+    ) -> Result<SaveBuilderResult, Error> {
+        self.execute_async(entities, validator).await
+    }
+
     // execute
     fn execute(self, entities: Vec<Box<dyn EntityDynamic>>) -> Result<SaveBuilderResult, Error> {
         let mut executor = SaveBuilderExecutor::new(self, entities);
         let results = executor.execute()?;
 
-        Ok(SaveBuilderResult::new(results))
+        Ok(executor.into_result(results))
+    }
+
+    // execute_async
+    async fn execute_async<V: ValidateAsync>(
+        self,
+        entities: Vec<Box<dyn EntityDynamic>>,
+        validator: &V,
+    ) -> Result<SaveBuilderResult, Error> {
+        let mut executor = SaveBuilderExecutor::new(self, entities);
+        let results = executor.execute_async(validator).await?;
+
+        Ok(executor.into_result(results))
     }
 }
 
@@ -173,36 +472,144 @@ impl<'a> SaveBuilderExecutor<'a> {
     }
 
     // execute
-    pub fn execute(&mut self) -> Result<Vec<DataRow>, Error> {
+    // stops early if the instruction budget is used up, the entities that
+    // weren't saved are left in self.entities, at least one is always saved
+    // so a resumed batch can't stall
+    pub fn execute(&mut self) -> Result<Vec<(DataRow, SaveOutcome)>, Error> {
         // Temporarily take the entities out of self to avoid multiple mutable borrows
-        let mut entities = mem::take(&mut self.entities);
+        let mut entities = mem::take(&mut self.entities).into_iter();
 
         // get results
         let mut results = Vec::new();
+        for mut entity in entities.by_ref() {
+            results.push(self.execute_one(&mut *entity)?);
+
+            if self.over_budget() {
+                break;
+            }
+        }
+        self.entities = entities.collect();
+
+        Ok(results)
+    }
+
+    // into_result
+    // whatever the executor didn't get to is returned as PartialComplete
+    fn into_result(self, results: Vec<(DataRow, SaveOutcome)>) -> SaveBuilderResult {
+        let mut result = SaveBuilderResult::new(results);
+        if !self.entities.is_empty() {
+            result.status = SaveStatus::PartialComplete(self.entities);
+        }
+
+        result
+    }
+
+    // over_budget
+    fn over_budget(&self) -> bool {
+        self.config
+            .options
+            .instruction_budget
+            .is_some_and(|budget| instruction_counter() >= budget)
+    }
+
+    // execute_async
+    // as execute, with the validator run between the sync validators and
+    // the write, so it can look up other rows before anything changes
+    // the validator may await, so the batch spans messages and isn't held
+    // to the instruction budget
+    pub async fn execute_async<V: ValidateAsync>(
+        &mut self,
+        validator: &V,
+    ) -> Result<Vec<(DataRow, SaveOutcome)>, Error> {
+        let mut entities = mem::take(&mut self.entities);
+
+        let debug = &self.config.debug;
+        let mut results = Vec::new();
         for entity in &mut entities {
-            let data_row = self.execute_one(&mut **entity)?;
-            results.push(data_row);
+            let mut timings = SaveTimings::start(debug);
+            let key = self.prepare(&mut **entity, &mut timings)?;
+
+            if self.config.options.validate {
+                validator
+                    .validate_async(self.db, &**entity)
+                    .await
+                    .map_err(|errors| SaveError::Validation {
+                        key: key.clone(),
+                        path: entity.path_dyn(),
+                        errors: errors.into(),
+                    })?;
+                timings.validate += timings.lap(debug);
+            }
+
+            let result = self.write(&**entity, key, &mut timings)?;
+            self.print_timings(&result.1.key, &timings);
+            results.push(result);
         }
 
         Ok(results)
     }
 
     // execute_one
-    fn execute_one(&self, entity: &mut dyn EntityDynamic) -> Result<DataRow, Error> {
+    fn execute_one(&self, entity: &mut dyn EntityDynamic) -> Result<(DataRow, SaveOutcome), Error> {
+        let mut timings = SaveTimings::start(&self.config.debug);
+        let key = self.prepare(entity, &mut timings)?;
+        let result = self.write(entity, key, &mut timings)?;
+        self.print_timings(&result.1.key, &timings);
+
+        Ok(result)
+    }
+
+    // print_timings
+    // formatted only in debug mode
+    fn print_timings(&self, key: &DataKey, timings: &SaveTimings) {
+        if self.config.debug.is_enabled() {
+            self.config
+                .debug
+                .println(&format!("store.timings: {key}: {timings}"));
+        }
+    }
+
+    // prepare
+    // mutates and validates the entity, returning the key it will be saved under
+    // every save goes through here, so this is where read only is enforced
+    fn prepare(
+        &self,
+        entity: &mut dyn EntityDynamic,
+        timings: &mut SaveTimings,
+    ) -> Result<DataKey, Error> {
+        if CanisterStateManager::is_read_only() {
+            Err(SaveError::ReadOnly)?;
+        }
+
         let mode = &self.config.mode;
+        let debug = &self.config.debug;
 
         //
         // firstly mutate the entity so the ids are generated
         // and relevant data is sanitized
+        // order : on_create + generate_ids / on_update, sanitize, validate
         //
 
-        if matches!(mode, SaveMode::Create) {
-            entity.on_create();
+        match mode {
+            SaveMode::Create => {
+                entity.on_create();
+                if !self.config.options.preserve_ids {
+                    entity.generate_ids();
+                    if entity.key_strategy_dyn() == KeyStrategy::Sequential {
+                        let seq = self.next_sequence(&entity.path_dyn())?;
+                        entity.set_sequence_dyn(seq);
+                    }
+                }
+            }
+            SaveMode::Update | SaveMode::Replace => entity.on_update(),
         }
+        timings.prepare = timings.lap(debug);
+
         if self.config.options.sanitize {
             let mut adapter = orm::visit::EntityAdapterMut(entity);
             orm::sanitize(&mut adapter);
         }
+        timings.sanitize = timings.lap(debug);
 
         //
         // build key / value
@@ -214,7 +621,7 @@ impl<'a> SaveBuilderExecutor<'a> {
 
         // debug
         // (before validation so we can see what the entity is)
-        self.config.debug.println(&format!(
+        debug.println(&format!(
             "store.{}: {}",
             mode.to_string().to_lowercase(),
             key
@@ -223,33 +630,70 @@ impl<'a> SaveBuilderExecutor<'a> {
         // validate
         if self.config.options.validate {
             let adapter = orm::visit::EntityAdapter(entity);
-            orm::validate(&adapter)?;
+            orm::validate_fields(&adapter).map_err(|errors| SaveError::Validation {
+                key: key.clone(),
+                path: entity.path_dyn(),
+                errors,
+            })?;
         }
+        timings.validate = timings.lap(debug);
+
+        Ok(key)
+    }
+
+    // write
+    fn write(
+        &self,
+        entity: &dyn EntityDynamic,
+        key: DataKey,
+        timings: &mut SaveTimings,
+    ) -> Result<(DataRow, SaveOutcome), Error> {
+        let mode = &self.config.mode;
+        let debug = &self.config.debug;
+        let resolver = Resolver::new(&entity.path_dyn());
 
         // serialize
+        // rows are stored as CBOR, serialize_dyn goes through orm::serialize
+        // which is lib_cbor, the same crate mimic_common::cbor re-exports
         let data: Vec<u8> = entity.serialize_dyn()?;
+        timings.serialize = timings.lap(debug);
+
+        // relations
+        let now = types::Timestamp::now();
+        if self.config.options.check_relations {
+            self.check_relations(&resolver, &data, now)?;
+        }
 
         //
         // match mode
         // on Update and Replace compare old and new data
         //
 
-        let now = types::Timestamp::now();
         let store_path = resolver.store()?;
         let result = self
             .db
             .with_store(&store_path, |store| Ok(store.get(&key)))?;
 
-        let (created, modified) = match mode {
+        // condition
+        if let Some(condition) = &self.config.condition {
+            self.check_condition(condition, result.as_ref(), &key, now)?;
+        }
+
+        let expires_at = self.config.ttl.and_then(|ttl| now.checked_add(ttl));
+        let (created, modified, expires_at) = match mode {
             SaveMode::Create => {
-                if result.is_some() {
+                // expired rows count as absent
+                if result
+                    .as_ref()
+                    .is_some_and(|old| !old.metadata.is_expired(now))
+                {
                     Err(SaveError::KeyExists { key: key.clone() })?;
                 }
 
-                (now, now)
+                (now, now, expires_at)
             }
 
-            SaveMode::Update => match result {
+            SaveMode::Update => match &result {
                 Some(old) => {
                     let modified = if data == old.data {
                         old.metadata.modified
@@ -257,12 +701,16 @@ impl<'a> SaveBuilderExecutor<'a> {
                         now
                     };
 
-                    (old.metadata.created, modified)
+                    (
+                        old.metadata.created,
+                        modified,
+                        expires_at.or(old.metadata.expires_at),
+                    )
                 }
                 None => Err(SaveError::KeyNotFound { key: key.clone() })?,
             },
 
-            SaveMode::Replace => match result {
+            SaveMode::Replace => match &result {
                 Some(old) => {
                     let modified = if data == old.data {
                         old.metadata.modified
@@ -270,27 +718,357 @@ impl<'a> SaveBuilderExecutor<'a> {
                         now
                     };
 
-                    (old.metadata.created, modified)
+                    (old.metadata.created, modified, expires_at)
                 }
-                None => (now, now),
+                None => (now, now, expires_at),
             },
         };
 
+        // unique indexes
+        self.check_unique(&resolver, &store_path, &data, &key, now)?;
+        timings.checks = timings.lap(debug);
+
+        // expired rows count as absent here too
+        let outcome = SaveOutcome {
+            key: key.clone(),
+            mode: *mode,
+            created: result
+                .as_ref()
+                .map_or(true, |old| old.metadata.is_expired(now)),
+        };
+
+        // skip unchanged
+        if self.config.options.skip_unchanged && !matches!(mode, SaveMode::Create) {
+            if let Some(old) = &result {
+                if old.data == data && old.metadata.expires_at == expires_at {
+                    debug.println(&format!("store.unchanged: {key}"));
+
+                    return Ok((DataRow::new(key, old.clone()), outcome));
+                }
+            }
+        }
+
         // insert data
         let value = DataValue {
             data,
-            metadata: Metadata { created, modified },
+            metadata: Metadata {
+                created,
+                modified,
+                expires_at,
+            },
         };
-        self.db.with_store_mut(&store_path, |store| {
+        self.store_row(&resolver, &store_path, &key, &value, result.as_ref())?;
+        timings.write = timings.lap(debug);
+
+        // data row to return
+        let result = DataRow::new(key, value);
+
+        Ok((result, outcome))
+    }
+
+    // store_row
+    // writes the row along with its secondary index rows, the index rows
+    // of the row it replaces are removed first
+    fn store_row(
+        &self,
+        resolver: &Resolver,
+        store_path: &str,
+        key: &DataKey,
+        value: &DataValue,
+        old: Option<&DataValue>,
+    ) -> Result<(), Error> {
+        let indexes = resolver.indexes()?;
+        let old_index_keys = match old {
+            Some(old) => index_keys(&resolver.entity, &indexes, &old.data, key)?,
+            None => Vec::new(),
+        };
+        let new_index_keys = index_keys(&resolver.entity, &indexes, &value.data, key)?;
+
+        let index_value = DataValue {
+            data: Vec::new(),
+            metadata: value.metadata.clone(),
+        };
+        self.db.with_store_mut(store_path, |store| {
+            for index_key in &old_index_keys {
+                store.data.remove(index_key);
+            }
+            for index_key in new_index_keys {
+                store.data.insert(index_key, index_value.clone());
+            }
             store.data.insert(key.clone(), value.clone());
 
             Ok(())
         })?;
 
-        // data row to return
-        let result = DataRow::new(key, value);
+        Ok(())
+    }
 
-        Ok(result)
+    // import
+    // writes raw rows back as they were exported, metadata included, with
+    // the policy deciding what happens to keys already in the store
+    // nothing is generated, sanitized or validated, but the rows go through
+    // the same unique checks and index upkeep as any other save
+    // every key has to resolve to an entity before the first write so a bad
+    // key, or with ImportPolicy::Fail one that's already there, leaves the
+    // store untouched, a row that breaks a unique index counts as failed
+    // index rows are rebuilt so any passed in are ignored, and a sequence
+    // counter only moves forward so it never hands out an imported key
+    pub fn import(
+        &self,
+        rows: Vec<(DataKey, DataValue)>,
+        policy: ImportPolicy,
+    ) -> Result<ImportSummary, Error> {
+        if CanisterStateManager::is_read_only() {
+            Err(SaveError::ReadOnly)?;
+        }
+
+        let mut resolved = Vec::with_capacity(rows.len());
+        let mut sequences = Vec::new();
+        for (key, value) in rows {
+            key.validate()?;
+            if key.is_index() {
+                continue;
+            }
+            if let Some(entity) = key.sequence_entity() {
+                let store_path = Resolver::new(entity).store()?;
+                sequences.push((store_path, key, value));
+                continue;
+            }
+
+            let resolver = Resolver::new(key.parts().last().map_or("", |(path, _)| path));
+            let store_path = resolver.store()?;
+            if policy == ImportPolicy::Fail
+                && self
+                    .db
+                    .with_store(&store_path, |store| Ok(store.get(&key)))?
+                    .is_some()
+            {
+                Err(SaveError::KeyExists { key: key.clone() })?;
+            }
+            resolved.push((resolver, store_path, key, value));
+        }
+
+        let now = types::Timestamp::now();
+        let mut summary = ImportSummary::default();
+        for (resolver, store_path, key, value) in resolved {
+            let old = self
+                .db
+                .with_store(&store_path, |store| Ok(store.get(&key)))?;
+
+            if old.is_some() && policy == ImportPolicy::Skip {
+                summary.skipped += 1;
+                continue;
+            }
+
+            match self.check_unique(&resolver, &store_path, &value.data, &key, now) {
+                Ok(()) => {}
+                Err(Error::Save {
+                    source: SaveError::UniqueViolation { .. },
+                }) => {
+                    summary.failed += 1;
+                    continue;
+                }
+                Err(e) => return Err(e),
+            }
+
+            self.store_row(&resolver, &store_path, &key, &value, old.as_ref())?;
+            summary.inserted += 1;
+        }
+
+        for (store_path, key, value) in sequences {
+            let imported = orm::deserialize::<u64>(&value.data)?;
+            let current = self
+                .db
+                .with_store(&store_path, |store| Ok(store.get(&key)))?
+                .map(|value| orm::deserialize::<u64>(&value.data))
+                .transpose()?
+                .unwrap_or_default();
+
+            if imported > current {
+                self.db.with_store_mut(&store_path, |store| {
+                    store.data.insert(key, value);
+
+                    Ok(())
+                })?;
+            }
+        }
+
+        Ok(summary)
+    }
+
+    // check_condition
+    // an absent field reads as null, so a null expected value matches it
+    fn check_condition(
+        &self,
+        condition: &SaveCondition,
+        old: Option<&DataValue>,
+        key: &DataKey,
+        now: types::Timestamp,
+    ) -> Result<(), Error> {
+        let matched = match old {
+            Some(old) if !old.metadata.is_expired(now) => {
+                let values = orm::deserialize_fields(&old.data, &[&condition.field])?;
+
+                values.get(&condition.field).unwrap_or(&Value::Null) == &condition.expected
+            }
+            _ => false,
+        };
+
+        if !matched {
+            Err(SaveError::ConditionFailed {
+                key: key.clone(),
+                field: condition.field.clone(),
+            })?;
+        }
+
+        Ok(())
+    }
+
+    // check_relations
+    // every key held in a check_relation field has to be a live row
+    fn check_relations(
+        &self,
+        resolver: &Resolver,
+        data: &[u8],
+        now: types::Timestamp,
+    ) -> Result<(), Error> {
+        let relations = resolver.relations()?;
+        if relations.is_empty() {
+            return Ok(());
+        }
+
+        let names: Vec<&str> = relations.iter().map(|(field, _)| field.as_str()).collect();
+        let values = orm::deserialize_fields(data, &names)?;
+
+        for (field, target) in &relations {
+            let Some(value) = values.get(field) else {
+                continue;
+            };
+            let target_resolver = Resolver::new(target);
+            let store_path = target_resolver.store()?;
+
+            for id in relation_keys(value) {
+                let target_key = target_resolver.data_key(&[id])?;
+                let exists = self.db.with_store(&store_path, |store| {
+                    Ok(store
+                        .get(&target_key)
+                        .is_some_and(|value| !value.metadata.is_expired(now)))
+                })?;
+
+                if !exists {
+                    Err(SaveError::DanglingReference {
+                        field: field.clone(),
+                        target_key,
+                    })?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // check_unique
+    // scans the index range for each unique field, any live index row that
+    // points at a different key means the value is taken, nulls don't count
+    fn check_unique(
+        &self,
+        resolver: &Resolver,
+        store_path: &str,
+        data: &[u8],
+        key: &DataKey,
+        now: types::Timestamp,
+    ) -> Result<(), Error> {
+        let fields = resolver.unique_indexes()?;
+        if fields.is_empty() {
+            return Ok(());
+        }
+
+        let names: Vec<&str> = fields.iter().map(String::as_str).collect();
+        let slices = orm::field_slices(data, &names)?;
+
+        for field in &fields {
+            let Some(slice) = slices.get(field) else {
+                continue;
+            };
+            let value: Value = orm::deserialize(slice)?;
+            if value.is_null() {
+                continue;
+            }
+
+            let (start, end) = index_bounds(&resolver.entity, field, slice);
+            let taken = self.db.with_store(store_path, |store| {
+                Ok(store
+                    .data
+                    .range((Bound::Included(start), Bound::Excluded(end)))
+                    .any(|(index_key, index_value)| {
+                        primary_key(&index_key) != *key && !index_value.metadata.is_expired(now)
+                    }))
+            })?;
+
+            if taken {
+                Err(SaveError::UniqueViolation {
+                    field: field.clone(),
+                    value: orm::to_json(&value)?.to_string(),
+                })?;
+            }
+        }
+
+        Ok(())
+    }
+
+    // next_sequence
+    // bumps the entity's counter, a create that then fails leaves a gap
+    fn next_sequence(&self, entity_path: &str) -> Result<u64, Error> {
+        let store_path = Resolver::new(entity_path).store()?;
+        let key = sequence_key(entity_path);
+
+        let last = self
+            .db
+            .with_store(&store_path, |store| Ok(store.data.get(&key)))?
+            .map(|value| orm::deserialize::<u64>(&value.data))
+            .transpose()?
+            .unwrap_or_default();
+        let seq = last + 1;
+
+        let now = types::Timestamp::now();
+        let value = DataValue {
+            data: orm::serialize(&seq)?,
+            metadata: Metadata {
+                created: now,
+                modified: now,
+                expires_at: None,
+            },
+        };
+        self.db.with_store_mut(&store_path, |store| {
+            store.data.insert(key, value);
+
+            Ok(())
+        })?;
+
+        Ok(seq)
+    }
+}
+
+// instruction_counter
+// instructions used by the current message, 0 outside a canister
+fn instruction_counter() -> u64 {
+    if cfg!(target_arch = "wasm32") {
+        ic::api::instruction_counter()
+    } else {
+        0
+    }
+}
+
+// relation_keys
+// a relation is stored as ulid text, optional ones may be null
+fn relation_keys(value: &Value) -> Vec<String> {
+    match value {
+        Value::Text(id) => vec![id.clone()],
+        Value::Array(items) => items
+            .iter()
+            .filter_map(|item| item.as_text().map(ToString::to_string))
+            .collect(),
+        _ => Vec::new(),
     }
 }
 
@@ -300,12 +1078,43 @@ impl<'a> SaveBuilderExecutor<'a> {
 
 pub struct SaveBuilderResult {
     pub results: Vec<DataRow>,
+    pub outcomes: Vec<SaveOutcome>,
+    pub status: SaveStatus,
 }
 
 impl SaveBuilderResult {
     #[must_use]
-    pub const fn new(results: Vec<DataRow>) -> Self {
-        Self { results }
+    pub fn new(results: Vec<(DataRow, SaveOutcome)>) -> Self {
+        let (results, outcomes) = results.into_iter().unzip();
+
+        Self {
+            results,
+            outcomes,
+            status: SaveStatus::Complete,
+        }
+    }
+
+    // is_complete
+    // false if the budget ran out, take_remaining has what's left
+    #[must_use]
+    pub const fn is_complete(&self) -> bool {
+        self.status.is_complete()
+    }
+
+    // take_remaining
+    // the entities to pass to from_entities_dynamic in the next message
+    pub fn take_remaining(&mut self) -> Vec<Box<dyn EntityDynamic>> {
+        match mem::replace(&mut self.status, SaveStatus::Complete) {
+            SaveStatus::Complete => Vec::new(),
+            SaveStatus::PartialComplete(remaining) => remaining,
+        }
+    }
+
+    // outcomes
+    // one per row, in the same order as the rows
+    #[must_use]
+    pub fn outcomes(&self) -> Vec<SaveOutcome> {
+        self.outcomes.clone()
     }
 
     // ok
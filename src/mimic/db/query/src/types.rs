@@ -1,8 +1,9 @@
 use candid::CandidType;
 use db::{DataKey, DataRow, DataValue, Metadata};
 use derive_more::{Deref, DerefMut};
-use orm::types::SortDirection;
+use orm::{types::SortDirection, Value};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{collections::HashMap, fmt};
 
 ///
 /// QueryRow
@@ -15,6 +16,18 @@ pub struct QueryRow {
     pub value: QueryValue,
 }
 
+impl fmt::Display for QueryRow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} => {} bytes, {}",
+            self.key,
+            self.value.data.len(),
+            self.value.metadata
+        )
+    }
+}
+
 impl From<DataRow> for QueryRow {
     fn from(row: DataRow) -> Self {
         Self {
@@ -160,12 +173,55 @@ where
     }
 }
 
+///
+/// ProjectedRow
+/// a row with only the requested fields of the entity
+///
+
+#[derive(Clone, Debug)]
+pub struct ProjectedRow {
+    pub key: DataKey,
+    pub fields: HashMap<String, Value>,
+}
+
+///
+/// Aggregate
+/// running totals over a numeric field, rows where the field is empty
+/// are not counted
+///
+
+#[derive(CandidType, Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct Aggregate {
+    pub count: u64,
+    pub sum: f64,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+}
+
+impl Aggregate {
+    // push
+    pub fn push(&mut self, value: f64) {
+        self.count += 1;
+        self.sum += value;
+        self.min = Some(self.min.map_or(value, |min| min.min(value)));
+        self.max = Some(self.max.map_or(value, |max| max.max(value)));
+    }
+
+    // avg
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn avg(&self) -> Option<f64> {
+        (self.count > 0).then(|| self.sum / self.count as f64)
+    }
+}
+
 ///
 /// LoadRequest
 /// (from the front end, so no generics)
 ///
 /// entity : Entity path
-/// format : the format you want the results in (Rows or Count)
+/// format : the format you want the results in (Rows, Count or Page)
+/// cursor : the next_cursor of a previous page, rows start after this key
 ///
 
 #[derive(CandidType, Clone, Debug, Serialize, Deserialize)]
@@ -174,6 +230,7 @@ pub struct LoadRequest {
     pub method: LoadMethod,
     pub offset: u32,
     pub limit: Option<u32>,
+    pub cursor: Option<DataKey>,
     pub filter: Option<Filter>,
     pub order: Option<Order>,
     pub format: LoadFormat,
@@ -189,6 +246,7 @@ pub struct LoadRequest {
 pub enum LoadFormat {
     Rows,
     Count,
+    Page,
 }
 
 ///
@@ -204,6 +262,7 @@ pub enum LoadFormat {
 /// Prefix : like all but we're asking for the composite key prefix
 ///          so Pet (Character=1) will return the Pets from Character 1
 /// Range  : user-defined range, ie. Item=1000 Item=1500
+/// Index  : rows where an indexed field equals the serialized value
 ///
 
 #[derive(CandidType, Clone, Debug, Default, Serialize, Deserialize)]
@@ -214,6 +273,10 @@ pub enum LoadMethod {
     Many(Vec<Vec<String>>),
     Prefix(Vec<String>),
     Range(Vec<String>, Vec<String>),
+    Index {
+        field: String,
+        value: Vec<u8>,
+    },
 }
 
 ///
@@ -226,6 +289,21 @@ pub enum LoadMethod {
 pub enum LoadResponse {
     Rows(Vec<QueryRow>),
     Count(u32),
+    Page(LoadPage),
+}
+
+///
+/// LoadPage
+///
+/// rows        : at most limit rows, in DataKey order
+/// next_cursor : pass this back as the cursor to get the next page,
+///               None if there are no more rows
+///
+
+#[derive(CandidType, Clone, Debug, Serialize, Deserialize)]
+pub struct LoadPage {
+    pub rows: Vec<QueryRow>,
+    pub next_cursor: Option<DataKey>,
 }
 
 ///
@@ -332,7 +410,7 @@ impl From<&str> for Filter {
 /// Order
 ///
 
-#[derive(CandidType, Clone, Debug, Deref, DerefMut, Serialize, Deserialize)]
+#[derive(CandidType, Clone, Debug, Default, Deref, DerefMut, Serialize, Deserialize)]
 pub struct Order(Vec<(String, SortDirection)>);
 
 impl From<Vec<&str>> for Order {
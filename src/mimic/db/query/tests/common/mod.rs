@@ -0,0 +1,52 @@
+#![allow(dead_code)]
+
+use db::Db;
+use orm::traits::Path;
+use std::{
+    future::Future,
+    pin::pin,
+    sync::Once,
+    task::{Context, Poll, RawWaker, RawWakerVTable, Waker},
+};
+
+//
+// shared by the db_query host tests, each test gets its own in-memory
+// store and the canister state lives in a thread_local, so tests running
+// side by side don't see each other's rows or read only flag
+//
+
+pub const STORE: &str = mimic_base::canister::test::store::Data::PATH;
+
+// db
+// the resolver reads the schema, so it's loaded before the first store
+pub fn db() -> Db {
+    static INIT: Once = Once::new();
+    INIT.call_once(|| {
+        let json = serde_json::to_string(&*orm_schema::build::schema()).unwrap();
+        core_schema::init_schema_json(&json).unwrap();
+    });
+
+    let mut db = Db::new();
+    db.insert_memory(STORE);
+
+    db
+}
+
+// block_on
+// nothing the tests await is ever pending for long, so polling in a loop
+// with a waker that does nothing is enough
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    const VTABLE: RawWakerVTable = RawWakerVTable::new(|_| RAW, |_| {}, |_| {}, |_| {});
+    const RAW: RawWaker = RawWaker::new(std::ptr::null(), &VTABLE);
+
+    // SAFETY: every vtable function ignores the data pointer
+    let waker = unsafe { Waker::from_raw(RAW) };
+    let mut cx = Context::from_waker(&waker);
+    let mut future = pin!(future);
+
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
@@ -0,0 +1,75 @@
+mod common;
+
+use common::db;
+use mimic_base::types::test::store::{CreateBasic, Filterable, OwnedChild};
+use orm::types::FieldPredicate;
+
+#[test]
+fn test_delete_cascade() {
+    let db = db();
+    let parent = db_query::create(&db)
+        .from_entity(CreateBasic::default())
+        .unwrap()
+        .entity::<CreateBasic>()
+        .unwrap();
+    for _ in 0..2 {
+        let e = OwnedChild {
+            create_basic_id: parent.id,
+            ..Default::default()
+        };
+        db_query::create(&db).from_entity(e).unwrap();
+    }
+
+    let keys = db_query::delete::<CreateBasic>(&db)
+        .cascade()
+        .one(&[parent.id])
+        .unwrap()
+        .keys()
+        .unwrap();
+    assert_eq!(keys.len(), 3);
+
+    let count = db_query::load::<OwnedChild>(&db)
+        .all()
+        .execute_dyn()
+        .unwrap()
+        .count();
+    assert_eq!(count, 0);
+}
+
+#[test]
+fn test_delete_filter() {
+    let db = db();
+    for name in ["old a", "old b", "new"] {
+        let e = Filterable {
+            name: name.into(),
+            ..Default::default()
+        };
+        db_query::create(&db).from_entity(e).unwrap();
+    }
+    let count = || {
+        db_query::load::<Filterable>(&db)
+            .all()
+            .execute_dyn()
+            .unwrap()
+            .count()
+    };
+    let predicate = FieldPredicate::starts_with("old");
+
+    // a dry run lists the rows and leaves them alone
+    let keys = db_query::delete::<Filterable>(&db)
+        .dry_run()
+        .filter_field("name", &predicate)
+        .unwrap()
+        .keys()
+        .unwrap();
+    assert_eq!(keys.len(), 2);
+    assert_eq!(count(), 3);
+
+    let keys = db_query::delete::<Filterable>(&db)
+        .filter_field("name", &predicate)
+        .unwrap()
+        .keys()
+        .unwrap();
+    assert_eq!(keys.len(), 2);
+    assert_eq!(count(), 1);
+}
@@ -0,0 +1,152 @@
+mod common;
+
+use common::{db, STORE};
+use db::{DataKey, DataValue, Db, ImportPolicy};
+use db_query::{save::SaveError, Error};
+use mimic_base::types::test::store::{CreateSequential, UniqueName};
+
+// create
+fn create(db: &Db, name: &str) -> UniqueName {
+    let e = UniqueName {
+        name: name.to_string(),
+        ..Default::default()
+    };
+
+    db_query::create(db)
+        .from_entity(e)
+        .unwrap()
+        .entity::<UniqueName>()
+        .unwrap()
+}
+
+// by_name
+fn by_name(db: &Db, name: &str) -> Vec<UniqueName> {
+    db_query::load::<UniqueName>(db)
+        .index("name", &name)
+        .unwrap()
+        .execute()
+        .unwrap()
+        .entities()
+        .collect()
+}
+
+// rows
+fn rows(db: &Db) -> Vec<(DataKey, DataValue)> {
+    db.export(STORE).unwrap().collect()
+}
+
+#[test]
+fn test_export_import() {
+    let source = db();
+    let target = db();
+    let a = create(&source, "a");
+    create(&source, "b");
+
+    // the export is only the data rows, the index rows are rebuilt
+    let rows = rows(&source);
+    assert!(rows.iter().all(|(key, _)| key.is_data()));
+    let summary = db_query::import(&target, rows.clone(), ImportPolicy::Fail).unwrap();
+    assert_eq!(summary.inserted, 2);
+
+    // the metadata comes across and the index finds the rows
+    let copied = self::rows(&target);
+    for (key, value) in &rows {
+        let (_, other) = copied.iter().find(|(other, _)| other == key).unwrap();
+        assert_eq!(value.data, other.data);
+        assert_eq!(value.metadata.created, other.metadata.created);
+    }
+    assert_eq!(by_name(&target, "a")[0].id, a.id);
+
+    // a bad key rejects the whole batch
+    let (_, value) = rows[0].clone();
+    let bad = vec![
+        rows[0].clone(),
+        (DataKey::from_path("test::Missing", &["1"]), value),
+    ];
+    assert!(db_query::import(&db(), bad, ImportPolicy::Overwrite).is_err());
+}
+
+#[test]
+fn test_import_policy() {
+    let source = db();
+    let target = db();
+    let a = create(&source, "a");
+    let rows = rows(&source);
+
+    // the target holds the same key under another name
+    let mut stale = a.clone();
+    stale.name = "old".to_string();
+    db_query::replace(&target).from_entity(stale).unwrap();
+
+    let summary = db_query::import(&target, rows.clone(), ImportPolicy::Skip).unwrap();
+    assert_eq!(
+        (summary.inserted, summary.skipped, summary.failed),
+        (0, 1, 0)
+    );
+
+    // failing writes nothing, even for the keys that were free
+    let b = create(&source, "b");
+    let res = db_query::import(&target, self::rows(&source), ImportPolicy::Fail);
+    assert!(matches!(
+        res,
+        Err(Error::Save {
+            source: SaveError::KeyExists { .. }
+        })
+    ));
+    assert_eq!(by_name(&target, "old").len(), 1);
+    assert!(by_name(&target, &b.name).is_empty());
+
+    // overwriting drops the index row of the row it replaced
+    let summary = db_query::import(&target, rows, ImportPolicy::Overwrite).unwrap();
+    assert_eq!(summary.inserted, 1);
+    assert_eq!(by_name(&target, "a")[0].id, a.id);
+    assert!(by_name(&target, "old").is_empty());
+    let index_rows = target
+        .with_store(STORE, |store| {
+            Ok(store.data.keys().filter(DataKey::is_index).count())
+        })
+        .unwrap();
+    assert_eq!(index_rows, 1);
+}
+
+#[test]
+fn test_import_unique() {
+    let source = db();
+    let target = db();
+    create(&source, "a");
+    create(&target, "a");
+
+    // the name is taken by a row under another key
+    let summary = db_query::import(&target, rows(&source), ImportPolicy::Overwrite).unwrap();
+    assert_eq!((summary.inserted, summary.failed), (0, 1));
+    assert_eq!(by_name(&target, "a").len(), 1);
+}
+
+#[test]
+fn test_import_sequence() {
+    let source = db();
+    let target = db();
+    for _ in 0..3 {
+        db_query::create(&source)
+            .from_entity(CreateSequential::default())
+            .unwrap();
+    }
+
+    // a stray index row is ignored rather than stored as data
+    let mut rows = rows(&source);
+    let index = DataKey::new(vec![
+        ("test::Entity#name".to_string(), vec!["00".to_string()]),
+        ("test::Entity".to_string(), vec!["1".to_string()]),
+    ]);
+    rows.push((index, rows[0].1.clone()));
+    let summary = db_query::import(&target, rows, ImportPolicy::Fail).unwrap();
+    assert_eq!(summary.inserted, 3);
+
+    // the counter comes across so the next create doesn't reuse a key
+    let next = db_query::create(&target)
+        .from_entity(CreateSequential::default())
+        .unwrap()
+        .entity::<CreateSequential>()
+        .unwrap();
+    assert_eq!(next.id, 4);
+}
@@ -0,0 +1,62 @@
+mod common;
+
+use common::db;
+use mimic_base::types::test::store::UniqueName;
+
+#[test]
+fn test_unique_long_value() {
+    let db = db();
+    let name = "x".repeat(300);
+
+    let e = UniqueName {
+        name: name.clone(),
+        ..Default::default()
+    };
+    let saved = db_query::create(&db)
+        .from_entity(e.clone())
+        .unwrap()
+        .entity::<UniqueName>()
+        .unwrap();
+
+    // the hashed index row still turns away a second row with the name
+    assert!(db_query::create(&db).from_entity(e).is_err());
+    let other = UniqueName {
+        name: format!("{name}y"),
+        ..Default::default()
+    };
+    db_query::create(&db).from_entity(other).unwrap();
+
+    let found: Vec<_> = db_query::load::<UniqueName>(&db)
+        .index("name", &name)
+        .unwrap()
+        .execute()
+        .unwrap()
+        .entities()
+        .collect();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].id, saved.id);
+}
+
+#[test]
+fn test_unique_index() {
+    let db = db();
+    let e = UniqueName {
+        name: "bob".to_string(),
+        ..Default::default()
+    };
+    let saved = db_query::create(&db)
+        .from_entity(e.clone())
+        .unwrap()
+        .entity::<UniqueName>()
+        .unwrap();
+
+    // a second row can't take the name, the row itself can be saved again
+    assert!(db_query::create(&db).from_entity(e.clone()).is_err());
+    db_query::replace(&db).from_entity(saved.clone()).unwrap();
+
+    // deleting the row frees the name up
+    db_query::delete::<UniqueName>(&db)
+        .one(&[saved.id])
+        .unwrap();
+    db_query::create(&db).from_entity(e).unwrap();
+}
@@ -0,0 +1,452 @@
+mod common;
+
+use common::db;
+use db::{DataKey, Db};
+use db_query::{
+    load::{LoadError, MAX_ORDER_ROWS},
+    types::Order,
+    Error,
+};
+use mimic_base::types::{
+    test::store::{Filterable, Limit, SortKeyB, SortKeyOrder},
+    Ulid,
+};
+use orm::types::{FieldPredicate, SortDirection};
+
+// limits
+// saves a Limit row for each value, the u32 key is zero padded so the
+// key order is the numeric order
+fn limits(db: &Db, values: impl IntoIterator<Item = u32>) {
+    for value in values {
+        db_query::create(db).from_entity(Limit { value }).unwrap();
+    }
+}
+
+// load_error
+fn load_error<T>(res: Result<T, Error>) -> LoadError {
+    match res {
+        Err(Error::Load { source }) => source,
+        _ => panic!("expected a load error"),
+    }
+}
+
+// values
+fn values(db: &Db, order: SortDirection, offset: u32, limit: u32) -> Vec<u32> {
+    db_query::load::<Limit>(db)
+        .all()
+        .order_by("value", order)
+        .offset(offset)
+        .limit(limit)
+        .execute()
+        .unwrap()
+        .entities()
+        .map(|e| e.value)
+        .collect()
+}
+
+#[test]
+fn test_order_row_cap() {
+    let db = db();
+    for value in 0..MAX_ORDER_ROWS {
+        db_query::create(&db)
+            .from_entity(Limit {
+                value: value as u32,
+            })
+            .unwrap();
+    }
+
+    // at the cap the rows are still ordered
+    let rows = db_query::load::<Limit>(&db)
+        .all()
+        .order_by("value", SortDirection::Desc)
+        .limit(1)
+        .execute()
+        .unwrap();
+    assert_eq!(rows.keys().count(), 1);
+
+    // one row past it fails, unordered loads don't care
+    db_query::create(&db)
+        .from_entity(Limit {
+            value: MAX_ORDER_ROWS as u32,
+        })
+        .unwrap();
+    let err = load_error(
+        db_query::load::<Limit>(&db)
+            .all()
+            .order_by("value", SortDirection::Desc)
+            .execute(),
+    );
+    assert!(matches!(
+        err,
+        LoadError::OrderTooManyRows {
+            max: MAX_ORDER_ROWS
+        }
+    ));
+    let count = db_query::load::<Limit>(&db).all().count().unwrap();
+    assert_eq!(count, MAX_ORDER_ROWS as u64 + 1);
+}
+
+#[test]
+fn test_data_key_order() {
+    const ROWS: u16 = 1_000;
+    let db = db();
+    for _ in 0..ROWS {
+        db_query::create(&db)
+            .from_entity(SortKeyOrder::default())
+            .unwrap();
+    }
+
+    // the keys come back in b-tree order
+    let rows: Vec<DataKey> = db_query::load::<SortKeyOrder>(&db)
+        .all()
+        .order(Order::from(vec!["id"]))
+        .execute()
+        .unwrap()
+        .keys()
+        .collect();
+    assert_eq!(rows.len(), usize::from(ROWS));
+    for (i, pair) in rows.windows(2).enumerate() {
+        assert!(pair[0] < pair[1], "row ordering is incorrect at index {i}");
+    }
+}
+
+#[test]
+fn test_filter_query() {
+    let db = db();
+    let rows = [
+        (
+            "01HMBEJJM0D6CMABQ3ZF6TMQ1M",
+            "The Book of Magic",
+            "This book has so much magic in it",
+        ),
+        (
+            "01HMBEK2QT84T2GNV2Y02ED9D9",
+            "The Sparkle Sword",
+            "*** SPARKLES ***",
+        ),
+        ("01HMBEK9HQTTH6ZWYYYNTJ4ZC1", "Fruit Salad", "Yummy yummy"),
+        ("01HMBEKHXZMRYDHP51APS9791H", "Same", "Same"),
+    ];
+
+    // replace so that the ids are left unchanged
+    for (id, name, description) in rows {
+        let e = Filterable {
+            id: Ulid::from_string(id).unwrap(),
+            name: name.into(),
+            description: description.into(),
+        };
+        db_query::replace(&db).from_entity(e).unwrap();
+    }
+
+    let tests = [
+        ("a", 4),
+        ("the", 2),
+        ("Yummy", 1),
+        ("yummy", 1),
+        ("SPARKLE", 1),
+        ("ZZXX", 0),
+        ("hMbE", 4),
+        ("01hmbek9", 1),
+        ("same", 1),
+        ("00", 0),
+    ];
+    for (search, expected) in tests {
+        let count = db_query::load::<Filterable>(&db)
+            .all()
+            .filter(search)
+            .execute()
+            .unwrap()
+            .keys()
+            .count();
+        assert_eq!(count, expected, "filter '{search}' failed");
+    }
+}
+
+#[test]
+fn test_limit_query() {
+    let db = db();
+    for value in 1..100 {
+        db_query::replace(&db).from_entity(Limit { value }).unwrap();
+    }
+
+    for limit in [10, 20, 50] {
+        for offset in [0, 5, 10] {
+            let count = db_query::load::<Limit>(&db)
+                .all()
+                .offset(offset)
+                .limit(limit)
+                .execute_dyn()
+                .unwrap()
+                .keys()
+                .count();
+            assert_eq!(count, limit as usize);
+        }
+    }
+}
+
+#[test]
+fn test_load_empty() {
+    let db = db();
+    let load = || db_query::load::<Limit>(&db).all();
+
+    assert_eq!(load().execute().unwrap().keys().count(), 0);
+    assert_eq!(load().filter("a").execute().unwrap().keys().count(), 0);
+    assert!(values(&db, SortDirection::Desc, 0, 10).is_empty());
+    assert_eq!(load().stream().unwrap().count(), 0);
+    assert_eq!(load().count().unwrap(), 0);
+
+    let page = load().limit(10).page().unwrap();
+    assert!(page.rows.is_empty());
+    assert_eq!(page.next_cursor, None);
+}
+
+#[test]
+fn test_filter_field() {
+    let db = db();
+    for (name, description) in [("old a", "red"), ("old b", "blue"), ("new", "red")] {
+        let e = Filterable {
+            name: name.into(),
+            description: description.into(),
+            ..Default::default()
+        };
+        db_query::create(&db).from_entity(e).unwrap();
+    }
+    let count = |predicates: &[(&str, FieldPredicate)]| {
+        predicates
+            .iter()
+            .fold(
+                db_query::load::<Filterable>(&db).all(),
+                |load, (field, p)| load.filter_field(field, p.clone()),
+            )
+            .execute()
+            .unwrap()
+            .keys()
+            .count()
+    };
+
+    assert_eq!(count(&[("name", FieldPredicate::starts_with("old"))]), 2);
+    assert_eq!(count(&[("name", FieldPredicate::contains("b"))]), 1);
+    assert_eq!(
+        count(&[("name", FieldPredicate::eq(&"new".to_string()).unwrap())]),
+        1
+    );
+    assert_eq!(count(&[("name", FieldPredicate::starts_with("gone"))]), 0);
+
+    // every predicate has to match
+    let both = [
+        ("name", FieldPredicate::starts_with("old")),
+        ("description", FieldPredicate::contains("red")),
+    ];
+    assert_eq!(count(&both), 1);
+
+    // predicates and the text filter combine too
+    let rows = db_query::load::<Filterable>(&db)
+        .all()
+        .filter("red")
+        .filter_field("name", FieldPredicate::starts_with("old"))
+        .execute()
+        .unwrap()
+        .keys()
+        .count();
+    assert_eq!(rows, 1);
+}
+
+#[test]
+fn test_filter_field_range() {
+    let db = db();
+    limits(&db, 1..=20);
+    let count = |min: Option<&u32>, max: Option<&u32>| {
+        db_query::load::<Limit>(&db)
+            .all()
+            .filter_field("value", FieldPredicate::range(min, max).unwrap())
+            .execute()
+            .unwrap()
+            .keys()
+            .count()
+    };
+
+    // both bounds are inclusive
+    assert_eq!(count(Some(&5), Some(&10)), 6);
+    assert_eq!(count(Some(&20), None), 1);
+    assert_eq!(count(None, Some(&1)), 1);
+    assert_eq!(count(None, None), 20);
+    assert_eq!(count(Some(&10), Some(&5)), 0);
+}
+
+#[test]
+fn test_order() {
+    let db = db();
+    limits(&db, [7, 3, 19, 1, 12]);
+
+    assert_eq!(
+        values(&db, SortDirection::Asc, 0, 10),
+        vec![1, 3, 7, 12, 19]
+    );
+    assert_eq!(
+        values(&db, SortDirection::Desc, 0, 10),
+        vec![19, 12, 7, 3, 1]
+    );
+
+    // offset and limit are applied after the sort
+    assert_eq!(values(&db, SortDirection::Desc, 1, 2), vec![12, 7]);
+    assert_eq!(values(&db, SortDirection::Asc, 4, 10), vec![19]);
+    assert!(values(&db, SortDirection::Asc, 5, 10).is_empty());
+    assert!(values(&db, SortDirection::Asc, 0, 0).is_empty());
+}
+
+#[test]
+fn test_offset_limit() {
+    let db = db();
+    limits(&db, 1..=10);
+    let keys = |offset: u32, limit: Option<u32>| {
+        db_query::load::<Limit>(&db)
+            .all()
+            .offset(offset)
+            .limit_option(limit)
+            .execute()
+            .unwrap()
+            .entities()
+            .map(|e| e.value)
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(keys(0, None).len(), 10);
+    assert_eq!(keys(0, Some(3)), vec![1, 2, 3]);
+    assert_eq!(keys(8, Some(5)), vec![9, 10]);
+    assert!(keys(10, None).is_empty());
+    assert!(keys(0, Some(0)).is_empty());
+
+    // count applies them to the total
+    let count = |offset: u32, limit: u32| {
+        db_query::load::<Limit>(&db)
+            .all()
+            .offset(offset)
+            .limit(limit)
+            .count()
+            .unwrap()
+    };
+    assert_eq!(count(8, 5), 2);
+    assert_eq!(count(20, 5), 0);
+}
+
+#[test]
+fn test_range() {
+    let db = db();
+    limits(&db, 1..=20);
+    let range = |start: u32, end: u32| {
+        db_query::load::<Limit>(&db)
+            .range(&[start], &[end])
+            .unwrap()
+            .execute()
+            .unwrap()
+            .entities()
+            .map(|e| e.value)
+            .collect::<Vec<_>>()
+    };
+
+    // both ends are included
+    assert_eq!(range(5, 8), vec![5, 6, 7, 8]);
+    assert_eq!(range(20, 20), vec![20]);
+    assert!(range(21, 30).is_empty());
+
+    // a composite key has no single range
+    let id = Ulid::generate().to_string();
+    let res = db_query::load::<SortKeyB>(&db)
+        .range(&[&id, &id, &id], &[&id, &id, &id])
+        .unwrap()
+        .execute();
+    assert!(matches!(
+        res,
+        Err(Error::Load {
+            source: LoadError::RangeNotAllowed
+        })
+    ));
+}
+
+#[test]
+fn test_page() {
+    let db = db();
+    limits(&db, 1..=25);
+    let page = |cursor: Option<DataKey>| {
+        db_query::load::<Limit>(&db)
+            .all()
+            .cursor_option(cursor)
+            .limit(10)
+            .page()
+            .unwrap()
+    };
+
+    // the cursor walks every row once
+    let mut sizes = Vec::new();
+    let mut keys = Vec::new();
+    let mut cursor = None;
+    loop {
+        let p = page(cursor);
+        sizes.push(p.rows.len());
+        keys.extend(p.rows.into_iter().map(|row| row.key));
+        cursor = p.next_cursor;
+        if cursor.is_none() {
+            break;
+        }
+    }
+    assert_eq!(sizes, vec![10, 10, 5]);
+    let all: Vec<DataKey> = db_query::load::<Limit>(&db)
+        .all()
+        .execute()
+        .unwrap()
+        .keys()
+        .collect();
+    assert_eq!(keys, all);
+
+    // a full last page doesn't point at an empty one
+    let last = db_query::load::<Limit>(&db)
+        .all()
+        .offset(15)
+        .limit(10)
+        .page()
+        .unwrap();
+    assert_eq!(last.rows.len(), 10);
+    assert_eq!(last.next_cursor, None);
+
+    // a range page starts after the cursor
+    let p = db_query::load::<Limit>(&db)
+        .range(&[5], &[10])
+        .unwrap()
+        .cursor(all[6].clone())
+        .page()
+        .unwrap();
+    assert_eq!(p.rows.len(), 3);
+    assert_eq!(p.next_cursor, None);
+}
+
+#[test]
+fn test_not_allowed() {
+    let db = db();
+    limits(&db, 1..=3);
+    let load = || db_query::load::<Limit>(&db).all();
+
+    assert!(matches!(
+        load_error(load().filter("1").page()),
+        LoadError::PageNotAllowed
+    ));
+    assert!(matches!(
+        load_error(load().order(Order::from(vec!["value"])).page()),
+        LoadError::PageNotAllowed
+    ));
+    assert!(matches!(
+        load_error(load().filter("1").stream()),
+        LoadError::StreamNotAllowed
+    ));
+    assert!(matches!(
+        load_error(load().filter("1").execute_dyn()),
+        LoadError::FilterNotAllowed
+    ));
+    assert!(matches!(
+        load_error(
+            load()
+                .filter_field("value", FieldPredicate::eq(&1u32).unwrap())
+                .count()
+        ),
+        LoadError::CountFilterNotAllowed
+    ));
+}
@@ -0,0 +1,72 @@
+mod common;
+
+use common::{db, STORE};
+use db::{DataKey, DataValue, Migration};
+use mimic_base::types::test::store::UniqueName;
+use orm::traits::Path;
+
+///
+/// Rename
+/// upper cases every UniqueName, so the index rows have to move
+///
+
+struct Rename;
+
+impl Migration for Rename {
+    fn from_version(&self) -> u32 {
+        0
+    }
+
+    fn to_version(&self) -> u32 {
+        1
+    }
+
+    fn applies_to(&self, key: &DataKey) -> bool {
+        key.parts()
+            .last()
+            .is_some_and(|(path, _)| *path == UniqueName::path())
+    }
+
+    fn migrate(&self, value: &mut DataValue) -> Result<(), String> {
+        let mut e: UniqueName = orm::deserialize(&value.data).map_err(|e| e.to_string())?;
+        e.name = e.name.to_uppercase();
+        value.data = orm::serialize(&e).map_err(|e| e.to_string())?;
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_migrate_reindex() {
+    let db = db();
+    for name in ["a", "b"] {
+        let e = UniqueName {
+            name: name.to_string(),
+            ..Default::default()
+        };
+        db_query::create(&db).from_entity(e).unwrap();
+    }
+
+    // the two rows and their two index rows
+    let progress = db_query::migrate(&db, STORE, &[&Rename], 10).unwrap();
+    assert_eq!(
+        (progress.version, progress.rows, progress.done),
+        (1, 4, true)
+    );
+
+    let count = |name: &str| {
+        db_query::load::<UniqueName>(&db)
+            .index("name", &name)
+            .unwrap()
+            .count()
+            .unwrap()
+    };
+    assert_eq!((count("a"), count("A"), count("B")), (0, 1, 1));
+
+    // the unique index holds the new name
+    let e = UniqueName {
+        name: "A".to_string(),
+        ..Default::default()
+    };
+    assert!(db_query::create(&db).from_entity(e).is_err());
+}
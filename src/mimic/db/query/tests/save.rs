@@ -0,0 +1,431 @@
+mod common;
+
+use common::{block_on, db, STORE};
+use core_state::CanisterStateManager;
+use db::Db;
+use db_query::{resolver::ResolverError, save::SaveError, SaveMode, ValidateAsync};
+use mimic_base::types::{
+    test::{
+        has_map::HasMap,
+        store::{
+            CheckedRelation, CheckedSortKeyRelation, CreateBasic, CreateContentHash,
+            CreateSequential, Filterable, MissingFieldLarge, MissingFieldSmall,
+        },
+    },
+    Ulid,
+};
+use orm::{
+    proptest::{arbitrary::any, prop_assert_eq, proptest},
+    traits::{EntityDynamic, Path},
+};
+use std::{cell::RefCell, rc::Rc};
+use types::ErrorTree;
+
+///
+/// AcceptAll
+///
+
+struct AcceptAll;
+
+impl ValidateAsync for AcceptAll {
+    async fn validate_async(&self, _: &Db, _: &dyn EntityDynamic) -> Result<(), ErrorTree> {
+        Ok(())
+    }
+}
+
+///
+/// MaxRows
+///
+/// turns away a CreateBasic once the store holds this many, so the check
+/// has to read the db
+///
+
+struct MaxRows(u64);
+
+impl ValidateAsync for MaxRows {
+    async fn validate_async(&self, db: &Db, _: &dyn EntityDynamic) -> Result<(), ErrorTree> {
+        let count = db_query::load::<CreateBasic>(db).all().count().unwrap();
+        if count >= self.0 {
+            let mut errors = ErrorTree::new();
+            errors.set("id", "too many rows");
+
+            return Err(errors);
+        }
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_validate_async() {
+    let db = db();
+    let count = || db_query::load::<CreateBasic>(&db).all().count().unwrap();
+
+    for _ in 0..2 {
+        let save = db_query::create(&db).from_entity_async(CreateBasic::default(), &MaxRows(2));
+        let saved = block_on(save).unwrap().entity::<CreateBasic>().unwrap();
+        assert!(db_query::load::<CreateBasic>(&db)
+            .get(&[saved.id])
+            .unwrap()
+            .is_some());
+    }
+    assert_eq!(count(), 2);
+
+    // the failure is reported like a field error and nothing is written
+    let save = db_query::create(&db).from_entity_async(CreateBasic::default(), &MaxRows(2));
+    let Err(db_query::Error::Save {
+        source: SaveError::Validation { path, errors, .. },
+    }) = block_on(save)
+    else {
+        panic!("expected a validation error");
+    };
+    assert_eq!(path, CreateBasic::PATH);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].path, "id");
+    assert_eq!(errors[0].message, "too many rows");
+    assert_eq!(count(), 2);
+}
+
+#[test]
+fn test_read_only() {
+    let db = db();
+    CanisterStateManager::set_read_only(true).unwrap();
+
+    // sync and async saves are both turned away before anything is written
+    let res = db_query::create(&db).from_entity(CreateBasic::default());
+    assert!(matches!(
+        res,
+        Err(db_query::Error::Save {
+            source: SaveError::ReadOnly
+        })
+    ));
+
+    let save = db_query::create(&db).from_entity_async(CreateBasic::default(), &AcceptAll);
+    let res = block_on(save);
+    assert!(matches!(
+        res,
+        Err(db_query::Error::Save {
+            source: SaveError::ReadOnly
+        })
+    ));
+
+    let count = db_query::load::<CreateBasic>(&db)
+        .all()
+        .execute_dyn()
+        .unwrap()
+        .count();
+    assert_eq!(count, 0);
+
+    CanisterStateManager::set_read_only(false).unwrap();
+    let save = db_query::create(&db).from_entity_async(CreateBasic::default(), &AcceptAll);
+    let saved = block_on(save).unwrap().entity::<CreateBasic>().unwrap();
+
+    // deletes are turned away too, reads still work
+    CanisterStateManager::set_read_only(true).unwrap();
+    assert!(db_query::delete::<CreateBasic>(&db)
+        .one(&[saved.id])
+        .is_err());
+    assert!(db_query::load::<CreateBasic>(&db)
+        .get(&[saved.id])
+        .unwrap()
+        .is_some());
+
+    CanisterStateManager::set_read_only(false).unwrap();
+    db_query::delete::<CreateBasic>(&db)
+        .one(&[saved.id])
+        .unwrap();
+}
+
+#[test]
+fn test_relation_sort_keys() {
+    let db = db();
+
+    // the target key can't be made from the one ulid, so nothing is saved
+    let e = CheckedSortKeyRelation {
+        sort_key_b_id: Ulid::generate(),
+        ..Default::default()
+    };
+    let res = db_query::create(&db).from_entity(e.clone());
+    assert!(matches!(
+        res,
+        Err(db_query::Error::Resolver {
+            source: ResolverError::RelationHasSortKeys { .. }
+        })
+    ));
+
+    db_query::create(&db)
+        .skip_relation_check()
+        .from_entity(e)
+        .unwrap();
+}
+
+#[test]
+fn test_entity_with_map() {
+    let db = db();
+
+    let mut e = HasMap::default();
+    e.map_int_string.insert(3, "value".to_string());
+    e.map_int_string.insert(4, "value".to_string());
+    let saved = db_query::create(&db)
+        .from_entity(e)
+        .unwrap()
+        .entity::<HasMap>()
+        .unwrap();
+    assert_eq!(saved.map_int_string.len(), 2);
+
+    let count = db_query::load::<HasMap>(&db)
+        .only()
+        .execute_dyn()
+        .unwrap()
+        .keys()
+        .count();
+    assert_eq!(count, 1);
+}
+
+#[test]
+fn test_clear() {
+    let db = db();
+    for _ in 0..100 {
+        db_query::create(&db)
+            .from_entity(CreateBasic::default())
+            .unwrap();
+    }
+
+    db.with_store_mut(STORE, |store| {
+        store.clear();
+        Ok(())
+    })
+    .unwrap();
+
+    let count = db_query::load::<CreateBasic>(&db)
+        .all()
+        .execute_dyn()
+        .unwrap()
+        .count();
+    assert_eq!(count, 0);
+}
+
+#[test]
+fn test_create() {
+    const ROWS: usize = 5_000;
+    let db = db();
+    let count = || {
+        db_query::load::<CreateBasic>(&db)
+            .all()
+            .execute_dyn()
+            .unwrap()
+            .count()
+    };
+
+    db_query::create(&db)
+        .from_entity(CreateBasic::default())
+        .unwrap();
+    assert_eq!(count(), 1);
+
+    for _ in 1..ROWS {
+        db_query::create(&db)
+            .from_entity(CreateBasic::default())
+            .unwrap();
+    }
+    assert_eq!(count(), ROWS);
+}
+
+#[test]
+fn test_create_content_hash() {
+    let db = db();
+    let entity = |name: &str| CreateContentHash {
+        name: name.into(),
+        ..Default::default()
+    };
+
+    let a = db_query::create(&db)
+        .from_entity(entity("a"))
+        .unwrap()
+        .entity::<CreateContentHash>()
+        .unwrap();
+    let b = db_query::create(&db)
+        .from_entity(entity("b"))
+        .unwrap()
+        .entity::<CreateContentHash>()
+        .unwrap();
+    assert_ne!(a.id, b.id);
+
+    // same content, same key, so the duplicate is rejected
+    assert!(db_query::create(&db).from_entity(entity("a")).is_err());
+}
+
+#[test]
+fn test_create_sequential() {
+    let db = db();
+    let ids: Vec<u64> = (0..3)
+        .map(|_| {
+            db_query::create(&db)
+                .from_entity(CreateSequential::default())
+                .unwrap()
+                .entity::<CreateSequential>()
+                .unwrap()
+                .id
+        })
+        .collect();
+
+    assert_eq!(ids, vec![1, 2, 3]);
+}
+
+#[test]
+fn test_create_preserve_ids() {
+    let db = db();
+    let id = Ulid::generate();
+    let e = db_query::create(&db)
+        .preserve_ids()
+        .from_entity(CreateBasic { id })
+        .unwrap()
+        .entity::<CreateBasic>()
+        .unwrap();
+    assert_eq!(e.id, id);
+
+    // without it the id is generated
+    let e = db_query::create(&db)
+        .from_entity(CreateBasic { id })
+        .unwrap()
+        .entity::<CreateBasic>()
+        .unwrap();
+    assert_ne!(e.id, id);
+}
+
+#[test]
+fn test_replace_outcome() {
+    let db = db();
+
+    // an upsert reports whether it wrote a new row
+    let e = CreateBasic {
+        id: Ulid::generate(),
+    };
+    for created in [true, false] {
+        let outcomes = db_query::replace(&db)
+            .from_entity(e.clone())
+            .unwrap()
+            .outcomes();
+        assert_eq!(outcomes.len(), 1);
+        assert_eq!(outcomes[0].mode, SaveMode::Replace);
+        assert_eq!(outcomes[0].created, created);
+    }
+}
+
+#[test]
+fn test_replace_if() {
+    let db = db();
+    let mut e = db_query::create(&db)
+        .from_entity(Filterable {
+            name: "packed".into(),
+            ..Default::default()
+        })
+        .unwrap()
+        .entity::<Filterable>()
+        .unwrap();
+
+    // only moves on from the state it expects
+    e.name = "shipped".into();
+    db_query::replace(&db)
+        .replace_if("name", &"packed")
+        .unwrap()
+        .from_entity(e.clone())
+        .unwrap();
+
+    assert!(matches!(
+        db_query::replace(&db)
+            .replace_if("name", &"packed")
+            .unwrap()
+            .from_entity(e.clone()),
+        Err(db_query::Error::Save {
+            source: SaveError::ConditionFailed { .. }
+        })
+    ));
+
+    // a row that isn't there doesn't match
+    let missing = Filterable {
+        id: Ulid::generate(),
+        ..e
+    };
+    assert!(db_query::replace(&db)
+        .replace_if("name", &"shipped")
+        .unwrap()
+        .from_entity(missing)
+        .is_err());
+}
+
+#[test]
+fn test_save_timings() {
+    let db = db();
+
+    // debug mode reports a breakdown per saved row
+    let lines = Rc::new(RefCell::new(Vec::new()));
+    let captured = Rc::clone(&lines);
+    db_query::create(&db)
+        .debug_sink(move |s| captured.borrow_mut().push(s.to_string()))
+        .from_entity(Filterable::default())
+        .unwrap();
+
+    let lines = lines.borrow();
+    let timings: Vec<_> = lines
+        .iter()
+        .filter(|line| line.starts_with("store.timings: "))
+        .collect();
+    assert_eq!(timings.len(), 1);
+    assert!(timings[0].contains("validate") && timings[0].contains("write"));
+}
+
+#[test]
+fn test_check_relation() {
+    let db = db();
+
+    // a missing target is rejected unless the check is skipped
+    let e = CheckedRelation {
+        create_basic_id: Ulid::generate(),
+        ..Default::default()
+    };
+    assert!(db_query::create(&db).from_entity(e.clone()).is_err());
+    db_query::create(&db)
+        .skip_relation_check()
+        .from_entity(e)
+        .unwrap();
+
+    // an existing target is fine
+    let target = db_query::create(&db)
+        .from_entity(CreateBasic::default())
+        .unwrap()
+        .entity::<CreateBasic>()
+        .unwrap();
+    let e = CheckedRelation {
+        create_basic_id: target.id,
+        ..Default::default()
+    };
+    db_query::create(&db).from_entity(e).unwrap();
+}
+
+#[test]
+fn test_missing_field() {
+    let small = MissingFieldSmall {
+        a: Ulid::generate(),
+        b: Ulid::generate(),
+    };
+
+    // a row saved before the field was added still loads
+    let bytes = orm::serialize(&small).unwrap();
+    let large = orm::deserialize::<MissingFieldLarge>(&bytes).unwrap();
+
+    assert!(!large.a.is_nil());
+    assert!(!large.b.is_nil());
+    assert!(large.c.is_nil());
+}
+
+proptest! {
+    // whatever Filterable the strategy makes survives a save and a load
+    #[test]
+    fn test_save_load_round_trip(e in any::<Filterable>()) {
+        let db = db();
+        db_query::replace(&db).from_entity(e.clone()).unwrap();
+
+        let row = db_query::load::<Filterable>(&db).get(&[e.id]).unwrap().unwrap();
+        prop_assert_eq!(row.value.entity, e);
+    }
+}
@@ -1,13 +1,23 @@
+pub mod migrate;
 pub mod types;
 
-pub use types::{DataKey, DataRow, DataValue, Metadata};
+pub use migrate::{Migration, MigrationProgress, Reindex};
+pub use types::{
+    DataKey, DataRow, DataValue, ImportPolicy, ImportSummary, Metadata, PurgeProgress, StoreStats,
+};
 
+use ::types::Timestamp;
 use candid::CandidType;
 use derive_more::{Deref, DerefMut};
 use ic::structures::{memory::VirtualMemory, BTreeMap};
 use serde::{Deserialize, Serialize};
 use snafu::Snafu;
-use std::{cell::RefCell, collections::HashMap, thread::LocalKey};
+use std::{
+    cell::RefCell,
+    collections::{BTreeSet, HashMap},
+    ops::Bound,
+    thread::LocalKey,
+};
 
 ///
 /// Error
@@ -15,11 +25,43 @@ use std::{cell::RefCell, collections::HashMap, thread::LocalKey};
 
 #[derive(CandidType, Debug, Serialize, Deserialize, Snafu)]
 pub enum Error {
+    #[snafu(transparent)]
+    Cbor { source: lib_cbor::Error },
+
+    #[snafu(display("invalid data key '{key}': {msg}"))]
+    InvalidDataKey { key: String, msg: String },
+
+    #[snafu(display("migration {from_version} to {to_version} failed at '{key}': {msg}"))]
+    Migration {
+        from_version: u32,
+        to_version: u32,
+        key: String,
+        msg: String,
+    },
+
     #[snafu(display("store not found: {path}"))]
     StoreNotFound { path: String },
 }
 
 impl Error {
+    #[must_use]
+    pub fn invalid_data_key(key: &str, msg: &str) -> Self {
+        Self::InvalidDataKey {
+            key: key.to_string(),
+            msg: msg.to_string(),
+        }
+    }
+
+    #[must_use]
+    pub fn migration(from_version: u32, to_version: u32, key: &str, msg: &str) -> Self {
+        Self::Migration {
+            from_version,
+            to_version,
+            key: key.to_string(),
+            msg: msg.to_string(),
+        }
+    }
+
     #[must_use]
     pub fn store_not_found(path: &str) -> Self {
         Self::StoreNotFound {
@@ -35,6 +77,9 @@ impl Error {
 #[derive(Default)]
 pub struct Db {
     stores: HashMap<&'static str, &'static LocalKey<RefCell<Store>>>,
+
+    #[cfg(feature = "test")]
+    memory: HashMap<&'static str, RefCell<Store>>,
 }
 
 impl Db {
@@ -49,11 +94,32 @@ impl Db {
         self.stores.insert(name, accessor);
     }
 
+    // insert_memory
+    // a store owned by the Db rather than a thread_local, off wasm the
+    // default memory is a plain Vec so tests run on the host
+    #[cfg(feature = "test")]
+    pub fn insert_memory(&mut self, name: &'static str) {
+        use ic::structures::{
+            memory::{MemoryId, MemoryManager},
+            DefaultMemoryImpl,
+        };
+
+        let mm = MemoryManager::init(DefaultMemoryImpl::default());
+        let store = Store::init(mm.get(MemoryId::new(0)));
+
+        self.memory.insert(name, RefCell::new(store));
+    }
+
     // with_store
     pub fn with_store<F, R>(&self, name: &str, f: F) -> Result<R, Error>
     where
         F: FnOnce(&Store) -> Result<R, Error>,
     {
+        #[cfg(feature = "test")]
+        if let Some(store) = self.memory.get(name) {
+            return f(&store.borrow());
+        }
+
         self.stores
             .get(name)
             .ok_or_else(|| Error::store_not_found(name))
@@ -65,11 +131,97 @@ impl Db {
     where
         F: FnOnce(&mut Store) -> Result<R, Error>,
     {
+        #[cfg(feature = "test")]
+        if let Some(store) = self.memory.get(name) {
+            return f(&mut store.borrow_mut());
+        }
+
         self.stores
             .get(name)
             .ok_or_else(|| Error::store_not_found(name))
             .and_then(|local_key| local_key.with(|store| f(&mut store.borrow_mut())))
     }
+
+    // stats
+    pub fn stats(&self, name: &str) -> Result<StoreStats, Error> {
+        self.with_store(name, |store| Ok(store.stats()))
+    }
+
+    // clear_prefix
+    pub fn clear_prefix(&self, name: &str, prefix: &DataKey) -> Result<u64, Error> {
+        self.with_store_mut(name, |store| Ok(store.clear_prefix(prefix)))
+    }
+
+    // migrate
+    pub fn migrate(
+        &self,
+        name: &str,
+        migrations: &[&dyn Migration],
+        reindex: &dyn Reindex,
+        limit: usize,
+    ) -> Result<MigrationProgress, Error> {
+        self.with_store_mut(name, |store| store.migrate(migrations, reindex, limit))
+    }
+
+    // export
+    // the data rows and sequence counters as stored, the data is never
+    // deserialized, db_query::import writes them back and rebuilds the
+    // index rows, the schema version belongs to the store so stays behind
+    // the store is read a page at a time as it can't be borrowed past the
+    // closure that holds it
+    pub fn export(&self, name: &str) -> Result<Export<'_>, Error> {
+        self.with_store(name, |_| Ok(()))?;
+
+        Ok(Export {
+            db: self,
+            name: name.to_string(),
+            cursor: None,
+            page: Vec::new().into_iter(),
+            done: false,
+        })
+    }
+}
+
+///
+/// Export
+/// the rows of Db::export, each page picks up after the last key read
+///
+
+pub struct Export<'a> {
+    db: &'a Db,
+    name: String,
+    cursor: Option<DataKey>,
+    page: std::vec::IntoIter<(DataKey, DataValue)>,
+    done: bool,
+}
+
+// EXPORT_PAGE
+// rows read per borrow of the store
+const EXPORT_PAGE: usize = 1_000;
+
+impl Iterator for Export<'_> {
+    type Item = (DataKey, DataValue);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(row) = self.page.next() {
+                return Some(row);
+            }
+            if self.done {
+                return None;
+            }
+
+            let (rows, cursor) = self
+                .db
+                .with_store(&self.name, |store| {
+                    Ok(store.export(self.cursor.as_ref(), EXPORT_PAGE))
+                })
+                .unwrap_or_default();
+            self.done = cursor.is_none();
+            self.cursor = cursor;
+            self.page = rows.into_iter();
+        }
+    }
 }
 
 ///
@@ -91,4 +243,270 @@ impl Store {
             data: BTreeMap::init(memory),
         }
     }
+
+    // stats
+    // a full scan that never deserializes the entity data
+    #[must_use]
+    pub fn stats(&self) -> StoreStats {
+        let mut stats = StoreStats::default();
+        for (key, value) in self.data.iter() {
+            stats.push(&key, &value);
+        }
+
+        stats
+    }
+
+    // clear_prefix
+    // removes the prefix key and every key that starts with it,
+    // the same range a prefix load reads, returning how many went
+    //
+    // the index rows pointing at those keys go too, they sit under
+    // {entity}#{field} and end with the data key, the sequence row at
+    // {entity}# is kept so a cleared entity doesn't hand out keys twice
+    pub fn clear_prefix(&mut self, prefix: &DataKey) -> u64 {
+        let end = prefix.create_upper_bound();
+        let keys: BTreeSet<DataKey> = self
+            .data
+            .range(prefix.clone()..=end)
+            .map(|(key, _)| key)
+            .collect();
+
+        let entities: BTreeSet<&str> = keys
+            .iter()
+            .filter_map(|key| key.parts().last())
+            .map(|(path, _)| path.as_str())
+            .collect();
+        let mut index_keys = Vec::new();
+        for entity in entities {
+            let start = DataKey::new(vec![(format!("{entity}#"), Vec::new())]);
+            let end = DataKey::new(vec![(format!("{entity}$"), Vec::new())]);
+            index_keys.extend(
+                self.data
+                    .range(start..end)
+                    .map(|(key, _)| key)
+                    .filter(|key| {
+                        key.parts().len() > 1
+                            && keys.contains(&DataKey::new(key.parts()[1..].to_vec()))
+                    }),
+            );
+        }
+
+        for key in keys.iter().chain(&index_keys) {
+            self.data.remove(key);
+        }
+
+        keys.len() as u64
+    }
+
+    // export
+    // scans at most limit rows after the cursor and keeps the data rows and
+    // sequence counters, the cursor is None once the end is reached
+    #[must_use]
+    pub fn export(
+        &self,
+        cursor: Option<&DataKey>,
+        limit: usize,
+    ) -> (Vec<(DataKey, DataValue)>, Option<DataKey>) {
+        let start = cursor.cloned().map_or(Bound::Unbounded, Bound::Excluded);
+        let mut scanned = 0;
+        let mut last = None;
+        let mut rows = Vec::new();
+        for (key, value) in self.data.range((start, Bound::Unbounded)).take(limit) {
+            scanned += 1;
+            if key.is_data() || key.sequence_entity().is_some() {
+                rows.push((key.clone(), value));
+            }
+            last = Some(key);
+        }
+
+        (rows, last.filter(|_| scanned == limit))
+    }
+
+    // purge_expired
+    // scans at most limit rows after the cursor and removes the ones past
+    // their expiry, so a large store is worked through a slice at a time
+    // rather than in one scan, index rows share the expiry of their row
+    pub fn purge_expired(
+        &mut self,
+        now: Timestamp,
+        cursor: Option<&DataKey>,
+        limit: usize,
+    ) -> PurgeProgress {
+        let start = cursor.cloned().map_or(Bound::Unbounded, Bound::Excluded);
+        let batch: Vec<(DataKey, bool)> = self
+            .data
+            .range((start, Bound::Unbounded))
+            .take(limit)
+            .map(|(key, value)| {
+                let expired = value.metadata.is_expired(now);
+                (key, expired)
+            })
+            .collect();
+
+        let cursor = if batch.len() < limit {
+            None
+        } else {
+            batch.last().map(|(key, _)| key.clone())
+        };
+        let removed: Vec<DataKey> = batch
+            .into_iter()
+            .filter_map(|(key, expired)| expired.then_some(key))
+            .collect();
+        for key in &removed {
+            self.data.remove(key);
+        }
+
+        PurgeProgress { removed, cursor }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ic::structures::{
+        memory::{MemoryId, MemoryManager},
+        DefaultMemoryImpl,
+    };
+
+    fn key(entity: &str, id: &str) -> DataKey {
+        DataKey::new(vec![(entity.to_string(), vec![id.to_string()])])
+    }
+
+    fn value(data: &[u8]) -> DataValue {
+        DataValue {
+            data: data.to_vec(),
+            metadata: Metadata {
+                created: 0.into(),
+                modified: 0.into(),
+                expires_at: None,
+            },
+        }
+    }
+
+    #[test]
+    fn test_clear_prefix() {
+        let mm = MemoryManager::init(DefaultMemoryImpl::default());
+        let mut store = Store::init(mm.get(MemoryId::new(0)));
+
+        let value = value(&[]);
+        for id in ["1", "2", "3"] {
+            store.data.insert(key("test::A", id), value.clone());
+            store.data.insert(key("test::B", id), value.clone());
+        }
+
+        // an index row for a cleared row and one for a kept row, and the
+        // sequence row, see db_query::index
+        let index = |entity: &str, id: &str| {
+            DataKey::new(vec![
+                (format!("{entity}#name"), vec!["00".to_string()]),
+                (entity.to_string(), vec![id.to_string()]),
+            ])
+        };
+        let sequence = DataKey::new(vec![("test::A#".to_string(), Vec::new())]);
+        store.data.insert(index("test::A", "1"), value.clone());
+        store.data.insert(index("test::B", "1"), value.clone());
+        store.data.insert(sequence.clone(), value.clone());
+
+        let prefix = DataKey::new(vec![("test::A".to_string(), Vec::new())]);
+        assert_eq!(store.clear_prefix(&prefix), 3);
+        assert_eq!(store.data.len(), 5);
+        assert!(store.data.contains_key(&key("test::B", "1")));
+        assert!(!store.data.contains_key(&index("test::A", "1")));
+        assert!(store.data.contains_key(&index("test::B", "1")));
+        assert!(store.data.contains_key(&sequence));
+    }
+
+    #[test]
+    fn test_purge_expired() {
+        let mm = MemoryManager::init(DefaultMemoryImpl::default());
+        let mut store = Store::init(mm.get(MemoryId::new(0)));
+
+        for id in ["1", "2", "3", "4", "5"] {
+            let mut value = value(&[]);
+            if id != "3" {
+                value.metadata.expires_at = Some(10.into());
+            }
+            store.data.insert(key("test::A", id), value);
+        }
+
+        // nothing has expired yet, the runs still walk the whole store
+        let progress = store.purge_expired(5.into(), None, 10);
+        assert!(progress.removed.is_empty());
+        assert_eq!(progress.cursor, None);
+
+        // two rows a run, resuming from the cursor
+        let mut runs = 0;
+        let mut removed = Vec::new();
+        let mut cursor = None;
+        loop {
+            let progress = store.purge_expired(10.into(), cursor.as_ref(), 2);
+            removed.extend(progress.removed);
+            runs += 1;
+            cursor = progress.cursor;
+            if cursor.is_none() {
+                break;
+            }
+        }
+        assert_eq!(runs, 3);
+        assert_eq!(removed.len(), 4);
+        assert_eq!(store.data.len(), 1);
+        assert!(store.data.contains_key(&key("test::A", "3")));
+    }
+
+    #[test]
+    fn test_export() {
+        let mm = MemoryManager::init(DefaultMemoryImpl::default());
+        let mut store = Store::init(mm.get(MemoryId::new(0)));
+
+        for id in ["1", "2", "3"] {
+            store.data.insert(key("test::A", id), value(&[]));
+        }
+        let index = DataKey::new(vec![
+            ("test::A#name".to_string(), vec!["00".to_string()]),
+            ("test::A".to_string(), vec!["1".to_string()]),
+        ]);
+        let sequence = DataKey::new(vec![("test::A#".to_string(), Vec::new())]);
+        store.data.insert(index, value(&[]));
+        store.data.insert(sequence.clone(), value(&[3]));
+        store.data.insert(migrate::migration_key(), value(&[]));
+
+        // the index rows and schema version count towards the limit but
+        // aren't exported
+        let mut cursor = None;
+        let mut keys = Vec::new();
+        loop {
+            let (rows, next) = store.export(cursor.as_ref(), 2);
+            keys.extend(rows.into_iter().map(|(key, _)| key));
+            cursor = next;
+            if cursor.is_none() {
+                break;
+            }
+        }
+        assert_eq!(
+            keys,
+            vec![
+                key("test::A", "1"),
+                key("test::A", "2"),
+                key("test::A", "3"),
+                sequence
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "test")]
+    fn test_memory_db() {
+        let mut db = Db::new();
+        db.insert_memory("test::Store");
+
+        db.with_store_mut("test::Store", |store| {
+            store.data.insert(key("test::A", "1"), value(&[1]));
+            Ok(())
+        })
+        .unwrap();
+
+        let rows: Vec<_> = db.export("test::Store").unwrap().collect();
+        assert_eq!(rows.len(), 1);
+        assert!(db.stats("test::Missing").is_err());
+    }
 }
@@ -0,0 +1,417 @@
+use crate::{DataKey, DataValue, Error, Metadata, Store};
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+use std::ops::Bound;
+use types::Timestamp;
+
+///
+/// Migration
+///
+/// moves the rows of a store from one schema version to the next, a store
+/// starts at version 0 and the runner applies the migration whose
+/// from_version matches the stored version until none do
+///
+/// only the rows applies_to picks out are migrated, so a migration has to
+/// say which entities it is for, index and other bookkeeping rows are never
+/// passed to it
+///
+
+pub trait Migration {
+    #[allow(clippy::wrong_self_convention)]
+    fn from_version(&self) -> u32;
+
+    fn to_version(&self) -> u32;
+
+    fn applies_to(&self, key: &DataKey) -> bool;
+
+    fn migrate(&self, value: &mut DataValue) -> Result<(), String>;
+}
+
+///
+/// Reindex
+///
+/// the index rows a data row should have, a migration can change indexed
+/// fields so the runner swaps the old index rows for the new ones,
+/// db_query implements it from the schema
+///
+
+pub trait Reindex {
+    fn index_keys(&self, key: &DataKey, data: &[u8]) -> Result<Vec<DataKey>, String>;
+}
+
+///
+/// MigrationProgress
+///
+/// version : the schema version the store is now at
+/// rows    : how many rows were scanned by this run, index and bookkeeping
+///           rows included
+/// done    : false if the run stopped at the row limit, run it again to resume
+///
+
+#[derive(CandidType, Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct MigrationProgress {
+    pub version: u32,
+    pub rows: u64,
+    pub done: bool,
+}
+
+///
+/// MigrationState
+/// the marker row, cursor is the last row migrated to the next version
+///
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct MigrationState {
+    version: u32,
+    cursor: Option<DataKey>,
+}
+
+// migration_key
+// like the sequence counters it sorts outside any entity's range
+#[must_use]
+pub fn migration_key() -> DataKey {
+    DataKey::new(vec![("#schema_version".to_string(), Vec::new())])
+}
+
+impl Store {
+    // schema_version
+    pub fn schema_version(&self) -> Result<u32, Error> {
+        Ok(self.migration_state()?.version)
+    }
+
+    // migrate
+    // scans at most limit rows, the index rows between the data rows count
+    // as they cost the same to read, the cursor is saved when a run stops at
+    // the limit or on a failed row, so the next run picks up after the last
+    // row that was migrated, and a store that is already migrated is left
+    // alone
+    // a trap rolls back the whole run, cursor included, so it's redone
+    pub fn migrate(
+        &mut self,
+        migrations: &[&dyn Migration],
+        reindex: &dyn Reindex,
+        limit: usize,
+    ) -> Result<MigrationProgress, Error> {
+        let mut state = self.migration_state()?;
+        let mut rows = 0;
+
+        while let Some(migration) = migrations
+            .iter()
+            .find(|m| m.from_version() == state.version)
+        {
+            let to_version = migration.to_version();
+            if to_version <= state.version {
+                return Err(Error::migration(
+                    state.version,
+                    to_version,
+                    "",
+                    "version must increase",
+                ));
+            }
+
+            // the next batch of rows after the cursor
+            let start = state
+                .cursor
+                .clone()
+                .map_or(Bound::Unbounded, Bound::Excluded);
+            let batch: Vec<(DataKey, DataValue)> = self
+                .data
+                .range((start, Bound::Unbounded))
+                .take(limit - rows)
+                .collect();
+            let finished = batch.len() < limit - rows;
+
+            for (key, value) in batch {
+                if key.is_data() && migration.applies_to(&key) {
+                    if let Err(msg) = self.migrate_row(*migration, reindex, &key, value) {
+                        self.set_migration_state(&state)?;
+
+                        return Err(Error::migration(
+                            state.version,
+                            to_version,
+                            &key.to_string(),
+                            &msg,
+                        ));
+                    }
+                }
+                state.cursor = Some(key);
+                rows += 1;
+            }
+
+            if !finished {
+                self.set_migration_state(&state)?;
+
+                return Ok(MigrationProgress {
+                    version: state.version,
+                    rows: rows as u64,
+                    done: false,
+                });
+            }
+
+            state = MigrationState {
+                version: to_version,
+                cursor: None,
+            };
+            self.set_migration_state(&state)?;
+        }
+
+        Ok(MigrationProgress {
+            version: state.version,
+            rows: rows as u64,
+            done: true,
+        })
+    }
+
+    // migrate_row
+    // the row and its index rows are only written once the migration and
+    // both sets of index keys have come back fine
+    fn migrate_row(
+        &mut self,
+        migration: &dyn Migration,
+        reindex: &dyn Reindex,
+        key: &DataKey,
+        mut value: DataValue,
+    ) -> Result<(), String> {
+        let old_index_keys = reindex.index_keys(key, &value.data)?;
+        migration.migrate(&mut value)?;
+        let new_index_keys = reindex.index_keys(key, &value.data)?;
+
+        let index_value = DataValue {
+            data: Vec::new(),
+            metadata: value.metadata.clone(),
+        };
+        for index_key in &old_index_keys {
+            self.data.remove(index_key);
+        }
+        for index_key in new_index_keys {
+            self.data.insert(index_key, index_value.clone());
+        }
+        self.data.insert(key.clone(), value);
+
+        Ok(())
+    }
+
+    // migration_state
+    fn migration_state(&self) -> Result<MigrationState, Error> {
+        self.data
+            .get(&migration_key())
+            .map(|value| lib_cbor::deserialize(&value.data))
+            .transpose()
+            .map(Option::unwrap_or_default)
+            .map_err(Error::from)
+    }
+
+    // set_migration_state
+    fn set_migration_state(&mut self, state: &MigrationState) -> Result<(), Error> {
+        let now = Timestamp::now();
+        let created = self
+            .data
+            .get(&migration_key())
+            .map_or(now, |value| value.metadata.created);
+
+        let value = DataValue {
+            data: lib_cbor::serialize(state)?,
+            metadata: Metadata {
+                created,
+                modified: now,
+                expires_at: None,
+            },
+        };
+        self.data.insert(migration_key(), value);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ic::structures::{
+        memory::{MemoryId, MemoryManager},
+        DefaultMemoryImpl,
+    };
+
+    struct Push {
+        from: u32,
+        byte: u8,
+    }
+
+    impl Migration for Push {
+        fn from_version(&self) -> u32 {
+            self.from
+        }
+
+        fn to_version(&self) -> u32 {
+            self.from + 1
+        }
+
+        fn applies_to(&self, key: &DataKey) -> bool {
+            key.parts()[0].0 == "test::A"
+        }
+
+        fn migrate(&self, value: &mut DataValue) -> Result<(), String> {
+            if value.data.is_empty() {
+                return Err("empty row".to_string());
+            }
+            value.data.push(self.byte);
+
+            Ok(())
+        }
+    }
+
+    // ByteIndex
+    // one index row keyed by the last byte of the row
+    struct ByteIndex;
+
+    impl Reindex for ByteIndex {
+        fn index_keys(&self, key: &DataKey, data: &[u8]) -> Result<Vec<DataKey>, String> {
+            let Some(byte) = data.last() else {
+                return Ok(Vec::new());
+            };
+            let mut parts = vec![("test::A#byte".to_string(), vec![byte.to_string()])];
+            parts.extend_from_slice(key.parts());
+
+            Ok(vec![DataKey::new(parts)])
+        }
+    }
+
+    fn value(data: &[u8]) -> DataValue {
+        DataValue {
+            data: data.to_vec(),
+            metadata: Metadata {
+                created: 0.into(),
+                modified: 0.into(),
+                expires_at: None,
+            },
+        }
+    }
+
+    #[test]
+    fn test_migrate() {
+        let mm = MemoryManager::init(DefaultMemoryImpl::default());
+        let mut store = Store::init(mm.get(MemoryId::new(0)));
+        for id in ["1", "2", "3"] {
+            store
+                .data
+                .insert(DataKey::from_path("test::A", &[id]), value(&[0]));
+        }
+
+        let first = Push { from: 0, byte: 1 };
+        let second = Push { from: 1, byte: 2 };
+        let migrations: [&dyn Migration; 2] = [&first, &second];
+
+        // the first run stops at the limit and the second resumes after it
+        let progress = store.migrate(&migrations, &ByteIndex, 2).unwrap();
+        assert_eq!(
+            (progress.version, progress.rows, progress.done),
+            (0, 2, false)
+        );
+        // the rest of the first version then the second, which also scans
+        // the marker and the index rows the first one wrote
+        let progress = store.migrate(&migrations, &ByteIndex, 100).unwrap();
+        assert_eq!(
+            (progress.version, progress.rows, progress.done),
+            (2, 10, true)
+        );
+
+        for (key, value) in store.data.iter() {
+            if key.is_data() {
+                assert_eq!(value.data, vec![0, 1, 2]);
+            }
+        }
+
+        // the index rows moved with the rows
+        let index_rows: Vec<_> = store
+            .data
+            .iter()
+            .filter(|(key, _)| key.parts()[0].0 == "test::A#byte")
+            .map(|(key, _)| key.parts()[0].1[0].clone())
+            .collect();
+        assert_eq!(index_rows, vec!["2"; 3]);
+
+        // nothing left to run
+        let progress = store.migrate(&migrations, &ByteIndex, 100).unwrap();
+        assert_eq!((progress.version, progress.rows), (2, 0));
+        assert_eq!(store.schema_version().unwrap(), 2);
+    }
+
+    #[test]
+    fn test_migrate_error_resumes() {
+        let mm = MemoryManager::init(DefaultMemoryImpl::default());
+        let mut store = Store::init(mm.get(MemoryId::new(0)));
+        store
+            .data
+            .insert(DataKey::from_path("test::A", &["1"]), value(&[0]));
+        store
+            .data
+            .insert(DataKey::from_path("test::A", &["2"]), value(&[]));
+
+        let push = Push { from: 0, byte: 1 };
+        assert!(store.migrate(&[&push], &ByteIndex, 10).is_err());
+
+        // fix the bad row, the one already migrated isn't touched again, the
+        // scan goes on to the bad row and the index row the first one wrote
+        store
+            .data
+            .insert(DataKey::from_path("test::A", &["2"]), value(&[0]));
+        let progress = store.migrate(&[&push], &ByteIndex, 10).unwrap();
+        assert_eq!((progress.version, progress.rows), (1, 2));
+        for (key, value) in store.data.iter() {
+            if key.is_data() {
+                assert_eq!(value.data, vec![0, 1]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_migrate_index_rows_count() {
+        let mm = MemoryManager::init(DefaultMemoryImpl::default());
+        let mut store = Store::init(mm.get(MemoryId::new(0)));
+        for id in ["1", "2", "3"] {
+            let key = ByteIndex
+                .index_keys(&DataKey::from_path("test::A", &[id]), &[9])
+                .unwrap();
+            store.data.insert(key[0].clone(), value(&[]));
+        }
+        store
+            .data
+            .insert(DataKey::from_path("test::B", &["1"]), value(&[0]));
+
+        // a run that only reaches index rows still stops at the limit
+        let push = Push { from: 0, byte: 1 };
+        let progress = store.migrate(&[&push], &ByteIndex, 2).unwrap();
+        assert_eq!(
+            (progress.version, progress.rows, progress.done),
+            (0, 2, false)
+        );
+        let progress = store.migrate(&[&push], &ByteIndex, 2).unwrap();
+        assert_eq!(
+            (progress.version, progress.rows, progress.done),
+            (0, 2, false)
+        );
+        let progress = store.migrate(&[&push], &ByteIndex, 2).unwrap();
+        assert_eq!(
+            (progress.version, progress.rows, progress.done),
+            (1, 0, true)
+        );
+    }
+
+    #[test]
+    fn test_migrate_applies_to() {
+        let mm = MemoryManager::init(DefaultMemoryImpl::default());
+        let mut store = Store::init(mm.get(MemoryId::new(0)));
+        store
+            .data
+            .insert(DataKey::from_path("test::A", &["1"]), value(&[0]));
+        store
+            .data
+            .insert(DataKey::from_path("test::B", &["1"]), value(&[0]));
+
+        // rows of other entities are scanned past but left as they were
+        let push = Push { from: 0, byte: 1 };
+        let progress = store.migrate(&[&push], &ByteIndex, 10).unwrap();
+        assert_eq!((progress.version, progress.rows), (1, 2));
+        let b = store.data.get(&DataKey::from_path("test::B", &["1"]));
+        assert_eq!(b.unwrap().data, vec![0]);
+    }
+}
@@ -1,8 +1,9 @@
+use crate::Error;
 use candid::CandidType;
 use ic::structures::{storable::Bound, Storable};
 use mimic_derive::Storable;
 use serde::{Deserialize, Serialize};
-use std::{borrow::Cow, fmt};
+use std::{borrow::Cow, fmt, str::FromStr};
 use types::Timestamp;
 
 ///
@@ -27,6 +28,12 @@ impl DataRow {
     }
 }
 
+impl fmt::Display for DataRow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} => {}", self.key, self.value)
+    }
+}
+
 ///
 /// DataValue
 ///
@@ -37,9 +44,19 @@ pub struct DataValue {
     pub metadata: Metadata,
 }
 
+impl fmt::Display for DataValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} bytes, {}", self.data.len(), self.metadata)
+    }
+}
+
 ///
 /// DataKey
 ///
+/// string form : path[key,key],path[key]
+/// the parts are joined with ',' and a backslash escapes any '\\', '[', ']'
+/// or ',' inside a path or key, so Display and parse round-trip
+///
 
 #[derive(CandidType, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
 pub struct DataKey(Vec<(String, Vec<String>)>);
@@ -50,6 +67,143 @@ impl DataKey {
         Self(parts)
     }
 
+    // from_path
+    // a single part key, ie. for building prefixes without an entity
+    #[must_use]
+    pub fn from_path<T: ToString>(path: &str, keys: &[T]) -> Self {
+        let keys = keys.iter().map(ToString::to_string).collect();
+
+        Self(vec![(path.to_string(), keys)])
+    }
+
+    // parse
+    // the inverse of Display
+    pub fn parse(s: &str) -> Result<Self, Error> {
+        let err = |msg: &str| Error::invalid_data_key(s, msg);
+        let mut chars = s.chars().peekable();
+        let mut parts = Vec::new();
+
+        loop {
+            // path
+            let path = Self::parse_token(&mut chars, '[').ok_or_else(|| err("missing '['"))?;
+            if path.is_empty() {
+                return Err(err("empty path"));
+            }
+
+            // keys
+            let mut keys = Vec::new();
+            if chars.peek() == Some(&']') {
+                chars.next();
+            } else {
+                loop {
+                    let key = Self::parse_key(&mut chars).ok_or_else(|| err("missing ']'"))?;
+                    keys.push(key.0);
+                    if key.1 == ']' {
+                        break;
+                    }
+                }
+            }
+            parts.push((path, keys));
+
+            // separator
+            match chars.next() {
+                None => break,
+                Some(',') => {}
+                Some(_) => return Err(err("expected ',' between parts")),
+            }
+        }
+
+        Ok(Self(parts))
+    }
+
+    // parse_token
+    // reads up to an unescaped delimiter, consuming it
+    fn parse_token(
+        chars: &mut std::iter::Peekable<std::str::Chars>,
+        delim: char,
+    ) -> Option<String> {
+        let mut token = String::new();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => token.push(chars.next()?),
+                c if c == delim => return Some(token),
+                '[' | ']' | ',' => return None,
+                c => token.push(c),
+            }
+        }
+
+        None
+    }
+
+    // parse_key
+    // reads a key and the unescaped ',' or ']' that ended it
+    fn parse_key(chars: &mut std::iter::Peekable<std::str::Chars>) -> Option<(String, char)> {
+        let mut key = String::new();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => key.push(chars.next()?),
+                ',' => return Some((key, ',')),
+                ']' => return Some((key, ']')),
+                '[' => return None,
+                c => key.push(c),
+            }
+        }
+
+        None
+    }
+
+    // validate
+    // the checks a key needs before it's written without going through an
+    // entity, every part needs a path and there has to be at least one part
+    pub fn validate(&self) -> Result<(), Error> {
+        let err = |msg: &str| Err(Error::invalid_data_key(&self.to_string(), msg));
+
+        if self.0.is_empty() {
+            return err("no parts");
+        }
+        if self.0.iter().any(|(path, _)| path.is_empty()) {
+            return err("empty path");
+        }
+
+        Ok(())
+    }
+
+    // parts
+    #[must_use]
+    pub fn parts(&self) -> &[(String, Vec<String>)] {
+        &self.0
+    }
+
+    // is_data
+    // false for the rows kept alongside the entity rows, the index rows,
+    // sequence counters and schema version, their first path has a #
+    #[must_use]
+    pub fn is_data(&self) -> bool {
+        self.0.first().is_some_and(|(path, _)| !path.contains('#'))
+    }
+
+    // is_index
+    // an index row, {entity}#{field} followed by the data key it points at
+    #[must_use]
+    pub fn is_index(&self) -> bool {
+        self.0.first().is_some_and(|(path, _)| {
+            path.split_once('#')
+                .is_some_and(|(entity, field)| !entity.is_empty() && !field.is_empty())
+        })
+    }
+
+    // sequence_entity
+    // the entity of a sequence counter row, {entity}# with no keys
+    #[must_use]
+    pub fn sequence_entity(&self) -> Option<&str> {
+        match self.0.as_slice() {
+            [(path, keys)] if keys.is_empty() => path
+                .strip_suffix('#')
+                .filter(|entity| !entity.is_empty() && !entity.contains('#')),
+            _ => None,
+        }
+    }
+
     // create_upper_bound
     #[must_use]
     pub fn create_upper_bound(&self) -> Self {
@@ -72,16 +226,42 @@ impl fmt::Display for DataKey {
         let mut first = true;
         for (path, keys) in &self.0 {
             if !first {
-                write!(f, ", ")?;
+                write!(f, ",")?;
             }
             first = false;
 
-            write!(f, "{}({})", path, keys.join(", "))?;
+            let keys: Vec<Cow<str>> = keys.iter().map(|key| escape(key)).collect();
+            write!(f, "{}[{}]", escape(path), keys.join(","))?;
         }
         Ok(())
     }
 }
 
+impl FromStr for DataKey {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+// escape
+fn escape(s: &str) -> Cow<'_, str> {
+    if s.contains(['\\', '[', ']', ',']) {
+        let mut escaped = String::with_capacity(s.len() + 2);
+        for c in s.chars() {
+            if matches!(c, '\\' | '[' | ']' | ',') {
+                escaped.push('\\');
+            }
+            escaped.push(c);
+        }
+
+        Cow::Owned(escaped)
+    } else {
+        Cow::Borrowed(s)
+    }
+}
+
 impl Storable for DataKey {
     fn to_bytes(&self) -> Cow<[u8]> {
         Cow::Owned(lib_cbor::serialize(self).unwrap())
@@ -97,6 +277,35 @@ impl Storable for DataKey {
     };
 }
 
+///
+/// ImportPolicy
+/// what an import does with a row whose key is already in the store
+///
+/// Skip      : keep the existing row
+/// Overwrite : replace it with the imported row, the index rows of the
+///             replaced row are removed first
+/// Fail      : error with KeyExists before anything is written
+///
+
+#[derive(CandidType, Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum ImportPolicy {
+    Skip,
+    #[default]
+    Overwrite,
+    Fail,
+}
+
+///
+/// ImportSummary
+///
+
+#[derive(CandidType, Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ImportSummary {
+    pub inserted: u64,
+    pub skipped: u64,
+    pub failed: u64,
+}
+
 ///
 /// Metadata
 ///
@@ -105,6 +314,86 @@ impl Storable for DataKey {
 pub struct Metadata {
     pub created: Timestamp,
     pub modified: Timestamp,
+
+    #[serde(default)]
+    pub expires_at: Option<Timestamp>,
+}
+
+impl Metadata {
+    // is_expired
+    #[must_use]
+    pub fn is_expired(&self, now: Timestamp) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+}
+
+impl fmt::Display for Metadata {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "created {}, modified {}",
+            self.created.to_rfc3339(),
+            self.modified.to_rfc3339()
+        )?;
+        if let Some(expires_at) = self.expires_at {
+            write!(f, ", expires {}", expires_at.to_rfc3339())?;
+        }
+
+        Ok(())
+    }
+}
+
+///
+/// PurgeProgress
+///
+/// removed : the expired rows this run removed
+/// cursor  : the last row scanned, pass it to the next run to resume,
+///           None once the run reached the end of the store
+///
+
+#[derive(CandidType, Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct PurgeProgress {
+    pub removed: Vec<DataKey>,
+    pub cursor: Option<DataKey>,
+}
+
+///
+/// StoreStats
+/// a health snapshot of a store, built from keys and metadata only
+///
+/// rows       : entity rows only
+/// bytes      : the serialized entity data, keys and metadata are not counted
+/// index_rows : secondary index rows, sequence counters and the schema
+///              version aren't counted anywhere
+///
+
+#[derive(CandidType, Clone, Debug, Default, Serialize, Deserialize)]
+pub struct StoreStats {
+    pub rows: u64,
+    pub bytes: u64,
+    pub oldest_modified: Option<Timestamp>,
+    pub newest_modified: Option<Timestamp>,
+
+    #[serde(default)]
+    pub index_rows: u64,
+}
+
+impl StoreStats {
+    // push
+    pub fn push(&mut self, key: &DataKey, value: &DataValue) {
+        if key.is_index() {
+            self.index_rows += 1;
+        }
+        if !key.is_data() {
+            return;
+        }
+        let modified = value.metadata.modified;
+
+        self.rows += 1;
+        self.bytes += value.data.len() as u64;
+        self.oldest_modified = Some(self.oldest_modified.map_or(modified, |t| t.min(modified)));
+        self.newest_modified = Some(self.newest_modified.map_or(modified, |t| t.max(modified)));
+    }
 }
 
 #[cfg(test)]
@@ -184,4 +473,107 @@ mod tests {
             "Rarity('123123') should be less than Rarity('~')"
         );
     }
+
+    #[test]
+    fn test_data_row_display() {
+        let key = DataKey::new(vec![("test::Entity".to_string(), vec!["1".to_string()])]);
+        let value = DataValue {
+            data: vec![0; 4],
+            metadata: Metadata {
+                created: 0.into(),
+                modified: 60.into(),
+                expires_at: None,
+            },
+        };
+
+        assert_eq!(
+            DataRow::new(key, value).to_string(),
+            "test::Entity[1] => 4 bytes, created 1970-01-01T00:00:00Z, modified 1970-01-01T00:01:00Z"
+        );
+    }
+
+    #[test]
+    fn test_store_stats_push() {
+        let value = |data: usize, modified: u64| DataValue {
+            data: vec![0; data],
+            metadata: Metadata {
+                created: 0.into(),
+                modified: modified.into(),
+                expires_at: None,
+            },
+        };
+
+        let mut stats = StoreStats::default();
+        let key = DataKey::from_path("test::Entity", &["1"]);
+        for v in [value(3, 20), value(0, 10), value(5, 30)] {
+            stats.push(&key, &v);
+        }
+
+        // index, sequence and schema version rows aren't entity rows
+        let index = DataKey::new(vec![
+            ("test::Entity#name".to_string(), vec!["00".to_string()]),
+            ("test::Entity".to_string(), vec!["1".to_string()]),
+        ]);
+        stats.push(&index, &value(0, 5));
+        for path in ["test::Entity#", "#schema_version"] {
+            let key = DataKey::new(vec![(path.to_string(), Vec::new())]);
+            stats.push(&key, &value(4, 5));
+        }
+
+        assert_eq!(stats.rows, 3);
+        assert_eq!(stats.bytes, 8);
+        assert_eq!(stats.index_rows, 1);
+        assert_eq!(stats.oldest_modified, Some(10.into()));
+        assert_eq!(stats.newest_modified, Some(30.into()));
+    }
+
+    #[test]
+    fn test_metadata_is_expired() {
+        let metadata = Metadata {
+            created: 0.into(),
+            modified: 0.into(),
+            expires_at: Some(100.into()),
+        };
+
+        assert!(!metadata.is_expired(99.into()));
+        assert!(metadata.is_expired(100.into()));
+
+        let forever = Metadata {
+            expires_at: None,
+            ..metadata
+        };
+        assert!(!forever.is_expired(u64::MAX.into()));
+    }
+
+    #[test]
+    fn test_data_key_parse_roundtrip() {
+        let keys = [
+            DataKey::from_path("design::Character", &[1]),
+            DataKey::new(vec![
+                ("design::Character".to_string(), vec!["1".to_string()]),
+                (
+                    "Pet".to_string(),
+                    vec!["a,b".to_string(), "[c]\\".to_string()],
+                ),
+            ]),
+            DataKey::new(vec![("design::Empty".to_string(), vec![])]),
+        ];
+
+        for key in keys {
+            let s = key.to_string();
+            assert_eq!(DataKey::parse(&s).unwrap(), key, "{s}");
+        }
+
+        assert_eq!(DataKey::from_path("a", &["1", "2"]).to_string(), "a[1,2]");
+        assert_eq!(
+            DataKey::parse("a[1],b[]").unwrap(),
+            DataKey::new(vec![
+                ("a".to_string(), vec!["1".to_string()]),
+                ("b".to_string(), vec![]),
+            ])
+        );
+        assert!(DataKey::parse("a[1").is_err());
+        assert!(DataKey::parse("a[1]b[2]").is_err());
+        assert!(DataKey::parse("[1]").is_err());
+    }
 }
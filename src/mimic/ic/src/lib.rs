@@ -12,6 +12,7 @@ pub use helper::get_wasm_hash;
 use candid::CandidType;
 use serde::{Deserialize, Serialize};
 use snafu::Snafu;
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
 
 ///
 /// CYCLES
@@ -37,14 +38,25 @@ pub fn format_cycles(cycles: u128) -> String {
 
 #[derive(CandidType, Debug, Serialize, Deserialize, Snafu)]
 pub enum Error {
+    #[snafu(transparent)]
+    BTreeMap {
+        source: structures::btreemap::BTreeMapError,
+    },
+
     #[snafu(transparent)]
     Cell { source: structures::cell::CellError },
+
+    #[snafu(transparent)]
+    Memory {
+        source: structures::memory::MemoryError,
+    },
 }
 
 ///
 /// Logging
 ///
 
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum Log {
     Ok,
     Perf,
@@ -53,34 +65,178 @@ pub enum Log {
     Error,
 }
 
+impl Log {
+    // label
+    #[must_use]
+    pub const fn label(&self) -> &'static str {
+        match self {
+            Self::Ok => "OK",
+            Self::Perf => "PERF",
+            Self::Info => "INFO",
+            Self::Warn => "WARN",
+            Self::Error => "ERROR",
+        }
+    }
+
+    // rank
+    // Ok is a success message so it sits with Info, Perf is the chattiest
+    #[must_use]
+    pub const fn rank(&self) -> u8 {
+        match self {
+            Self::Perf => 0,
+            Self::Ok | Self::Info => 1,
+            Self::Warn => 2,
+            Self::Error => 3,
+        }
+    }
+}
+
+// MIN_LOG_RANK
+// canisters are single threaded, the atomic just keeps the static safe
+static MIN_LOG_RANK: AtomicU8 = AtomicU8::new(0);
+
+// set_log_level
+// logs below the level are skipped before they're formatted, call from init
+pub fn set_log_level(level: Log) {
+    MIN_LOG_RANK.store(level.rank(), Ordering::Relaxed);
+}
+
+// log_enabled
+#[must_use]
+pub fn log_enabled(level: Log) -> bool {
+    level.rank() >= MIN_LOG_RANK.load(Ordering::Relaxed)
+}
+
+///
+/// LogFormat
+///
+/// Text : a coloured line with the fields as key=value after the message
+/// Json : one json object per line, for log aggregators
+///
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum LogFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+// LOG_JSON
+static LOG_JSON: AtomicBool = AtomicBool::new(false);
+
+// set_log_format
+pub fn set_log_format(format: LogFormat) {
+    LOG_JSON.store(format == LogFormat::Json, Ordering::Relaxed);
+}
+
+// log_write
+// what log! expands to once the level has passed the filter
+pub fn log_write(level: Log, message: &str, fields: &[(&str, String)]) {
+    if LOG_JSON.load(Ordering::Relaxed) {
+        ic_cdk::println!("{}", log_json(level, message, fields, lib_time::now()));
+    } else {
+        ic_cdk::println!("{}", log_text(level, message, fields));
+    }
+}
+
+// log_text
+fn log_text(level: Log, message: &str, fields: &[(&str, String)]) -> String {
+    let colour = match level {
+        Log::Ok => 32,
+        Log::Perf => 35,
+        Log::Info => 34,
+        Log::Warn => 33,
+        Log::Error => 31,
+    };
+    let mut line = format!("\x1b[{colour}m{}\x1b[0m: {message}", level.label());
+    for (key, value) in fields {
+        line.push_str(&format!(" {key}={value}"));
+    }
+
+    line
+}
+
+// log_json
+fn log_json(level: Log, message: &str, fields: &[(&str, String)], timestamp: u64) -> String {
+    let fields: serde_json::Map<String, serde_json::Value> = fields
+        .iter()
+        .map(|(key, value)| ((*key).to_string(), value.clone().into()))
+        .collect();
+
+    serde_json::json!({
+        "level": level.label(),
+        "message": message,
+        "fields": fields,
+        "timestamp": timestamp,
+    })
+    .to_string()
+}
+
 #[macro_export]
 macro_rules! log {
+    // Inner macro for actual logging logic to avoid code duplication
+    (@inner $level:expr, [$($key:ident = $value:expr),*], $fmt:expr, $($arg:tt)*) => {{
+        let level: $crate::Log = $level;
+        if $crate::log_enabled(level) {
+            let fields: &[(&str, String)] = &[$((stringify!($key), $value.to_string())),*];
+            $crate::log_write(level, &format!($fmt, $($arg)*), fields);
+        }
+    }};
+
+    // Match when structured fields follow the arguments after a semicolon
+    ($level:expr, $fmt:expr $(, $arg:expr)* ; $($key:ident = $value:expr),+ $(,)?) => {{
+        $crate::log!(@inner $level, [$($key = $value),+], $fmt, $($arg),*);
+    }};
+
     // Match when only the format string is provided (no additional args)
     ($level:expr, $fmt:expr) => {{
         // Pass an empty set of arguments to @inner
-        log!(@inner $level, $fmt,);
+        $crate::log!(@inner $level, [], $fmt,);
     }};
 
     // Match when additional arguments are provided
     ($level:expr, $fmt:expr, $($arg:tt)*) => {{
-        log!(@inner $level, $fmt, $($arg)*);
+        $crate::log!(@inner $level, [], $fmt, $($arg)*);
     }};
+}
 
-    // Inner macro for actual logging logic to avoid code duplication
-    (@inner $level:expr, $fmt:expr, $($arg:tt)*) => {{
-        let level: Log = $level;
-        let formatted_message = format!($fmt, $($arg)*);  // Apply formatting with args
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        let msg = match level {
-            Log::Ok => format!("\x1b[32mOK\x1b[0m: {}", formatted_message),
-            Log::Perf => format!("\x1b[35mPERF\x1b[0m: {}", formatted_message),
-            Log::Info => format!("\x1b[34mINFO\x1b[0m: {}", formatted_message),
-            Log::Warn => format!("\x1b[33mWARN\x1b[0m: {}", formatted_message),
-            Log::Error => format!("\x1b[31mERROR\x1b[0m: {}", formatted_message),
+    #[test]
+    fn test_log_level() {
+        assert!(log_enabled(Log::Perf));
 
-            _ => formatted_message,
-        };
+        set_log_level(Log::Warn);
+        assert!(!log_enabled(Log::Info));
+        assert!(!log_enabled(Log::Ok));
+        assert!(log_enabled(Log::Warn));
+        assert!(log_enabled(Log::Error));
 
-        println!("{}", msg);
-    }};
+        set_log_level(Log::Perf);
+        assert!(log_enabled(Log::Perf));
+    }
+
+    #[test]
+    fn test_log_format() {
+        let fields = [
+            ("canister", "aaaaa-aa".to_string()),
+            ("kind", "\"x\"".to_string()),
+        ];
+
+        assert_eq!(
+            log_text(Log::Info, "cycles low", &fields),
+            "\x1b[34mINFO\x1b[0m: cycles low canister=aaaaa-aa kind=\"x\""
+        );
+        assert_eq!(
+            log_json(Log::Warn, "cycles low", &fields, 10),
+            r#"{"fields":{"canister":"aaaaa-aa","kind":"\"x\""},"level":"WARN","message":"cycles low","timestamp":10}"#
+        );
+
+        // both forms of the macro still expand
+        let id = 7;
+        log!(Log::Info, "plain {id}");
+        log!(Log::Info, "with {}", id; id = id, kind = "x");
+    }
 }
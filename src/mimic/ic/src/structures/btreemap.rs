@@ -1,19 +1,36 @@
-use crate::structures::memory::VirtualMemory;
-use derive_more::{Deref, DerefMut};
-use ic_stable_structures::{btreemap::BTreeMap as WrappedBTreeMap, Storable};
+use crate::{structures::memory::VirtualMemory, Error};
+use candid::CandidType;
+use ic_stable_structures::{btreemap::BTreeMap as WrappedBTreeMap, storable::Bound, Storable};
+use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+use std::{borrow::Cow, marker::PhantomData, ops};
+
+///
+/// BTreeMapError
+///
+
+#[derive(CandidType, Debug, Serialize, Deserialize, Snafu)]
+pub enum BTreeMapError {
+    #[snafu(display("value too large: {size} bytes, max {max_size}"))]
+    ValueTooLarge { size: u64, max_size: u32 },
+}
 
 //
 // BTreeMap
 // a wrapper around BTreeMap that uses the default VirtualMemory
 //
+// the wrapped map holds each value as the bytes V encodes to, so a value is
+// encoded once on insert and that's what gets checked against the bound,
+// the bytes are the same as V's own so the stable layout doesn't change
+//
 
-#[derive(Deref, DerefMut)]
 pub struct BTreeMap<K, V>
 where
     K: Storable + Ord + Clone,
     V: Storable,
 {
-    data: WrappedBTreeMap<K, V, VirtualMemory>,
+    data: WrappedBTreeMap<K, Encoded<V>, VirtualMemory>,
+    bound: Bound,
 }
 
 impl<K, V> BTreeMap<K, V>
@@ -23,25 +40,257 @@ where
 {
     #[must_use]
     pub fn init(memory: VirtualMemory) -> Self {
+        Self::init_with_bound(memory, V::BOUND)
+    }
+
+    /// init_with_bound
+    /// the stable structure lays values out by V::BOUND, so the declared bound
+    /// can only narrow it, Unbounded on a bounded V keeps V's own max
+    #[must_use]
+    pub fn init_with_bound(memory: VirtualMemory, bound: Bound) -> Self {
+        let bound = match (bound, V::BOUND) {
+            (
+                Bound::Bounded { max_size, .. },
+                Bound::Bounded {
+                    max_size: value_max,
+                    is_fixed_size,
+                },
+            ) => Bound::Bounded {
+                max_size: max_size.min(value_max),
+                is_fixed_size,
+            },
+            (Bound::Unbounded, value_bound) => value_bound,
+            (bound, Bound::Unbounded) => bound,
+        };
+
         Self {
             data: WrappedBTreeMap::init(memory),
+            bound,
+        }
+    }
+
+    /// bound
+    #[must_use]
+    pub const fn bound(&self) -> &Bound {
+        &self.bound
+    }
+
+    /// get
+    #[must_use]
+    pub fn get(&self, key: &K) -> Option<V> {
+        self.data.get(key).map(Encoded::decode)
+    }
+
+    /// contains_key
+    #[must_use]
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.data.contains_key(key)
+    }
+
+    /// len
+    #[must_use]
+    pub fn len(&self) -> u64 {
+        self.data.len()
+    }
+
+    /// is_empty
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// insert
+    /// panics if the value is past the bound, like the stable structure does
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.try_insert(key, value)
+            .unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// try_insert
+    /// checks the encoded value against the bound first, so an oversized
+    /// value is an error rather than a trap inside the stable structure
+    pub fn try_insert(&mut self, key: K, value: V) -> Result<Option<V>, Error> {
+        let value = Encoded::<V>::encode(&value);
+        if let Bound::Bounded { max_size, .. } = self.bound {
+            let size = value.bytes.len() as u64;
+            if size > u64::from(max_size) {
+                Err(BTreeMapError::ValueTooLarge { size, max_size })?;
+            }
         }
+
+        Ok(self.data.insert(key, value).map(Encoded::decode))
+    }
+
+    /// remove
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        self.data.remove(key).map(Encoded::decode)
+    }
+
+    /// iter
+    pub fn iter(&self) -> impl Iterator<Item = (K, V)> + '_ {
+        self.data.iter().map(|(k, v)| (k, v.decode()))
+    }
+
+    /// range
+    pub fn range(&self, key_range: impl ops::RangeBounds<K>) -> impl Iterator<Item = (K, V)> + '_ {
+        self.data.range(key_range).map(|(k, v)| (k, v.decode()))
     }
 
     /// keys
+    /// the values are never decoded
     pub fn keys(&self) -> impl Iterator<Item = K> + '_ {
         self.data.iter().map(|(k, _)| k)
     }
 
     /// values
     pub fn values(&self) -> impl Iterator<Item = V> + '_ {
-        self.data.iter().map(|(_, v)| v)
+        self.data.iter().map(|(_, v)| v.decode())
+    }
+
+    /// keys_paged
+    /// at most limit keys from start, with the key the next page starts at
+    #[must_use]
+    pub fn keys_paged(&self, start: Option<&K>, limit: usize) -> (Vec<K>, Option<K>) {
+        let (page, next) = self.page(start, limit);
+
+        (page.into_iter().map(|(k, _)| k).collect(), next)
+    }
+
+    /// values_paged
+    #[must_use]
+    pub fn values_paged(&self, start: Option<&K>, limit: usize) -> (Vec<V>, Option<K>) {
+        let (page, next) = self.page(start, limit);
+
+        (page.into_iter().map(|(_, v)| v).collect(), next)
+    }
+
+    /// page
+    /// reads one entry past the limit to find the continuation key, the
+    /// range iterator means only the page is visited however big the map is
+    fn page(&self, start: Option<&K>, limit: usize) -> (Vec<(K, V)>, Option<K>) {
+        let start = start.map_or(ops::Bound::Unbounded, |k| ops::Bound::Included(k.clone()));
+        let mut page: Vec<(K, V)> = self
+            .range((start, ops::Bound::Unbounded))
+            .take(limit.saturating_add(1))
+            .collect();
+
+        let next = if page.len() > limit {
+            page.pop().map(|(k, _)| k)
+        } else {
+            None
+        };
+
+        (page, next)
     }
 
     /// clear
     /// the original clear() method in the ic-stable-structures library
     /// couldn't be wrapped as it took ownership, so they made a new one
     pub fn clear(&mut self) {
-        self.clear_new();
+        self.data.clear_new();
+    }
+}
+
+///
+/// Encoded
+/// a value as the bytes it encodes to, with the same bound as the value
+///
+
+struct Encoded<V> {
+    bytes: Vec<u8>,
+    _value: PhantomData<V>,
+}
+
+impl<V: Storable> Encoded<V> {
+    fn encode(value: &V) -> Self {
+        Self {
+            bytes: value.to_bytes().into_owned(),
+            _value: PhantomData,
+        }
+    }
+
+    fn decode(self) -> V {
+        V::from_bytes(Cow::Owned(self.bytes))
+    }
+}
+
+impl<V: Storable> Storable for Encoded<V> {
+    const BOUND: Bound = V::BOUND;
+
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(&self.bytes)
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        Self {
+            bytes: bytes.into_owned(),
+            _value: PhantomData,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structures::{
+        memory::{MemoryId, MemoryManager},
+        DefaultMemoryImpl,
+    };
+
+    #[test]
+    fn test_paged() {
+        let mm = MemoryManager::init(DefaultMemoryImpl::default());
+        let mut map = BTreeMap::<u32, u32>::init(mm.get(MemoryId::new(0)));
+        for n in 0..5 {
+            map.insert(n, n * 10);
+        }
+
+        let (keys, next) = map.keys_paged(None, 2);
+        assert_eq!((keys, next), (vec![0, 1], Some(2)));
+
+        let (values, next) = map.values_paged(next.as_ref(), 2);
+        assert_eq!((values, next), (vec![20, 30], Some(4)));
+
+        let (keys, next) = map.keys_paged(next.as_ref(), 2);
+        assert_eq!((keys, next), (vec![4], None));
+    }
+
+    #[test]
+    fn test_init_with_bound() {
+        let mm = MemoryManager::init(DefaultMemoryImpl::default());
+        let bound = Bound::Bounded {
+            max_size: 4,
+            is_fixed_size: false,
+        };
+        let mut map = BTreeMap::<u32, String>::init_with_bound(mm.get(MemoryId::new(0)), bound);
+
+        assert!(map.try_insert(1, "abcd".to_string()).is_ok());
+        assert!(matches!(
+            map.try_insert(2, "abcde".to_string()),
+            Err(Error::BTreeMap {
+                source: BTreeMapError::ValueTooLarge {
+                    size: 5,
+                    max_size: 4
+                }
+            })
+        ));
+        assert_eq!(map.len(), 1);
+
+        // a bounded value type keeps its own max
+        let map = BTreeMap::<u32, u64>::init_with_bound(mm.get(MemoryId::new(1)), Bound::Unbounded);
+        assert!(matches!(map.bound(), Bound::Bounded { max_size: 8, .. }));
+    }
+
+    #[test]
+    #[should_panic(expected = "value too large")]
+    fn test_insert_past_bound() {
+        let mm = MemoryManager::init(DefaultMemoryImpl::default());
+        let bound = Bound::Bounded {
+            max_size: 4,
+            is_fixed_size: false,
+        };
+        let mut map = BTreeMap::<u32, String>::init_with_bound(mm.get(MemoryId::new(0)), bound);
+
+        map.insert(1, "abcde".to_string());
     }
 }
@@ -81,3 +81,25 @@ where
         Ok(res)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structures::{
+        memory::{MemoryId, MemoryManager},
+        DefaultMemoryImpl,
+    };
+
+    #[test]
+    fn test_init_keeps_value() {
+        let mm = MemoryManager::init(DefaultMemoryImpl::default());
+
+        let mut cell = Cell::init(mm.get(MemoryId::new(0)), 1_u64).unwrap();
+        assert_eq!(cell.get(), 1);
+        assert_eq!(cell.set(2).unwrap(), 1);
+
+        // the default is only used when the memory is empty
+        let cell = Cell::init(mm.get(MemoryId::new(0)), 1_u64).unwrap();
+        assert_eq!(cell.get(), 2);
+    }
+}
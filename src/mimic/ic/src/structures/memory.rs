@@ -0,0 +1,164 @@
+pub use ic_stable_structures::memory_manager::*;
+
+use candid::CandidType;
+use ic_stable_structures::{DefaultMemoryImpl, Memory};
+use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+use std::{cell::RefCell, collections::BTreeMap};
+
+pub type VirtualMemory = ic_stable_structures::memory_manager::VirtualMemory<DefaultMemoryImpl>;
+
+// stable memory grows in 64KiB wasm pages
+pub const WASM_PAGE_SIZE: u64 = 65_536;
+
+///
+/// MemoryError
+///
+
+#[derive(CandidType, Debug, Serialize, Deserialize, Snafu)]
+pub enum MemoryError {
+    #[snafu(display("memory id {id} is used by both {existing} and {label}"))]
+    IdInUse {
+        id: u8,
+        existing: String,
+        label: String,
+    },
+}
+
+//
+// REGISTRY
+// the label of the structure that claimed each memory id, a VirtualMemory
+// doesn't say which id backs it so this is the only record of it
+//
+
+thread_local! {
+    static REGISTRY: RefCell<BTreeMap<u8, String>> = const { RefCell::new(BTreeMap::new()) };
+}
+
+// register
+// claims the id for the label, claiming it again with the same label is fine
+pub fn register(id: u8, label: &str) -> Result<(), MemoryError> {
+    REGISTRY.with_borrow_mut(|registry| match registry.get(&id) {
+        Some(existing) if existing != label => Err(MemoryError::IdInUse {
+            id,
+            existing: existing.clone(),
+            label: label.to_string(),
+        }),
+        _ => {
+            registry.insert(id, label.to_string());
+
+            Ok(())
+        }
+    })
+}
+
+// registered
+#[must_use]
+pub fn registered() -> BTreeMap<u8, String> {
+    REGISTRY.with_borrow(Clone::clone)
+}
+
+// memory_id
+// the id claimed by the label
+#[must_use]
+pub fn memory_id(label: &str) -> Option<u8> {
+    REGISTRY.with_borrow(|registry| {
+        registry
+            .iter()
+            .find(|(_, l)| *l == label)
+            .map(|(id, _)| *id)
+    })
+}
+
+// get_memory
+// two structures sharing an id would overwrite each other's data, so the
+// id is registered before its memory is handed out and a collision panics,
+// canisters register every id at init so this only catches ids they missed
+#[must_use]
+pub fn get_memory(
+    memory_manager: &MemoryManager<DefaultMemoryImpl>,
+    id: u8,
+    label: &str,
+) -> VirtualMemory {
+    if let Err(e) = register(id, label) {
+        panic!("{e}");
+    }
+
+    memory_manager.get(MemoryId::new(id))
+}
+
+///
+/// MemoryUsage
+///
+/// the pages a memory id has been grown to, memory is never given back so
+/// this is the high water mark rather than what is live
+///
+
+#[derive(CandidType, Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct MemoryUsage {
+    pub id: u8,
+    pub pages: u64,
+    pub bytes: u64,
+}
+
+// usage
+// the footprint of every registered structure, keyed by its label
+#[must_use]
+pub fn usage(memory_manager: &MemoryManager<DefaultMemoryImpl>) -> BTreeMap<String, MemoryUsage> {
+    registered()
+        .into_iter()
+        .map(|(id, label)| {
+            let pages = memory_manager.get(MemoryId::new(id)).size();
+
+            (
+                label,
+                MemoryUsage {
+                    id,
+                    pages,
+                    bytes: pages * WASM_PAGE_SIZE,
+                },
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_register() {
+        register(10, "a").unwrap();
+        register(10, "a").unwrap();
+        register(11, "b").unwrap();
+        assert!(register(10, "b").is_err());
+
+        assert_eq!(memory_id("b"), Some(11));
+        assert_eq!(registered().len(), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "memory id 1 is used by both a and b")]
+    fn test_get_memory_collision() {
+        let mm = MemoryManager::init(DefaultMemoryImpl::default());
+        let _ = get_memory(&mm, 1, "a");
+        let _ = get_memory(&mm, 1, "b");
+    }
+
+    #[test]
+    fn test_usage() {
+        let mm = MemoryManager::init(DefaultMemoryImpl::default());
+        let memory = get_memory(&mm, 20, "usage");
+        memory.grow(3);
+
+        let usage = usage(&mm);
+        assert_eq!(
+            usage.get("usage"),
+            Some(&MemoryUsage {
+                id: 20,
+                pages: 3,
+                bytes: 3 * WASM_PAGE_SIZE,
+            })
+        );
+    }
+}
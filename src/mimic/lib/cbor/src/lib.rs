@@ -1,8 +1,11 @@
+pub use ciborium::Value;
+
 use candid::CandidType;
-use ciborium::{de::from_reader, ser::into_writer, Value};
+use ciborium::{de::from_reader, ser::into_writer};
+use ciborium_ll::{Decoder, Header};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use snafu::Snafu;
-use std::fmt::Debug;
+use std::{collections::HashMap, fmt::Debug};
 
 ///
 /// Error
@@ -45,3 +48,185 @@ where
         }
     })
 }
+
+// deserialize_fields
+// only deserializes the values of the requested fields from a top-level map,
+// every other value is skipped over without being decoded
+pub fn deserialize_fields(bytes: &[u8], fields: &[&str]) -> Result<HashMap<String, Value>, Error> {
+    field_slices(bytes, fields)?
+        .into_iter()
+        .map(|(key, slice)| Ok((key, deserialize::<Value>(slice)?)))
+        .collect()
+}
+
+// field_slices
+// returns the raw encoded bytes of the requested fields from a top-level map
+pub fn field_slices<'a>(
+    bytes: &'a [u8],
+    fields: &[&str],
+) -> Result<HashMap<String, &'a [u8]>, Error> {
+    let mut projector = Projector::new(bytes);
+    let len = match projector.header()? {
+        Header::Map(len) => len,
+        _ => return Err(Projector::error("expected a map")),
+    };
+
+    let mut slices = HashMap::new();
+    let mut remaining = len;
+    while remaining != Some(0) {
+        // key
+        let key = match projector.header()? {
+            Header::Break if len.is_none() => break,
+            Header::Text(Some(len)) => projector.text(len)?,
+            _ => return Err(Projector::error("expected a text key")),
+        };
+
+        // value
+        let start = projector.pos;
+        projector.skip()?;
+        if fields.contains(&key) {
+            slices.insert(key.to_string(), &bytes[start..projector.pos]);
+        }
+
+        if let Some(n) = remaining.as_mut() {
+            *n -= 1;
+        }
+    }
+
+    Ok(slices)
+}
+
+///
+/// Projector
+/// walks CBOR item headers so that whole values can be stepped over
+///
+
+struct Projector<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Projector<'a> {
+    const fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, pos: 0 }
+    }
+
+    // error
+    fn error(msg: &str) -> Error {
+        Error::Deserialize {
+            msg: format!("failed to project fields: {msg}"),
+        }
+    }
+
+    // header
+    fn header(&mut self) -> Result<Header, Error> {
+        let mut decoder = Decoder::from(&self.bytes[self.pos..]);
+        let header = decoder.pull().map_err(|e| Self::error(&format!("{e:?}")))?;
+        self.pos += decoder.offset();
+
+        Ok(header)
+    }
+
+    // advance
+    fn advance(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| Self::error("unexpected end of input"))?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+
+        Ok(slice)
+    }
+
+    // text
+    fn text(&mut self, len: usize) -> Result<&'a str, Error> {
+        let slice = self.advance(len)?;
+
+        std::str::from_utf8(slice).map_err(|e| Self::error(&e.to_string()))
+    }
+
+    // skip
+    // steps over one complete item, including any nested items
+    fn skip(&mut self) -> Result<(), Error> {
+        match self.header()? {
+            Header::Positive(_) | Header::Negative(_) | Header::Float(_) | Header::Simple(_) => {}
+            Header::Tag(_) => self.skip()?,
+            Header::Bytes(Some(len)) | Header::Text(Some(len)) => {
+                self.advance(len)?;
+            }
+            Header::Array(Some(len)) => {
+                for _ in 0..len {
+                    self.skip()?;
+                }
+            }
+            Header::Map(Some(len)) => {
+                for _ in 0..len * 2 {
+                    self.skip()?;
+                }
+            }
+            Header::Bytes(None) | Header::Text(None) | Header::Array(None) | Header::Map(None) => {
+                self.skip_until_break()?;
+            }
+            Header::Break => return Err(Self::error("unexpected break")),
+        }
+
+        Ok(())
+    }
+
+    // skip_until_break
+    fn skip_until_break(&mut self) -> Result<(), Error> {
+        loop {
+            let start = self.pos;
+            if matches!(self.header()?, Header::Break) {
+                return Ok(());
+            }
+            self.pos = start;
+            self.skip()?;
+        }
+    }
+}
+
+//
+// TESTS
+//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[derive(Serialize)]
+    struct Entity {
+        id: u32,
+        name: String,
+        tags: Vec<String>,
+        nested: BTreeMap<String, Vec<u8>>,
+        score: f64,
+    }
+
+    #[test]
+    fn test_deserialize_fields() {
+        let entity = Entity {
+            id: 7,
+            name: "bob".to_string(),
+            tags: vec!["a".to_string(), "b".to_string()],
+            nested: BTreeMap::from([("x".to_string(), vec![1, 2, 3])]),
+            score: 1.5,
+        };
+        let bytes = serialize(&entity).unwrap();
+
+        let values = deserialize_fields(&bytes, &["name", "score"]).unwrap();
+        assert_eq!(values.len(), 2);
+        assert_eq!(values["name"], Value::Text("bob".to_string()));
+        assert_eq!(values["score"], Value::Float(1.5));
+    }
+
+    #[test]
+    fn test_deserialize_fields_not_a_map() {
+        let bytes = serialize(&vec![1, 2, 3]).unwrap();
+
+        assert!(deserialize_fields(&bytes, &["a"]).is_err());
+    }
+}
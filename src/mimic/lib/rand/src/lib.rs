@@ -9,6 +9,15 @@ use tinyrand::{Rand, Seeded, StdRand};
 pub static STD_RAND: LazyLock<Mutex<StdRand>> =
     LazyLock::new(|| Mutex::new(StdRand::seed(now_millis())));
 
+// seed_rng
+// replaces the time based seed so the values that follow are reproducible,
+// only built for tests, ulids take their timestamp from the clock unless
+// types::ulid::generator::set_clock is used too
+#[cfg(any(test, feature = "test"))]
+pub fn seed_rng(seed: u64) {
+    *STD_RAND.lock().expect("mutex") = StdRand::seed(seed);
+}
+
 // next_u8
 // (uses u16 because there is no next_u8)
 #[must_use]
@@ -48,9 +57,13 @@ pub fn next_u128() -> u128 {
 mod tests {
     use super::*;
 
+    // tests that reseed STD_RAND can't run alongside the ones reading it
+    static SEQUENTIAL: Mutex<()> = Mutex::new(());
+
     #[test]
     fn test_unique_u64s() {
         use std::collections::HashSet;
+        let _lock = SEQUENTIAL.lock().expect("mutex");
 
         let mut set = HashSet::new();
         while set.len() < 1000 {
@@ -59,6 +72,19 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_seed_rng() {
+        let _lock = SEQUENTIAL.lock().expect("mutex");
+
+        seed_rng(42);
+        let first: Vec<u64> = (0..10).map(|_| next_u64()).collect();
+
+        seed_rng(42);
+        let second: Vec<u64> = (0..10).map(|_| next_u64()).collect();
+
+        assert_eq!(first, second);
+    }
+
     #[test]
     fn test_rng_reseeding() {
         let mut rng1 = StdRand::seed(now_millis());
@@ -0,0 +1,84 @@
+use crate::{
+    imp::Implementor,
+    node::{Entity, FieldList, MacroNode, Newtype, Record, Trait},
+};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, ToTokens};
+
+///
+/// Arbitrary
+///
+/// values are sanitized and then anything that fails validation is
+/// rejected, so the strategies only produce values that could be saved
+///
+
+// entity
+pub fn entity(node: &Entity, t: Trait) -> TokenStream {
+    let q = strategy(&field_list(&node.fields));
+
+    Implementor::new(node.def(), t)
+        .set_tokens(q)
+        .to_token_stream()
+}
+
+// record
+pub fn record(node: &Record, t: Trait) -> TokenStream {
+    let q = strategy(&field_list(&node.fields));
+
+    Implementor::new(node.def(), t)
+        .set_tokens(q)
+        .to_token_stream()
+}
+
+// newtype
+pub fn newtype(node: &Newtype, t: Trait) -> TokenStream {
+    let value = &node.value;
+    let q = strategy(&quote! {
+        ::mimic::orm::proptest::arbitrary::any::<#value>().prop_map(Self)
+    });
+
+    Implementor::new(node.def(), t)
+        .set_tokens(q)
+        .to_token_stream()
+}
+
+// field_list
+// tuples of two strategies are nested so there's no limit on the field count
+fn field_list(node: &FieldList) -> TokenStream {
+    let names: Vec<_> = node.fields.iter().map(|field| &field.name).collect();
+    let vars: Vec<_> = (0..names.len()).map(|i| format_ident!("f{i}")).collect();
+
+    let mut strategy = quote!(::mimic::orm::proptest::strategy::Just(()));
+    let mut pattern = quote!(());
+    for (field, var) in node.fields.iter().zip(&vars).rev() {
+        let value = &field.value;
+        strategy = quote!((::mimic::orm::proptest::arbitrary::any::<#value>(), #strategy));
+        pattern = quote!((#var, #pattern));
+    }
+
+    quote! {
+        #strategy.prop_map(|#pattern| Self {
+            #(#names: #vars),*
+        })
+    }
+}
+
+// strategy
+fn strategy(base: &TokenStream) -> TokenStream {
+    quote! {
+        type Parameters = ();
+        type Strategy = ::mimic::orm::proptest::strategy::BoxedStrategy<Self>;
+
+        fn arbitrary_with((): Self::Parameters) -> Self::Strategy {
+            use ::mimic::orm::proptest::strategy::Strategy;
+
+            #base
+                .prop_map(|mut value| {
+                    ::mimic::orm::sanitize(&mut value);
+                    value
+                })
+                .prop_filter("failed validation", |value| ::mimic::orm::validate(value).is_ok())
+                .boxed()
+        }
+    }
+}
@@ -76,8 +76,10 @@ pub fn newtype(node: &Newtype, t: Trait) -> TokenStream {
 
 // format_default
 // not 100% sure NumCast will always work here, may need some extra checks
+// paths are called and expressions evaluated each time a default is made
 fn format_default(arg: &Arg) -> TokenStream {
     match arg {
+        Arg::Expr(expr) => quote!((#expr).into()),
         Arg::Path(path) => quote!(#path().into()),
         Arg::Bool(v) => quote!(#v.into()),
         Arg::Char(v) => quote!(#v.into()),
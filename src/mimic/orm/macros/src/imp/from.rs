@@ -177,6 +177,60 @@ pub fn newtype_str(node: &Newtype, t: Trait) -> TokenStream {
     imp.set_tokens(tokens).to_token_stream()
 }
 
+// newtype_try
+// the same conversions as From but the result is validated before it's
+// returned, these take the item type itself as a blanket impl over Into
+// would overlap with the std TryFrom impl
+pub fn newtype_try(node: &Newtype, t: Trait) -> TokenStream {
+    let item = &node.value.item;
+    let (from, wrap) = match node.value.cardinality() {
+        Cardinality::One => (quote!(#item), quote!(Self(t))),
+        Cardinality::Opt => (quote!(#item), quote!(Self(Some(t)))),
+        Cardinality::Many => (quote!(Vec<#item>), quote!(Self(t))),
+    };
+
+    let mut q = newtype_try_impl(node, t, &from, &wrap);
+
+    // strings also convert from &str
+    if matches!(
+        node.primitive.map(|p| p.group()),
+        Some(PrimitiveGroup::String)
+    ) && node.value.cardinality() != Cardinality::Many
+    {
+        let wrap = match node.value.cardinality() {
+            Cardinality::Opt => quote!(Self(Some(t.into()))),
+            _ => quote!(Self(t.into())),
+        };
+        q.extend(newtype_try_impl(node, t, &quote!(&str), &wrap));
+    }
+
+    q
+}
+
+// newtype_try_impl
+fn newtype_try_impl(
+    node: &Newtype,
+    t: Trait,
+    from: &TokenStream,
+    wrap: &TokenStream,
+) -> TokenStream {
+    let tokens = quote! {
+        type Error = ::mimic::orm::Error;
+
+        fn try_from(t: #from) -> Result<Self, Self::Error> {
+            let value = #wrap;
+            ::mimic::orm::validate(&value)?;
+
+            Ok(value)
+        }
+    };
+
+    Implementor::new(node.def(), t)
+        .set_tokens(tokens)
+        .add_trait_generic(from.clone())
+        .to_token_stream()
+}
+
 ///
 /// Tuple
 ///
@@ -14,6 +14,10 @@ pub fn newtype(node: &Newtype, t: Trait) -> TokenStream {
         fn inner(&self) -> &#primitive {
             self.0.inner()
         }
+
+        fn inner_mut(&mut self) -> &mut #primitive {
+            self.0.inner_mut()
+        }
     };
 
     Implementor::new(node.def(), t)
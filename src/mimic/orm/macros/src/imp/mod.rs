@@ -1,3 +1,4 @@
+pub mod arbitrary;
 pub mod default;
 pub mod display;
 pub mod filterable;
@@ -1,9 +1,10 @@
 use crate::{
     imp::Implementor,
-    node::{Entity, Trait},
+    node::{path_to_string, Entity, Trait},
 };
+use orm::types::KeyStrategy;
 use proc_macro2::TokenStream;
-use quote::{quote, ToTokens};
+use quote::{format_ident, quote, ToTokens};
 
 ///
 /// Entity
@@ -78,10 +79,13 @@ fn composite_key(node: &Entity) -> TokenStream {
 pub fn entity_dynamic(node: &Entity, t: Trait) -> TokenStream {
     let mut q = quote! {};
 
-    q.extend(on_create(node));
+    q.extend(generate_ids(node));
     q.extend(composite_key_dyn(node));
     q.extend(path_dyn(node));
+    q.extend(key_strategy_dyn(node));
     q.extend(serialize_dyn(node));
+    q.extend(to_json_dyn(node));
+    q.extend(validate_entity(node));
 
     Implementor::new(&node.def, t)
         .set_tokens(q)
@@ -105,17 +109,54 @@ fn composite_key_dyn(node: &Entity) -> TokenStream {
     }
 }
 
-// on_create
-fn on_create(node: &Entity) -> TokenStream {
+// generate_ids
+fn generate_ids(node: &Entity) -> TokenStream {
     let mut inner = quote!();
-    for pk in &node.primary_keys {
-        inner.extend(quote! {
-            self.#pk = ::mimic::orm::traits::PrimaryKey::on_create(&self.#pk);
-        });
+    match node.key_strategy {
+        KeyStrategy::Random => {
+            for pk in &node.primary_keys {
+                inner.extend(quote! {
+                    self.#pk = ::mimic::orm::traits::PrimaryKey::on_create(&self.#pk);
+                });
+            }
+        }
+
+        // the key is left out of the hash so the same content always
+        // gets the same key, whatever the caller passed in
+        KeyStrategy::ContentHash => {
+            for pk in &node.primary_keys {
+                inner.extend(quote! {
+                    let pk = ::std::mem::take(&mut self.#pk);
+                    self.#pk = ::mimic::orm::serialize(&self)
+                        .ok()
+                        .and_then(|data| {
+                            ::mimic::orm::traits::PrimaryKey::from_seed(
+                                ::mimic::orm::content_seed(&data),
+                            )
+                        })
+                        .unwrap_or(pk);
+                });
+            }
+        }
+
+        KeyStrategy::Ulid => {
+            for pk in &node.primary_keys {
+                inner.extend(quote! {
+                    if let Some(key) = ::mimic::orm::traits::PrimaryKey::from_ulid(
+                        ::mimic::types::Ulid::generate(),
+                    ) {
+                        self.#pk = key;
+                    }
+                });
+            }
+        }
+
+        // Sequential keys are set by the save executor, see set_sequence_dyn
+        KeyStrategy::Provided | KeyStrategy::Sequential => {}
     }
 
     quote! {
-        fn on_create(&mut self) {
+        fn generate_ids(&mut self) {
             #inner
         }
     }
@@ -130,6 +171,34 @@ fn path_dyn(_: &Entity) -> TokenStream {
     }
 }
 
+// key_strategy_dyn
+fn key_strategy_dyn(node: &Entity) -> TokenStream {
+    let key_strategy = format_ident!("{}", node.key_strategy.to_string());
+    let mut q = quote! {
+        fn key_strategy_dyn(&self) -> ::mimic::orm::types::KeyStrategy {
+            ::mimic::orm::types::KeyStrategy::#key_strategy
+        }
+    };
+
+    if node.key_strategy == KeyStrategy::Sequential {
+        let sets = node.primary_keys.iter().map(|pk| {
+            quote! {
+                if let Some(key) = ::mimic::orm::traits::PrimaryKey::from_seed(seq) {
+                    self.#pk = key;
+                }
+            }
+        });
+
+        q.extend(quote! {
+            fn set_sequence_dyn(&mut self, seq: u64) {
+                #(#sets)*
+            }
+        });
+    }
+
+    q
+}
+
 // serialize_dyn
 fn serialize_dyn(_: &Entity) -> TokenStream {
     quote! {
@@ -138,3 +207,106 @@ fn serialize_dyn(_: &Entity) -> TokenStream {
         }
     }
 }
+
+// to_json_dyn
+fn to_json_dyn(_: &Entity) -> TokenStream {
+    quote! {
+        fn to_json_dyn(&self) -> Result<::mimic::orm::JsonValue, ::mimic::orm::Error> {
+            ::mimic::orm::to_json(self)
+        }
+    }
+}
+
+// validate_entity
+// every check runs so all the broken invariants are reported together
+fn validate_entity(node: &Entity) -> TokenStream {
+    if node.checks.is_empty() {
+        return quote!();
+    }
+
+    let checks = node.checks.iter().map(|check| {
+        let name = path_to_string(check);
+        quote! {
+            errs.add_check(#name, #check(self));
+        }
+    });
+
+    quote! {
+        fn validate_entity(&self) -> Result<(), ::mimic::types::FieldErrors> {
+            let mut errs = ::mimic::types::FieldErrors::new();
+            #(#checks)*
+
+            errs.result()
+        }
+    }
+}
+
+///
+/// Validate
+///
+
+// validate
+// an entity visited directly, rather than through an EntityAdapter, runs
+// its checks once its fields are done
+pub fn validate(node: &Entity, t: Trait) -> TokenStream {
+    let q = quote! {
+        fn validate_after(&self) -> Result<(), ::mimic::types::FieldErrors> {
+            ::mimic::orm::traits::EntityDynamic::validate_entity(self)
+        }
+    };
+
+    Implementor::new(&node.def, t)
+        .set_tokens(q)
+        .to_token_stream()
+}
+
+///
+/// Builder
+///
+
+// builder
+// build() sanitizes then validates the entity the same way a save would,
+// the fields nobody set keep their defaults
+pub fn builder(node: &Entity) -> TokenStream {
+    let ident = &node.def.ident;
+    let builder_ident = format_ident!("{ident}Builder");
+
+    let setters = node.fields.fields.iter().map(|field| {
+        let name = &field.name;
+        let value = &field.value;
+        let setter = format_ident!("with_{name}");
+
+        quote! {
+            #[must_use]
+            pub fn #setter(mut self, value: impl Into<#value>) -> Self {
+                self.entity.#name = value.into();
+                self
+            }
+        }
+    });
+
+    quote! {
+        #[derive(Clone, Debug, Default)]
+        pub struct #builder_ident {
+            entity: #ident,
+        }
+
+        impl #builder_ident {
+            #(#setters)*
+
+            pub fn build(mut self) -> Result<#ident, ::mimic::orm::Error> {
+                ::mimic::orm::sanitize(&mut self.entity);
+                ::mimic::orm::validate(&self.entity)?;
+
+                Ok(self.entity)
+            }
+        }
+
+        impl #ident {
+            #[must_use]
+            pub fn builder() -> #builder_ident {
+                #builder_ident::default()
+            }
+        }
+    }
+}
@@ -4,7 +4,7 @@ use crate::{
 };
 use orm::types::PrimitiveType;
 use proc_macro2::TokenStream;
-use quote::{quote, format_ident, ToTokens};
+use quote::{format_ident, quote, ToTokens};
 
 ///
 /// NumCast
@@ -34,6 +34,47 @@ pub mod cast {
     }
 }
 
+///
+/// NumChecked
+///
+
+pub mod checked {
+    use super::*;
+
+    // newtype
+    pub fn newtype(node: &Newtype, t: Trait) -> TokenStream {
+        let q = quote! {
+            fn checked_add(self, other: Self) -> Option<Self> {
+                ::mimic::orm::traits::NumChecked::checked_add(self.0, other.0).map(Self)
+            }
+
+            fn checked_sub(self, other: Self) -> Option<Self> {
+                ::mimic::orm::traits::NumChecked::checked_sub(self.0, other.0).map(Self)
+            }
+
+            fn checked_mul(self, other: Self) -> Option<Self> {
+                ::mimic::orm::traits::NumChecked::checked_mul(self.0, other.0).map(Self)
+            }
+
+            fn saturating_add(self, other: Self) -> Self {
+                Self(::mimic::orm::traits::NumChecked::saturating_add(self.0, other.0))
+            }
+
+            fn saturating_sub(self, other: Self) -> Self {
+                Self(::mimic::orm::traits::NumChecked::saturating_sub(self.0, other.0))
+            }
+
+            fn saturating_mul(self, other: Self) -> Self {
+                Self(::mimic::orm::traits::NumChecked::saturating_mul(self.0, other.0))
+            }
+        };
+
+        Implementor::new(&node.def, t)
+            .set_tokens(q)
+            .to_token_stream()
+    }
+}
+
 ///
 /// NumFromPrimitive
 ///
@@ -42,18 +83,25 @@ pub mod from_primitive {
     use super::*;
 
     // newtype
+    // a validated newtype is None if the value fails its validators, NumCast
+    // goes through here too
     pub fn newtype(node: &Newtype, t: Trait) -> TokenStream {
         let value = &node.value;
+        let wrap = if node.validators.is_empty() {
+            quote!(.map(Self))
+        } else {
+            quote!(.map(Self).filter(|v| ::mimic::orm::validate(v).is_ok()))
+        };
 
         let mut q = quote! {
             fn from_i64(n: i64) -> Option<Self> {
                 type Ty = #value;
-                Ty::from_i64(n).map(Self)
+                Ty::from_i64(n)#wrap
             }
 
             fn from_u64(n: u64) -> Option<Self> {
                 type Ty = #value;
-                Ty::from_u64(n).map(Self)
+                Ty::from_u64(n)#wrap
             }
         };
 
@@ -65,7 +113,7 @@ pub mod from_primitive {
             q.extend(quote! {
                 fn from_f64(n: f64) -> Option<Self> {
                     type Ty = #value;
-                    Ty::from_f64(n).map(Self)
+                    Ty::from_f64(n)#wrap
                 }
             });
         }
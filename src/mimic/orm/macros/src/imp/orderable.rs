@@ -9,6 +9,8 @@ use quote::{quote, ToTokens};
 pub fn enum_(node: &Enum, t: Trait) -> TokenStream {
     let q = if node.is_orderable() {
         quote! {
+            const ORDERABLE: bool = true;
+
             fn cmp(&self, other: &Self) -> ::std::cmp::Ordering {
                 Ord::cmp(self, other)
             }
@@ -27,6 +29,8 @@ pub fn newtype(node: &Newtype, t: Trait) -> TokenStream {
     let q = match &node.primitive {
         Some(primitive) if primitive.is_orderable() => {
             quote! {
+                const ORDERABLE: bool = true;
+
                 fn cmp(&self, other: &Self) -> ::std::cmp::Ordering {
                     Ord::cmp(self, other)
                 }
@@ -16,6 +16,14 @@ pub fn newtype(node: &Newtype, t: Trait) -> TokenStream {
         fn format(&self) -> String {
             self.0.format()
         }
+
+        fn from_seed(seed: u64) -> Option<Self> {
+            ::mimic::orm::traits::PrimaryKey::from_seed(seed).map(Self)
+        }
+
+        fn from_ulid(ulid: ::mimic::types::Ulid) -> Option<Self> {
+            ::mimic::orm::traits::PrimaryKey::from_ulid(ulid).map(Self)
+        }
     };
 
     Implementor::new(&node.def, t)
@@ -56,6 +56,28 @@ pub fn field_list(node: &FieldList) -> TokenStream {
         })
         .collect();
 
+    // field_matches arms
+    let predicate_matches: Vec<_> = node
+        .fields
+        .iter()
+        .map(|field| {
+            let name = &field.name;
+            let name_str = name.to_string();
+
+            match field.value.cardinality() {
+                Cardinality::One => quote! {
+                    #name_str => ::mimic::orm::traits::field_matches(&self.#name, predicate),
+                },
+                Cardinality::Opt => quote! {
+                    #name_str => self.#name.as_ref().is_some_and(|value| {
+                        ::mimic::orm::traits::field_matches(value, predicate)
+                    }),
+                },
+                Cardinality::Many => quote!(),
+            }
+        })
+        .collect();
+
     // Prepare static DEFAULT_FIELDS only once
     let default_fields = node.fields.iter().map(|field| {
         let name = field.name.to_string();
@@ -83,5 +105,12 @@ pub fn field_list(node: &FieldList) -> TokenStream {
 
             false
         }
+
+        fn field_matches(&self, field: &str, predicate: &::mimic::orm::types::FieldPredicate) -> bool {
+            match field {
+                #(#predicate_matches)*
+                _ => false,
+            }
+        }
     }
 }
@@ -10,6 +10,7 @@ pub fn newtype(node: &Newtype, t: Trait) -> TokenStream {
     let mut rules = quote!();
 
     // sanitizers
+    // chained in declared order, each one gets the output of the one before
     for san in &node.sanitizers {
         let path = &san.path;
         let args = &san.args;
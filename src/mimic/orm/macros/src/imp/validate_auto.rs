@@ -44,7 +44,8 @@ pub fn enum_(node: &Enum, t: Trait) -> TokenStream {
 }
 
 // validators
-// takes a slice of Validators and turns it into the #inner of the function
+// takes a slice of Validators and turns it into the #inner of the function,
+// each error is tagged with the path of the validator that raised it
 fn validators(validators: &[TypeValidator]) -> TokenStream {
     let rules: Vec<TokenStream> = validators
         .iter()
@@ -52,7 +53,10 @@ fn validators(validators: &[TypeValidator]) -> TokenStream {
             let path = &val.path;
             let args = &val.args;
             quote! {
-                errs.add_result(#path::validate(&self.0, #(#args),*));
+                errs.add_validator_result(
+                    <#path as ::mimic::orm::traits::Path>::PATH,
+                    #path::validate(&self.0, #(#args),*),
+                );
             }
         })
         .collect();
@@ -90,10 +94,13 @@ pub fn newtype(node: &Newtype, t: Trait) -> TokenStream {
             match NumCast::from(self.0) {
                 Some(value) => {
                     if !valid_values.contains(&value) {
-                        errs.add(format!("value {} does not appear in guide", &self.0));
+                        errs.add_validator(
+                            "guide",
+                            format!("value {} does not appear in guide", &self.0),
+                        );
                     }
                 }
-                None => errs.add("failed to convert value to isize")
+                None => errs.add_validator("guide", "failed to convert value to isize")
             }
         });
     };
@@ -9,7 +9,8 @@ use syn::{Expr, Ident};
 
 //
 // Visitable
-// the code that allows a Visitor to recurse down into a nested ORM type
+// the code that allows a Visitor to recurse down into a nested ORM type,
+// every child visit is followed by ? so a Break stops the walk
 //
 
 ///
@@ -81,14 +82,14 @@ pub fn map(node: &Map, t: Trait) -> TokenStream {
     let inner = quote! {
         for (map_key, map_value) in self.0.iter() {
             let key = map_key.to_string();
-            ::mimic::orm::visit::perform_visit(visitor, map_key, &key);
-            ::mimic::orm::visit::perform_visit(visitor, map_value, &key);
+            ::mimic::orm::visit::perform_visit(visitor, map_key, &key)?;
+            ::mimic::orm::visit::perform_visit(visitor, map_value, &key)?;
         }
     };
     let inner_mut = quote! {
         for (map_key, map_value) in self.0.iter_mut() {
             let key = map_key.to_string();
-            ::mimic::orm::visit::perform_visit_mut(visitor, map_value, &key);
+            ::mimic::orm::visit::perform_visit_mut(visitor, map_value, &key)?;
         }
     };
     let q = drive_inner(&inner, &inner_mut);
@@ -144,16 +145,16 @@ pub fn tuple(node: &Tuple, t: Trait) -> TokenStream {
 fn quote_value(value: &Value, var: &syn::Expr, name: &str) -> TokenStream {
     match value.cardinality() {
         Cardinality::One => quote! {
-            ::mimic::orm::visit::perform_visit(visitor, &#var, #name);
+            ::mimic::orm::visit::perform_visit(visitor, &#var, #name)?;
         },
         Cardinality::Opt => quote! {
             if let Some(value) = #var.as_ref() {
-                ::mimic::orm::visit::perform_visit(visitor, value, #name);
+                ::mimic::orm::visit::perform_visit(visitor, value, #name)?;
             }
         },
         Cardinality::Many => quote! {
             for value in #var.iter() {
-                ::mimic::orm::visit::perform_visit(visitor, value, #name);
+                ::mimic::orm::visit::perform_visit(visitor, value, #name)?;
             }
         },
     }
@@ -167,16 +168,16 @@ fn quote_value_mut(value: &Value, var: &syn::Expr, name: &str) -> TokenStream {
 
     match value.cardinality() {
         Cardinality::One => quote! {
-            ::mimic::orm::visit::perform_visit_mut(visitor, &mut #var, #name);
+            ::mimic::orm::visit::perform_visit_mut(visitor, &mut #var, #name)?;
         },
         Cardinality::Opt => quote! {
             if let Some(value) = #var.as_mut() {
-                ::mimic::orm::visit::perform_visit_mut(visitor, value, #name);
+                ::mimic::orm::visit::perform_visit_mut(visitor, value, #name)?;
             }
         },
         Cardinality::Many => quote! {
             for value in #var.iter_mut() {
-                ::mimic::orm::visit::perform_visit_mut(visitor, value, #name);
+                ::mimic::orm::visit::perform_visit_mut(visitor, value, #name)?;
             }
         },
     }
@@ -209,16 +210,16 @@ fn quote_variant(value: &Value, ident: &Ident) -> TokenStream {
     let name = ident.to_string();
     match value.cardinality() {
         Cardinality::One => quote! {
-            Self::#ident(value) => ::mimic::orm::visit::perform_visit(visitor, value, #name),
+            Self::#ident(value) => ::mimic::orm::visit::perform_visit(visitor, value, #name)?,
         },
         Cardinality::Opt => quote! {
             Self::#ident(option_value) => if let Some(value) = option_value {
-                ::mimic::orm::visit::perform_visit(visitor, value, #name);
+                ::mimic::orm::visit::perform_visit(visitor, value, #name)?;
             },
         },
         Cardinality::Many => quote! {
             Self::#ident(values) => for value in values {
-                ::mimic::orm::visit::perform_visit(visitor, value, #name);
+                ::mimic::orm::visit::perform_visit(visitor, value, #name)?;
             },
         },
     }
@@ -234,16 +235,16 @@ fn quote_variant_mut(value: &Value, ident: &Ident) -> TokenStream {
     let name = ident.to_string();
     match value.cardinality() {
         Cardinality::One => quote! {
-            Self::#ident(value) => ::mimic::orm::visit::perform_visit_mut(visitor, value, #name),
+            Self::#ident(value) => ::mimic::orm::visit::perform_visit_mut(visitor, value, #name)?,
         },
         Cardinality::Opt => quote! {
             Self::#ident(option_value) => if let Some(value) = option_value {
-                ::mimic::orm::visit::perform_visit_mut(visitor, value, #name);
+                ::mimic::orm::visit::perform_visit_mut(visitor, value, #name)?;
             },
         },
         Cardinality::Many => quote! {
             Self::#ident(values) => for value in values {
-                ::mimic::orm::visit::perform_visit_mut(visitor, value, #name);
+                ::mimic::orm::visit::perform_visit_mut(visitor, value, #name)?;
             },
         },
     }
@@ -268,12 +269,20 @@ fn drive_inner(inner: &TokenStream, inner_mut: &TokenStream) -> TokenStream {
     };
 
     quote! {
-        fn drive(&self, #visitor: &mut dyn ::mimic::orm::visit::Visitor) {
+        fn drive(
+            &self,
+            #visitor: &mut dyn ::mimic::orm::visit::Visitor,
+        ) -> ::std::ops::ControlFlow<()> {
             #inner
+            ::std::ops::ControlFlow::Continue(())
         }
 
-        fn drive_mut(&mut self, #visitor_mut: &mut dyn ::mimic::orm::visit::Visitor) {
+        fn drive_mut(
+            &mut self,
+            #visitor_mut: &mut dyn ::mimic::orm::visit::Visitor,
+        ) -> ::std::ops::ControlFlow<()> {
             #inner_mut
+            ::std::ops::ControlFlow::Continue(())
         }
     }
 }
@@ -5,7 +5,7 @@ use orm_schema::Schemable;
 use proc_macro2::TokenStream;
 use quote::{quote, ToTokens};
 use strum::Display;
-use syn::{Lit, Path};
+use syn::{Expr, Lit, Path};
 
 ///
 /// Arg
@@ -15,11 +15,15 @@ use syn::{Lit, Path};
 ///
 /// If arguments need to be strings then we can revisit and maybe have an alternative
 ///
+/// Expr is for anything path-like that isn't a bare path, ie. a call with
+/// arguments, and is emitted as written
+///
 
 #[derive(Clone, Debug)]
 pub enum Arg {
     Bool(bool),
     Char(char),
+    Expr(Box<Expr>),
     Number(ArgNumber),
     Path(Path),
     String(String),
@@ -39,13 +43,15 @@ impl FromMeta for Arg {
             // Str
             // Here, analyze the literal to decide if it's a path or a plain string
             Lit::Str(lit) => {
-                if lit.value().contains("::") {
+                let value = lit.value();
+                if value.contains("::") {
                     // Simplistic check for path-like syntax
-                    syn::parse_str::<Path>(&lit.value())
+                    syn::parse_str::<Path>(&value)
                         .map(Arg::Path)
-                        .map_err(|_| DarlingError::custom("Failed to parse path"))
+                        .or_else(|_| syn::parse_str::<Expr>(&value).map(|e| Arg::Expr(Box::new(e))))
+                        .map_err(|_| DarlingError::custom("Failed to parse path or expression"))
                 } else {
-                    Ok(Self::String(lit.value()))
+                    Ok(Self::String(value))
                 }
             }
             _ => Err(DarlingError::custom(format!(
@@ -60,6 +66,10 @@ impl Schemable for Arg {
         match self {
             Self::Bool(v) => quote!(::mimic::orm::schema::node::Arg::Bool(#v)),
             Self::Char(v) => quote!(::mimic::orm::schema::node::Arg::Char(#v)),
+            Self::Expr(v) => {
+                let expr = quote_one(v, to_string);
+                quote!(::mimic::orm::schema::node::Arg::Expr(#expr.to_string()))
+            }
             Self::Number(v) => {
                 let num = quote_one(v, ArgNumber::schema);
                 quote!(::mimic::orm::schema::node::Arg::Number(#num))
@@ -78,6 +88,7 @@ impl ToTokens for Arg {
         match self {
             Self::Bool(v) => quote!(#v),
             Self::Char(v) => quote!(#v),
+            Self::Expr(v) => quote!(#v),
             Self::Number(v) => quote!(#v),
             Self::Path(v) => quote!(#v),
             Self::String(v) => quote!(#v),
@@ -174,6 +185,9 @@ mod arg_tests {
         } else {
             panic!("Expected Path variant");
         }
+
+        let expr_lit = parse_quote!("crate::module::Type::new(1)");
+        assert!(matches!(Arg::from_value(&expr_lit), Ok(Arg::Expr(_))));
     }
 
     #[test]
@@ -1,12 +1,13 @@
 use crate::{
+    helper::quote_option,
     imp,
     node::{Def, MacroNode, Node, Trait, TraitNode, Traits},
 };
 use darling::FromMeta;
 use orm::types::Cycles;
 use orm_schema::Schemable;
-use quote::quote;
 use proc_macro2::TokenStream;
+use quote::quote;
 
 ///
 /// Canister
@@ -24,6 +25,12 @@ pub struct Canister {
     pub initial_cycles: Cycles,
     pub min_cycles: Cycles,
     pub build: CanisterBuild,
+
+    #[darling(default)]
+    pub cycles_check_secs: Option<u64>,
+
+    #[darling(default)]
+    pub upgrade_warn_bytes: Option<u64>,
 }
 
 impl Node for Canister {
@@ -58,6 +65,8 @@ impl Schemable for Canister {
         let initial_cycles = &self.initial_cycles.schema();
         let min_cycles = &self.min_cycles.schema();
         let build = self.build.schema();
+        let cycles_check_secs = quote_option(&self.cycles_check_secs, |secs| quote!(#secs));
+        let upgrade_warn_bytes = quote_option(&self.upgrade_warn_bytes, |bytes| quote!(#bytes));
 
         quote! {
             ::mimic::orm::schema::node::SchemaNode::Canister(::mimic::orm::schema::node::Canister{
@@ -65,6 +74,8 @@ impl Schemable for Canister {
                 initial_cycles: #initial_cycles,
                 min_cycles: #min_cycles,
                 build: #build,
+                cycles_check_secs: #cycles_check_secs,
+                upgrade_warn_bytes: #upgrade_warn_bytes,
             })
         }
     }
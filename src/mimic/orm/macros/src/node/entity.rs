@@ -1,13 +1,14 @@
 use crate::{
     helper::{quote_one, quote_option, quote_vec, split_idents, to_path, to_string},
     imp,
-    node::{Crud, Def, FieldList, MacroNode, Node, SortKey, Trait, TraitNode, Traits},
+    node::{Crud, Def, FieldList, Index, MacroNode, Node, SortKey, Trait, TraitNode, Traits},
 };
 use darling::FromMeta;
+use orm::types::KeyStrategy;
 use orm_schema::Schemable;
 use proc_macro2::TokenStream;
-use syn::{Ident, Path};
 use quote::quote;
+use syn::{Ident, Path};
 
 ///
 /// Entity
@@ -21,6 +22,13 @@ pub struct Entity {
     #[darling(default)]
     pub debug: bool,
 
+    #[darling(default)]
+    pub arbitrary: bool,
+
+    // also generates an {Entity}Builder with with_* setters
+    #[darling(default)]
+    pub builder: bool,
+
     pub store: Path,
 
     #[darling(multiple, rename = "sk")]
@@ -29,12 +37,22 @@ pub struct Entity {
     #[darling(default, rename = "pks", map = "split_idents")]
     pub primary_keys: Vec<Ident>,
 
+    #[darling(default)]
+    pub key_strategy: KeyStrategy,
+
     #[darling(default)]
     pub fields: FieldList,
 
+    #[darling(multiple, rename = "index")]
+    pub indexes: Vec<Index>,
+
     #[darling(multiple, rename = "source")]
     pub sources: Vec<Path>,
 
+    // fn(&Self) -> Result<(), FieldErrors>, run after the fields are validated
+    #[darling(multiple, rename = "check")]
+    pub checks: Vec<Path>,
+
     #[darling(default)]
     pub crud: Option<Crud>,
 
@@ -51,6 +69,11 @@ impl Node for Entity {
         let schema = self.ctor_schema();
         let derive = self.derive_struct();
         let imp = self.imp();
+        let builder = if self.builder {
+            imp::node::entity::builder(self)
+        } else {
+            quote!()
+        };
         let q = quote! {
             #schema
             #derive
@@ -58,6 +81,7 @@ impl Node for Entity {
                 #fields
             }
             #imp
+            #builder
         };
 
         // debug
@@ -85,6 +109,7 @@ impl TraitNode for Entity {
             Trait::FieldFilter,
             Trait::SortKey,
         ]);
+        traits.add_arbitrary(self.arbitrary);
 
         traits.list()
     }
@@ -98,11 +123,13 @@ impl TraitNode for Entity {
 
     fn map_imp(&self, t: Trait) -> TokenStream {
         match t {
+            Trait::Arbitrary => imp::arbitrary::entity(self, t),
             Trait::Default if self.fields.has_default() => imp::default::entity(self, t),
             Trait::Entity => imp::node::entity::entity(self, t),
             Trait::EntityDynamic => imp::node::entity::entity_dynamic(self, t),
             Trait::FieldFilter => imp::record_filter::entity(self, t),
             Trait::FieldSort => imp::record_sort::entity(self, t),
+            Trait::Validate if !self.checks.is_empty() => imp::node::entity::validate(self, t),
             Trait::Visitable => imp::visitable::entity(self, t),
 
             _ => imp::any(self, t),
@@ -117,6 +144,8 @@ impl Schemable for Entity {
         let sort_keys = quote_vec(&self.sort_keys, SortKey::schema);
         let fields = &self.fields.schema();
         let primary_keys = quote_vec(&self.primary_keys, to_string);
+        let key_strategy = self.key_strategy.schema();
+        let indexes = quote_vec(&self.indexes, Index::schema);
         let sources = quote_vec(&self.sources, to_path);
         let crud = quote_option(&self.crud, Crud::schema);
 
@@ -127,6 +156,8 @@ impl Schemable for Entity {
                 sort_keys: #sort_keys,
                 fields: #fields,
                 primary_keys: #primary_keys,
+                key_strategy: #key_strategy,
+                indexes: #indexes,
                 sources: #sources,
                 crud: #crud,
             })
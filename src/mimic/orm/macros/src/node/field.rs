@@ -62,17 +62,35 @@ impl ToTokens for FieldList {
 pub struct Field {
     pub name: Ident,
     pub value: Value,
+
+    // previous names, still accepted when deserializing
+    #[darling(multiple, rename = "alias")]
+    pub aliases: Vec<String>,
+
+    // saves fail if the related row doesn't exist
+    #[darling(default)]
+    pub check_relation: bool,
+
+    // the row is deleted along with the related row by a cascade delete
+    #[darling(default)]
+    pub owned: bool,
 }
 
 impl Schemable for Field {
     fn schema(&self) -> TokenStream {
         let name = quote_one(&self.name, to_string);
         let value = self.value.schema();
+        let aliases = quote_vec(&self.aliases, |alias| quote!(#alias.to_string()));
+        let check_relation = self.check_relation;
+        let owned = self.owned;
 
         quote! {
             ::mimic::orm::schema::node::Field {
                 name: #name,
                 value: #value,
+                aliases: #aliases,
+                check_relation: #check_relation,
+                owned: #owned,
             }
         }
     }
@@ -82,9 +100,11 @@ impl ToTokens for Field {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         let name = &self.name;
         let value = &self.value;
+        let aliases = &self.aliases;
 
         // bulld struct field
         tokens.extend(quote! {
+            #(#[serde(alias = #aliases)])*
             pub #name : #value,
         });
     }
@@ -0,0 +1,33 @@
+use crate::helper::{quote_one, to_string};
+use darling::FromMeta;
+use orm_schema::Schemable;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::Ident;
+
+///
+/// Index
+///
+
+#[derive(Debug, FromMeta)]
+pub struct Index {
+    pub field: Ident,
+
+    // saves fail if another row already holds the value
+    #[darling(default)]
+    pub unique: bool,
+}
+
+impl Schemable for Index {
+    fn schema(&self) -> TokenStream {
+        let field = quote_one(&self.field, to_string);
+        let unique = self.unique;
+
+        quote! {
+            ::mimic::orm::schema::node::Index {
+                field: #field,
+                unique: #unique,
+            }
+        }
+    }
+}
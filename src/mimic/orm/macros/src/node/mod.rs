@@ -8,6 +8,7 @@ mod enum_hash;
 mod field;
 mod fixture;
 mod guide;
+mod index;
 mod item;
 mod map;
 mod newtype;
@@ -34,6 +35,7 @@ pub use self::enum_hash::*;
 pub use self::field::*;
 pub use self::fixture::*;
 pub use self::guide::*;
+pub use self::index::*;
 pub use self::item::*;
 pub use self::map::*;
 pub use self::newtype::*;
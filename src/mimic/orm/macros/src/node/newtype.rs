@@ -14,6 +14,11 @@ use quote::quote;
 ///
 /// Newtype
 ///
+/// numeric and string newtypes get Display from their primitive, one with
+/// its own impl takes traits(remove(Display)), a newtype over an enum has no
+/// primitive so it takes traits(add(Display)) and the enum takes
+/// traits(add(EnumDisplay)), the macro can't see what the item path points to
+///
 
 #[derive(Debug, FromMeta)]
 pub struct Newtype {
@@ -23,6 +28,9 @@ pub struct Newtype {
     #[darling(default)]
     pub debug: bool,
 
+    #[darling(default)]
+    pub arbitrary: bool,
+
     pub value: Value,
 
     #[darling(default)]
@@ -52,11 +60,13 @@ impl Node for Newtype {
         let schema = self.ctor_schema();
         let derive = self.derive();
         let imp = self.imp();
+        let collection = self.collection_methods();
         let q = quote! {
             #schema
             #derive
             pub struct #ident #generics(#value);
             #imp
+            #collection
         };
 
         // debug
@@ -66,6 +76,54 @@ impl Node for Newtype {
     }
 }
 
+impl Newtype {
+    // collection_methods
+    // newtypes over a Many value get the common collection methods directly
+    fn collection_methods(&self) -> TokenStream {
+        if self.value.cardinality() != Cardinality::Many {
+            return quote!();
+        }
+
+        let Def {
+            ident, generics, ..
+        } = &self.def;
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+        let item = self.value.item_type();
+        let push = if self.value.item.is_relation() {
+            quote!(self.0.insert(value);)
+        } else {
+            quote!(self.0.push(value);)
+        };
+
+        quote! {
+            impl #impl_generics #ident #ty_generics #where_clause {
+                pub fn push(&mut self, value: #item) {
+                    #push
+                }
+
+                pub fn extend<I: IntoIterator<Item = #item>>(&mut self, iter: I) {
+                    self.0.extend(iter);
+                }
+
+                #[must_use]
+                pub fn iter(&self) -> impl Iterator<Item = &#item> + '_ {
+                    self.0.iter()
+                }
+
+                #[must_use]
+                pub fn len(&self) -> usize {
+                    self.0.len()
+                }
+
+                #[must_use]
+                pub fn is_empty(&self) -> bool {
+                    self.0.is_empty()
+                }
+            }
+        }
+    }
+}
+
 impl MacroNode for Newtype {
     fn def(&self) -> &Def {
         &self.def
@@ -81,9 +139,15 @@ impl TraitNode for Newtype {
             Trait::Default,
             Trait::Deref,
             Trait::DerefMut,
-            Trait::From,
         ]);
 
+        // validated newtypes can only be converted into through TryFrom
+        if self.validators.is_empty() {
+            traits.add(Trait::From);
+        } else {
+            traits.add(Trait::TryFrom);
+        }
+
         match &self.value.cardinality() {
             Cardinality::One | Cardinality::Opt => {
                 traits.extend(vec![Trait::Ord, Trait::PartialOrd]);
@@ -91,6 +155,8 @@ impl TraitNode for Newtype {
             Cardinality::Many => {}
         }
 
+        traits.add_arbitrary(self.arbitrary);
+
         // inner
         if self.primitive.is_some() {
             traits.add(Trait::Inner);
@@ -100,19 +166,27 @@ impl TraitNode for Newtype {
         match self.primitive.map(|p| p.group()) {
             Some(PrimitiveGroup::Integer | PrimitiveGroup::Decimal) => {
                 traits.extend(vec![
-                    Trait::Add,
-                    Trait::AddAssign,
                     Trait::Copy,
                     Trait::Display,
                     Trait::FromStr,
-                    Trait::Mul,
-                    Trait::MulAssign,
                     Trait::NumCast,
                     Trait::NumFromPrimitive,
                     Trait::NumToPrimitive,
-                    Trait::Sub,
-                    Trait::SubAssign,
                 ]);
+
+                // the operators and the saturating ops can't fail, so they'd
+                // build values that break the validators
+                if self.validators.is_empty() {
+                    traits.extend(vec![
+                        Trait::Add,
+                        Trait::AddAssign,
+                        Trait::Mul,
+                        Trait::MulAssign,
+                        Trait::NumChecked,
+                        Trait::Sub,
+                        Trait::SubAssign,
+                    ]);
+                }
             }
             Some(PrimitiveGroup::String) => {
                 traits.extend(vec![Trait::Display, Trait::FromStr]);
@@ -133,17 +207,20 @@ impl TraitNode for Newtype {
 
     fn map_imp(&self, t: Trait) -> TokenStream {
         match t {
+            Trait::Arbitrary => imp::arbitrary::newtype(self, t),
             Trait::Default if self.value.default.is_some() => imp::default::newtype(self, t),
             Trait::Display => imp::display::newtype(self, t),
             Trait::Filterable => imp::filterable::newtype(self, t),
             Trait::From => imp::from::newtype(self, t),
             Trait::Inner => imp::inner::newtype(self, t),
             Trait::NumCast => imp::num::cast::newtype(self, t),
+            Trait::NumChecked => imp::num::checked::newtype(self, t),
             Trait::NumToPrimitive => imp::num::to_primitive::newtype(self, t),
             Trait::NumFromPrimitive => imp::num::from_primitive::newtype(self, t),
             Trait::Orderable => imp::orderable::newtype(self, t),
             Trait::PrimaryKey => imp::primary_key::newtype(self, t),
             Trait::SanitizeAuto => imp::sanitize_auto::newtype(self, t),
+            Trait::TryFrom => imp::from::newtype_try(self, t),
             Trait::ValidateAuto => imp::validate_auto::newtype(self, t),
             Trait::Visitable => imp::visitable::newtype(self, t),
 
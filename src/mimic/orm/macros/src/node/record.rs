@@ -19,6 +19,9 @@ pub struct Record {
     #[darling(default)]
     pub debug: bool,
 
+    #[darling(default)]
+    pub arbitrary: bool,
+
     #[darling(default)]
     pub fields: FieldList,
 
@@ -75,6 +78,7 @@ impl TraitNode for Record {
     fn traits(&self) -> Vec<Trait> {
         let mut traits = self.traits.clone();
         traits.add_db_traits();
+        traits.add_arbitrary(self.arbitrary);
 
         traits.list()
     }
@@ -88,6 +92,7 @@ impl TraitNode for Record {
 
     fn map_imp(&self, t: Trait) -> TokenStream {
         match t {
+            Trait::Arbitrary => imp::arbitrary::record(self, t),
             Trait::Default if self.fields.has_default() => imp::default::record(self, t),
             Trait::FieldFilter => imp::record_filter::record(self, t),
             Trait::FieldSort => imp::record_sort::record(self, t),
@@ -1,7 +1,7 @@
 use darling::{ast::NestedMeta, Error as DarlingError, FromMeta};
 use derive_more::{Deref, DerefMut};
 use proc_macro2::TokenStream;
-use quote::{quote, format_ident, ToTokens};
+use quote::{format_ident, quote, ToTokens};
 use serde::{Deserialize, Serialize};
 use std::{collections::HashSet, hash::Hash, str::FromStr, sync::LazyLock};
 use strum::{Display, EnumString};
@@ -53,8 +53,10 @@ pub enum Trait {
     Serialize,
     Sub,
     SubAssign,
+    TryFrom,
 
     // orm
+    Arbitrary,
     CandidType,
     Entity,
     EntityDynamic,
@@ -66,6 +68,7 @@ pub enum Trait {
     FieldSort,
     Filterable,
     Inner,
+    NumChecked,
     NumFromPrimitive,
     NumToPrimitive,
     Path,
@@ -219,6 +222,14 @@ impl Traits {
         self.add.extend(DB_TRAITS.to_vec());
     }
 
+    // add_arbitrary
+    // only if the node opts in and the macros are built with the test feature
+    pub fn add_arbitrary(&mut self, arbitrary: bool) {
+        if arbitrary && cfg!(feature = "test") {
+            self.add(Trait::Arbitrary);
+        }
+    }
+
     // add
     pub fn add(&mut self, tr: Trait) {
         self.add.push(tr);
@@ -55,27 +55,32 @@ impl Schemable for Value {
     }
 }
 
-impl ToTokens for Value {
-    fn to_tokens(&self, tokens: &mut TokenStream) {
+impl Value {
+    // item_type
+    // the type of a single item, relations always use the same ulid type
+    pub fn item_type(&self) -> TokenStream {
         match &self.item {
-            Item::Is(item) => {
-                tokens.extend(match self.cardinality() {
-                    Cardinality::One => quote!(#item),
-                    Cardinality::Opt => quote!(Option<#item>),
-                    Cardinality::Many => quote!(Vec<#item>),
-                });
-            }
-
+            Item::Is(item) => quote!(#item),
             Item::Relation(_) => {
-                // always use the same ulid type
                 let item: Path = syn::parse_str(PRIM_ULID).unwrap();
 
-                tokens.extend(match self.cardinality() {
-                    Cardinality::One => quote!(#item),
-                    Cardinality::Opt => quote!(Option<#item>),
-                    Cardinality::Many => quote!(::mimic::orm::collections::HashSet<#item>),
-                });
+                quote!(#item)
             }
         }
     }
 }
+
+impl ToTokens for Value {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let item = self.item_type();
+
+        tokens.extend(match (self.cardinality(), &self.item) {
+            (Cardinality::One, _) => quote!(#item),
+            (Cardinality::Opt, _) => quote!(Option<#item>),
+            (Cardinality::Many, Item::Is(_)) => quote!(Vec<#item>),
+            (Cardinality::Many, Item::Relation(_)) => {
+                quote!(::mimic::orm::collections::HashSet<#item>)
+            }
+        });
+    }
+}
@@ -14,6 +14,9 @@ use types::ErrorTree;
 
 #[derive(CandidType, Debug, Serialize, Deserialize, Snafu)]
 pub enum Error {
+    #[snafu(display("serde json error: {msg}"))]
+    SerdeJson { msg: String },
+
     #[snafu(display("validation failed: {errors}"))]
     Validation { errors: ErrorTree },
 }
@@ -34,11 +37,22 @@ pub fn schema() -> RwLockReadGuard<'static, Schema> {
     SCHEMA.read().unwrap()
 }
 
+// load_json
+// replaces the global schema with one deserialized from JSON, so that schema
+// files produced by other builds can be validated
+pub fn load_json(json: &str) -> Result<(), Error> {
+    let loaded = serde_json::from_str::<Schema>(json)
+        .map_err(|e| Error::SerdeJson { msg: e.to_string() })?;
+    *schema_write() = loaded;
+
+    Ok(())
+}
+
 /// validate
 pub fn validate() -> Result<(), Error> {
     // validate using the visitor
     let mut visitor = Validator::new();
-    schema().accept(&mut visitor);
+    let _ = schema().accept(&mut visitor);
 
     // result
     visitor
@@ -1,6 +1,8 @@
 pub mod build;
 pub mod helper;
 pub mod node;
+#[cfg(any(test, feature = "test"))]
+pub mod test;
 pub mod types;
 pub mod visit;
 
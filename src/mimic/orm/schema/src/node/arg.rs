@@ -4,6 +4,7 @@ use crate::{
 };
 use derive_more::Deref;
 use serde::{Deserialize, Serialize};
+use std::ops::ControlFlow;
 use strum::Display;
 
 ///
@@ -14,6 +15,7 @@ use strum::Display;
 pub enum Arg {
     Bool(bool),
     Char(char),
+    Expr(String),
     Number(ArgNumber),
     Path(String),
     String(String),
@@ -26,10 +28,12 @@ impl VisitableNode for Arg {
         format!("arg ({self})")
     }
 
-    fn drive<V: Visitor>(&self, v: &mut V) {
+    fn drive<V: Visitor>(&self, v: &mut V) -> ControlFlow<()> {
         if let Self::Number(node) = self {
-            node.accept(v);
+            node.accept(v)?;
         }
+
+        ControlFlow::Continue(())
     }
 }
 
@@ -71,6 +75,31 @@ pub enum ArgNumber {
     Usize(usize),
 }
 
+impl ArgNumber {
+    // as_f64
+    // lossy for the widest integers, it's only used to compare schema values
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub const fn as_f64(&self) -> f64 {
+        match *self {
+            Self::F32(n) => n as f64,
+            Self::F64(n) => n,
+            Self::I8(n) => n as f64,
+            Self::I16(n) => n as f64,
+            Self::I32(n) => n as f64,
+            Self::I64(n) => n as f64,
+            Self::I128(n) => n as f64,
+            Self::Isize(n) => n as f64,
+            Self::U8(n) => n as f64,
+            Self::U16(n) => n as f64,
+            Self::U32(n) => n as f64,
+            Self::U64(n) => n as f64,
+            Self::U128(n) => n as f64,
+            Self::Usize(n) => n as f64,
+        }
+    }
+}
+
 impl ValidateNode for ArgNumber {}
 
 impl VisitableNode for ArgNumber {}
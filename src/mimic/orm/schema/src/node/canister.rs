@@ -13,10 +13,25 @@ use types::ErrorVec;
 /// VALIDATE_MIN_CYCLES
 pub const VALIDATE_MIN_CYCLES: u128 = 3 * TC;
 
+/// CYCLES_CHECK_SECS
+/// how often a canister checks its balance and requests cycles from root
+pub const CYCLES_CHECK_SECS: u64 = 600;
+
+//
+// UPGRADE
+//
+
+/// UPGRADE_WARN_BYTES
+/// stores larger than this are logged as a warning before an upgrade
+pub const UPGRADE_WARN_BYTES: u64 = 1 << 30;
+
 ///
 /// Canister
 /// u128 cycles are easier to deal with
 ///
+/// cycles_check_secs  : overrides CYCLES_CHECK_SECS for this canister
+/// upgrade_warn_bytes : overrides UPGRADE_WARN_BYTES for this canister
+///
 
 #[derive(CandidType, Clone, Debug, Serialize, Deserialize)]
 pub struct Canister {
@@ -24,6 +39,12 @@ pub struct Canister {
     pub initial_cycles: u128,
     pub min_cycles: u128,
     pub build: CanisterBuild,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cycles_check_secs: Option<u64>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub upgrade_warn_bytes: Option<u64>,
 }
 
 impl Canister {
@@ -33,6 +54,18 @@ impl Canister {
     pub fn name(&self) -> String {
         self.def.ident.to_case(Case::Snake)
     }
+
+    // cycles_check_secs
+    #[must_use]
+    pub fn cycles_check_secs(&self) -> u64 {
+        self.cycles_check_secs.unwrap_or(CYCLES_CHECK_SECS)
+    }
+
+    // upgrade_warn_bytes
+    #[must_use]
+    pub fn upgrade_warn_bytes(&self) -> u64 {
+        self.upgrade_warn_bytes.unwrap_or(UPGRADE_WARN_BYTES)
+    }
 }
 
 impl MacroNode for Canister {
@@ -56,6 +89,9 @@ impl ValidateNode for Canister {
                 "min_cycles cannot be less than the configured minimum {VALIDATE_MIN_CYCLES}",
             ));
         }
+        if self.cycles_check_secs == Some(0) {
+            errs.add("cycles_check_secs cannot be zero");
+        }
 
         errs.result()
     }
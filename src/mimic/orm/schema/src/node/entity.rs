@@ -1,10 +1,13 @@
 use crate::{
     build::schema,
-    node::{Crud, Def, FieldList, MacroNode, SortKey, Store, ValidateNode, VisitableNode},
+    node::{
+        Crud, Def, Error, FieldList, Index, MacroNode, SortKey, Store, ValidateNode, VisitableNode,
+    },
+    types::{Cardinality, KeyStrategy, PrimitiveType},
     visit::Visitor,
 };
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::{collections::HashSet, ops::ControlFlow};
 use types::ErrorVec;
 
 ///
@@ -21,6 +24,12 @@ pub struct Entity {
 
     pub primary_keys: Vec<String>,
 
+    #[serde(default)]
+    pub key_strategy: KeyStrategy,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub indexes: Vec<Index>,
+
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub sources: Vec<String>,
 
@@ -49,7 +58,16 @@ impl ValidateNode for Entity {
         let mut errs = ErrorVec::new();
 
         // store
-        errs.add_result(schema().check_node::<Store>(&self.store));
+        // a typo here would otherwise only surface on the first save
+        match schema().check_node::<Store>(&self.store) {
+            Ok(()) => {}
+            Err(Error::PathNotFound { .. }) => errs.add(format!(
+                "entity '{}' references store '{}' which is not declared",
+                self.def.path(),
+                self.store
+            )),
+            Err(e) => errs.add(format!("entity '{}' store: {e}", self.def.path())),
+        }
 
         // sort keys
         for sk in &self.sort_keys {
@@ -63,7 +81,66 @@ impl ValidateNode for Entity {
                 errs.add(format!("primary key field '{pk}' not found"));
             }
             if !seen.insert(pk.clone()) {
-                errs.push(format!("duplicate value for primary key field '{pk}'"));
+                errs.add(format!("duplicate value for primary key field '{pk}'"));
+            }
+        }
+
+        // owned relations
+        // cascade deletes find the children through the index
+        for field in &self.fields.fields {
+            if field.owned && !self.indexes.iter().any(|index| index.field == field.name) {
+                errs.add(format!("owned field '{}' needs an index", field.name));
+            }
+        }
+
+        // key strategy
+        if self.key_strategy.needs_single_key() && self.primary_keys.len() != 1 {
+            errs.add(format!(
+                "key strategy {:?} needs exactly one primary key",
+                self.key_strategy
+            ));
+        }
+
+        // the keys have to be able to hold what the strategy generates,
+        // otherwise the key would silently stay at its default
+        let allowed: &[PrimitiveType] = match self.key_strategy {
+            KeyStrategy::Ulid => &[PrimitiveType::Ulid, PrimitiveType::U128],
+            KeyStrategy::ContentHash | KeyStrategy::Sequential => &[
+                PrimitiveType::Ulid,
+                PrimitiveType::U64,
+                PrimitiveType::U128,
+                PrimitiveType::I128,
+            ],
+            KeyStrategy::Provided | KeyStrategy::Random => &[],
+        };
+        if !allowed.is_empty() {
+            let schema = schema();
+            for pk in &self.primary_keys {
+                let ty = schema.field_primitive_type(&self.def.path(), pk);
+                if !ty.is_some_and(|ty| allowed.contains(&ty)) {
+                    errs.add(format!(
+                        "key strategy {:?} needs primary key '{pk}' to be one of {allowed:?}",
+                        self.key_strategy
+                    ));
+                }
+            }
+        }
+
+        // indexes
+        let mut seen = HashSet::<String>::default();
+        for index in &self.indexes {
+            match self.fields.get_field(&index.field) {
+                Some(field) if field.value.cardinality == Cardinality::Many => {
+                    errs.add(format!(
+                        "index field '{}' cannot be a collection",
+                        index.field
+                    ));
+                }
+                Some(_) => {}
+                None => errs.add(format!("index field '{}' not found", index.field)),
+            }
+            if !seen.insert(index.field.clone()) {
+                errs.add(format!("duplicate index on field '{}'", index.field));
             }
         }
 
@@ -76,14 +153,19 @@ impl VisitableNode for Entity {
         self.def.path()
     }
 
-    fn drive<V: Visitor>(&self, v: &mut V) {
-        self.def.accept(v);
+    fn drive<V: Visitor>(&self, v: &mut V) -> ControlFlow<()> {
+        self.def.accept(v)?;
         for node in &self.sort_keys {
-            node.accept(v);
+            node.accept(v)?;
+        }
+        for node in &self.indexes {
+            node.accept(v)?;
         }
         if let Some(node) = &self.crud {
-            node.accept(v);
+            node.accept(v)?;
         }
-        self.fields.accept(v);
+        self.fields.accept(v)?;
+
+        ControlFlow::Continue(())
     }
 }
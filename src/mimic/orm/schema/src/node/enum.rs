@@ -4,7 +4,7 @@ use crate::{
 };
 use lib_case::{Case, Casing};
 use serde::{Deserialize, Serialize};
-use std::ops::Not;
+use std::ops::{ControlFlow, Not};
 use types::ErrorVec;
 
 ///
@@ -30,11 +30,13 @@ impl VisitableNode for Enum {
         self.def.path()
     }
 
-    fn drive<V: Visitor>(&self, v: &mut V) {
-        self.def.accept(v);
+    fn drive<V: Visitor>(&self, v: &mut V) -> ControlFlow<()> {
+        self.def.accept(v)?;
         for node in &self.variants {
-            node.accept(v);
+            node.accept(v)?;
         }
+
+        ControlFlow::Continue(())
     }
 }
 
@@ -91,9 +93,11 @@ impl ValidateNode for EnumVariant {
 }
 
 impl VisitableNode for EnumVariant {
-    fn drive<V: Visitor>(&self, v: &mut V) {
+    fn drive<V: Visitor>(&self, v: &mut V) -> ControlFlow<()> {
         if let Some(node) = &self.value {
-            node.accept(v);
+            node.accept(v)?;
         }
+
+        ControlFlow::Continue(())
     }
 }
@@ -6,6 +6,7 @@ use crate::{
 };
 use lib_case::{Case, Casing};
 use serde::{Deserialize, Serialize};
+use std::ops::{ControlFlow, Not};
 use types::ErrorVec;
 
 ///
@@ -39,18 +40,33 @@ impl ValidateNode for FieldList {
             }
         }
 
+        // aliases
+        // serde would read an aliased key into the wrong field
+        for field in &self.fields {
+            for alias in &field.aliases {
+                if self.get_field(alias).is_some() {
+                    errs.add(format!(
+                        "alias '{alias}' on field '{}' is the name of a field",
+                        field.name
+                    ));
+                }
+            }
+        }
+
         errs.result()
     }
 }
 
 impl VisitableNode for FieldList {
-    fn drive<V: Visitor>(&self, v: &mut V) {
+    fn drive<V: Visitor>(&self, v: &mut V) -> ControlFlow<()> {
         for node in &self.fields {
-            node.accept(v);
+            node.accept(v)?;
         }
         for node in &self.order {
-            node.accept(v);
+            node.accept(v)?;
         }
+
+        ControlFlow::Continue(())
     }
 }
 
@@ -62,6 +78,15 @@ impl VisitableNode for FieldList {
 pub struct Field {
     pub name: String,
     pub value: Value,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+
+    #[serde(default, skip_serializing_if = "Not::not")]
+    pub check_relation: bool,
+
+    #[serde(default, skip_serializing_if = "Not::not")]
+    pub owned: bool,
 }
 
 impl ValidateNode for Field {
@@ -91,6 +116,22 @@ impl ValidateNode for Field {
             errs.add(format!("field name '{}' must be in snake_case", self.name));
         }
 
+        // check_relation
+        if self.check_relation && !self.value.item.is_relation() {
+            errs.add(format!(
+                "field '{ident}' has check_relation but is not a relation"
+            ));
+        }
+
+        // owned
+        if self.owned
+            && (!self.value.item.is_relation() || self.value.cardinality == Cardinality::Many)
+        {
+            errs.add(format!(
+                "owned field '{ident}' must be a one or optional relation"
+            ));
+        }
+
         // check for relations with confusing idents
         if self.value.item.is_relation() {
             let cr = self.value.cardinality;
@@ -114,8 +155,10 @@ impl ValidateNode for Field {
 }
 
 impl VisitableNode for Field {
-    fn drive<V: Visitor>(&self, v: &mut V) {
-        self.value.accept(v);
+    fn drive<V: Visitor>(&self, v: &mut V) -> ControlFlow<()> {
+        self.value.accept(v)?;
+
+        ControlFlow::Continue(())
     }
 }
 
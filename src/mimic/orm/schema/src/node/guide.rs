@@ -4,6 +4,7 @@ use crate::{
 };
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
+use std::ops::ControlFlow;
 use types::ErrorVec;
 
 ///
@@ -41,10 +42,12 @@ impl ValidateNode for Guide {
 }
 
 impl VisitableNode for Guide {
-    fn drive<V: Visitor>(&self, v: &mut V) {
+    fn drive<V: Visitor>(&self, v: &mut V) -> ControlFlow<()> {
         for node in &self.entries {
-            node.accept(v);
+            node.accept(v)?;
         }
+
+        ControlFlow::Continue(())
     }
 }
 
@@ -0,0 +1,27 @@
+use crate::node::{ValidateNode, VisitableNode};
+use serde::{Deserialize, Serialize};
+use std::ops::Not;
+
+///
+/// Index
+///
+/// a single field equality index on an Entity, kept up to date by the
+/// save and delete builders, a unique index also stops two rows from
+/// being saved with the same value
+///
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Index {
+    pub field: String,
+
+    #[serde(default, skip_serializing_if = "Not::not")]
+    pub unique: bool,
+}
+
+impl ValidateNode for Index {}
+
+impl VisitableNode for Index {
+    fn route_key(&self) -> String {
+        format!("index ({})", self.field)
+    }
+}
@@ -4,7 +4,7 @@ use crate::{
     visit::Visitor,
 };
 use serde::{Deserialize, Serialize};
-use std::{any::TypeId, collections::HashSet};
+use std::{any::TypeId, collections::HashSet, ops::ControlFlow};
 use types::ErrorVec;
 
 ///
@@ -28,7 +28,7 @@ impl Item {
 impl ValidateNode for Item {}
 
 impl VisitableNode for Item {
-    fn drive<V: Visitor>(&self, v: &mut V) {
+    fn drive<V: Visitor>(&self, v: &mut V) -> ControlFlow<()> {
         match self {
             Self::Is(node) => node.accept(v),
             Self::Relation(node) => node.accept(v),
@@ -71,6 +71,8 @@ impl VisitableNode for ItemIs {}
 ///
 /// ItemRelation
 ///
+/// a relation is stored as the Ulid of the related entity
+///
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ItemRelation {
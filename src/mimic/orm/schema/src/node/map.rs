@@ -3,6 +3,7 @@ use crate::{
     visit::Visitor,
 };
 use serde::{Deserialize, Serialize};
+use std::ops::ControlFlow;
 
 ///
 /// Map
@@ -31,12 +32,14 @@ impl VisitableNode for Map {
         self.def.path()
     }
 
-    fn drive<V: Visitor>(&self, v: &mut V) {
-        self.def.accept(v);
-        self.key.accept(v);
-        self.value.accept(v);
+    fn drive<V: Visitor>(&self, v: &mut V) -> ControlFlow<()> {
+        self.def.accept(v)?;
+        self.key.accept(v)?;
+        self.value.accept(v)?;
         for node in &self.validators {
-            node.accept(v);
+            node.accept(v)?;
         }
+
+        ControlFlow::Continue(())
     }
 }
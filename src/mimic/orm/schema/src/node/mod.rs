@@ -8,6 +8,7 @@ mod enum_hash;
 mod field;
 mod fixture;
 mod guide;
+mod index;
 mod item;
 mod map;
 mod newtype;
@@ -33,6 +34,7 @@ pub use self::enum_hash::*;
 pub use self::field::*;
 pub use self::fixture::*;
 pub use self::guide::*;
+pub use self::index::*;
 pub use self::item::*;
 pub use self::map::*;
 pub use self::newtype::*;
@@ -57,7 +59,7 @@ use crate::{
 use candid::CandidType;
 use serde::{Deserialize, Serialize};
 use snafu::Snafu;
-use std::any::Any;
+use std::{any::Any, ops::ControlFlow};
 use types::ErrorVec;
 
 ///
@@ -69,6 +71,9 @@ pub enum Error {
     #[snafu(display("error downcasting schema node: {path}"))]
     DowncastFail { path: String },
 
+    #[snafu(display("duplicate path {path} used by nodes: {}", nodes.join(", ")))]
+    DuplicatePath { path: String, nodes: Vec<String> },
+
     #[snafu(display("{path} is an incorrect node type"))]
     IncorrectNodeType { path: String },
 
@@ -83,6 +88,13 @@ impl Error {
         }
     }
 
+    fn duplicate_path(path: &str, nodes: &[String]) -> Self {
+        Self::DuplicatePath {
+            path: path.to_string(),
+            nodes: nodes.to_vec(),
+        }
+    }
+
     fn incorrect_node_type(path: &str) -> Self {
         Self::IncorrectNodeType {
             path: path.to_string(),
@@ -131,16 +143,26 @@ pub trait VisitableNode: ValidateNode {
     }
 
     // accept
-    fn accept<V: Visitor>(&self, visitor: &mut V) {
+    // a Break from the visitor or any child stops the walk
+    fn accept<V: Visitor>(&self, visitor: &mut V) -> ControlFlow<()> {
         visitor.push(&self.route_key());
-        visitor.visit(self, Event::Enter);
-        self.drive(visitor);
-        visitor.visit(self, Event::Exit);
+        let flow = visit_node(self, visitor);
         visitor.pop();
+
+        flow
     }
 
     // drive
-    fn drive<V: Visitor>(&self, _: &mut V) {}
+    fn drive<V: Visitor>(&self, _: &mut V) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+}
+
+// visit_node
+fn visit_node<N: VisitableNode + ?Sized, V: Visitor>(node: &N, visitor: &mut V) -> ControlFlow<()> {
+    visitor.visit(node, Event::Enter)?;
+    node.drive(visitor)?;
+    visitor.visit(node, Event::Exit)
 }
 
 ///
@@ -189,9 +211,11 @@ pub struct Crud {
 impl ValidateNode for Crud {}
 
 impl VisitableNode for Crud {
-    fn drive<V: Visitor>(&self, v: &mut V) {
-        self.load.accept(v);
-        self.save.accept(v);
-        self.delete.accept(v);
+    fn drive<V: Visitor>(&self, v: &mut V) -> ControlFlow<()> {
+        self.load.accept(v)?;
+        self.save.accept(v)?;
+        self.delete.accept(v)?;
+
+        ControlFlow::Continue(())
     }
 }
@@ -6,6 +6,8 @@ use crate::{
     visit::Visitor,
 };
 use serde::{Deserialize, Serialize};
+use std::ops::ControlFlow;
+use types::ErrorVec;
 
 ///
 /// Newtype
@@ -35,24 +37,43 @@ impl MacroNode for Newtype {
     }
 }
 
-impl ValidateNode for Newtype {}
+impl ValidateNode for Newtype {
+    fn validate(&self) -> Result<(), ErrorVec> {
+        let mut errs = ErrorVec::new();
+
+        // guide entries shouldn't offer values the validators reject
+        if let Some(guide) = &self.guide {
+            for entry in &guide.entries {
+                for validator in &self.validators {
+                    if let Err(msg) = validator.check_number(&entry.value) {
+                        errs.add(format!("guide entry {msg}"));
+                    }
+                }
+            }
+        }
+
+        errs.result()
+    }
+}
 
 impl VisitableNode for Newtype {
     fn route_key(&self) -> String {
         self.def.path()
     }
 
-    fn drive<V: Visitor>(&self, v: &mut V) {
-        self.def.accept(v);
-        self.value.accept(v);
+    fn drive<V: Visitor>(&self, v: &mut V) -> ControlFlow<()> {
+        self.def.accept(v)?;
+        self.value.accept(v)?;
         if let Some(node) = &self.guide {
-            node.accept(v);
+            node.accept(v)?;
         }
         for node in &self.sanitizers {
-            node.accept(v);
+            node.accept(v)?;
         }
         for node in &self.validators {
-            node.accept(v);
+            node.accept(v)?;
         }
+
+        ControlFlow::Continue(())
     }
 }
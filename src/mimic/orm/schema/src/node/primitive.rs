@@ -4,6 +4,7 @@ use crate::{
     visit::Visitor,
 };
 use serde::{Deserialize, Serialize};
+use std::ops::ControlFlow;
 
 ///
 /// Primitive
@@ -29,7 +30,9 @@ impl VisitableNode for Primitive {
         self.def.path()
     }
 
-    fn drive<V: Visitor>(&self, v: &mut V) {
-        self.def.accept(v);
+    fn drive<V: Visitor>(&self, v: &mut V) -> ControlFlow<()> {
+        self.def.accept(v)?;
+
+        ControlFlow::Continue(())
     }
 }
@@ -3,6 +3,7 @@ use crate::{
     visit::Visitor,
 };
 use serde::{Deserialize, Serialize};
+use std::ops::ControlFlow;
 
 ///
 /// Record
@@ -27,8 +28,10 @@ impl VisitableNode for Record {
         self.def.path()
     }
 
-    fn drive<V: Visitor>(&self, v: &mut V) {
-        self.def.accept(v);
-        self.fields.accept(v);
+    fn drive<V: Visitor>(&self, v: &mut V) -> ControlFlow<()> {
+        self.def.accept(v)?;
+        self.fields.accept(v)?;
+
+        ControlFlow::Continue(())
     }
 }
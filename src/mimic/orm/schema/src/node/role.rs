@@ -4,7 +4,7 @@ use crate::{
     visit::Visitor,
 };
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::{collections::HashSet, ops::ControlFlow};
 use types::ErrorVec;
 
 ///
@@ -38,7 +38,7 @@ impl ValidateNode for Role {
         for perm in &self.permissions {
             errs.add_result(schema().check_node::<Permission>(perm));
             if !seen.insert(perm.clone()) {
-                errs.push(format!("duplicate value for permission '{perm}'"));
+                errs.add(format!("duplicate value for permission '{perm}'"));
             }
         }
 
@@ -51,7 +51,9 @@ impl VisitableNode for Role {
         self.def.path()
     }
 
-    fn drive<V: Visitor>(&self, v: &mut V) {
-        self.def.accept(v);
+    fn drive<V: Visitor>(&self, v: &mut V) -> ControlFlow<()> {
+        self.def.accept(v)?;
+
+        ControlFlow::Continue(())
     }
 }
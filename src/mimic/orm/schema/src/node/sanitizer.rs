@@ -3,6 +3,7 @@ use crate::{
     visit::Visitor,
 };
 use serde::{Deserialize, Serialize};
+use std::ops::ControlFlow;
 
 ///
 /// Sanitizer
@@ -26,7 +27,9 @@ impl VisitableNode for Sanitizer {
         self.def.path()
     }
 
-    fn drive<V: Visitor>(&self, v: &mut V) {
-        self.def.accept(v);
+    fn drive<V: Visitor>(&self, v: &mut V) -> ControlFlow<()> {
+        self.def.accept(v)?;
+
+        ControlFlow::Continue(())
     }
 }
@@ -1,9 +1,10 @@
 use crate::{
     node::{
-        Canister, Constant, Def, Entity, Enum, EnumHash, Error, Fixture, MacroNode, Map, Newtype,
-        Permission, Primitive, Record, Role, Sanitizer, Store, Tuple, ValidateNode, Validator,
-        VisitableNode,
+        Canister, Constant, Def, Entity, Enum, EnumHash, Error, Fixture, Item, MacroNode, Map,
+        Newtype, Permission, Primitive, Record, Role, Sanitizer, Store, Tuple, ValidateNode,
+        Validator, VisitableNode,
     },
+    types::{Cardinality, PrimitiveType},
     visit::Visitor,
 };
 use serde::{
@@ -14,6 +15,7 @@ use sha2::{Digest, Sha256};
 use std::{
     any::{Any, TypeId},
     collections::{BTreeMap, HashSet},
+    ops::ControlFlow,
 };
 use types::{ErrorVec, Timestamp};
 
@@ -43,6 +45,28 @@ pub enum SchemaNode {
 }
 
 impl SchemaNode {
+    // kind
+    const fn kind(&self) -> &'static str {
+        match self {
+            Self::Canister(_) => "Canister",
+            Self::Constant(_) => "Constant",
+            Self::Entity(_) => "Entity",
+            Self::Enum(_) => "Enum",
+            Self::EnumHash(_) => "EnumHash",
+            Self::Fixture(_) => "Fixture",
+            Self::Map(_) => "Map",
+            Self::Newtype(_) => "Newtype",
+            Self::Permission(_) => "Permission",
+            Self::Primitive(_) => "Primitive",
+            Self::Record(_) => "Record",
+            Self::Role(_) => "Role",
+            Self::Sanitizer(_) => "Sanitizer",
+            Self::Store(_) => "Store",
+            Self::Tuple(_) => "Tuple",
+            Self::Validator(_) => "Validator",
+        }
+    }
+
     const fn def(&self) -> &Def {
         match self {
             Self::Canister(n) => &n.def,
@@ -91,7 +115,7 @@ impl MacroNode for SchemaNode {
 impl ValidateNode for SchemaNode {}
 
 impl VisitableNode for SchemaNode {
-    fn drive<V: Visitor>(&self, v: &mut V) {
+    fn drive<V: Visitor>(&self, v: &mut V) -> ControlFlow<()> {
         match self {
             Self::Canister(n) => n.accept(v),
             Self::Constant(n) => n.accept(v),
@@ -122,6 +146,11 @@ pub struct Schema {
     pub nodes: BTreeMap<String, SchemaNode>,
     pub hash: String,
     pub timestamp: Timestamp,
+
+    // every node kind added under a path that was already taken,
+    // kept so validate can report the collision instead of losing a node
+    #[serde(skip)]
+    duplicates: BTreeMap<String, Vec<String>>,
 }
 
 impl Serialize for Schema {
@@ -161,12 +190,21 @@ impl Schema {
             nodes: BTreeMap::new(),
             hash: String::new(),
             timestamp: Timestamp::now(),
+            duplicates: BTreeMap::new(),
         }
     }
 
     // add_node
     pub fn add_node(&mut self, node: SchemaNode) {
-        self.nodes.insert(node.def().path(), node);
+        let path = node.def().path();
+        if let Some(existing) = self.nodes.get(&path) {
+            self.duplicates
+                .entry(path.clone())
+                .or_insert_with(|| vec![existing.kind().to_string()])
+                .push(node.kind().to_string());
+        }
+
+        self.nodes.insert(path, node);
     }
 
     // check_node
@@ -266,6 +304,38 @@ impl Schema {
             None
         }))
     }
+
+    // primitive_type
+    // the primitive a type path is stored as, following newtypes down to it
+    #[must_use]
+    pub fn primitive_type(&self, path: &str) -> Option<PrimitiveType> {
+        if let Some(node) = self.get_node::<Primitive>(path) {
+            return Some(node.ty);
+        }
+
+        let node = self.get_node::<Newtype>(path)?;
+        node.primitive
+            .or_else(|| match (&node.value.cardinality, &node.value.item) {
+                (Cardinality::One, Item::Is(item)) => self.primitive_type(&item.path),
+                _ => None,
+            })
+    }
+
+    // field_primitive_type
+    // for a field of an entity or record, relations are stored as the
+    // Ulid of the related entity
+    #[must_use]
+    pub fn field_primitive_type(&self, path: &str, field: &str) -> Option<PrimitiveType> {
+        let fields = self
+            .get_node::<Entity>(path)
+            .map(|node| &node.fields)
+            .or_else(|| self.get_node::<Record>(path).map(|node| &node.fields))?;
+
+        match &fields.get_field(field)?.value.item {
+            Item::Is(item) => self.primitive_type(&item.path),
+            Item::Relation(_) => Some(PrimitiveType::Ulid),
+        }
+    }
 }
 
 impl Default for Schema {
@@ -278,6 +348,11 @@ impl ValidateNode for Schema {
     fn validate(&self) -> Result<(), ErrorVec> {
         let mut errs = ErrorVec::new();
 
+        // two nodes resolving to the same path
+        for (path, nodes) in &self.duplicates {
+            errs.add(Error::duplicate_path(path, nodes).to_string());
+        }
+
         // duplicate fixtures for the same entity
         let mut set = HashSet::new();
         for fixture in self.get_node_values::<Fixture>() {
@@ -331,7 +406,7 @@ impl ValidateNode for Schema {
         for canister in self.get_node_values::<Canister>() {
             // Check for duplicate names
             if !dirs_seen.insert(canister.name().clone()) {
-                errs.push(format!(
+                errs.add(format!(
                     "Duplicate canister name found: {}",
                     canister.name()
                 ));
@@ -343,9 +418,64 @@ impl ValidateNode for Schema {
 }
 
 impl VisitableNode for Schema {
-    fn drive<V: Visitor>(&self, v: &mut V) {
+    fn drive<V: Visitor>(&self, v: &mut V) -> ControlFlow<()> {
         for node in self.nodes.values() {
-            node.accept(v);
+            node.accept(v)?;
         }
+
+        ControlFlow::Continue(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        node::FieldList,
+        test::{def, field, is, relation, value},
+        types::PrimitiveGroup,
+    };
+
+    fn newtype(ident: &str, path: &str, primitive: Option<PrimitiveType>) -> SchemaNode {
+        SchemaNode::Newtype(Newtype {
+            def: def(ident),
+            value: value(Cardinality::One, is(path)),
+            primitive,
+            guide: None,
+            sanitizers: Vec::new(),
+            validators: Vec::new(),
+        })
+    }
+
+    #[test]
+    fn test_field_primitive_type() {
+        let mut schema = Schema::new();
+        schema.add_node(SchemaNode::Primitive(Primitive {
+            def: def("U8"),
+            ty: PrimitiveType::U8,
+            path: "u8".to_string(),
+        }));
+        schema.add_node(newtype("Level", "test::U8", Some(PrimitiveType::U8)));
+        schema.add_node(newtype("Rank", "test::Level", None));
+
+        schema.add_node(SchemaNode::Record(Record {
+            def: def("Player"),
+            fields: FieldList {
+                fields: vec![
+                    field("rank", value(Cardinality::One, is("test::Rank"))),
+                    field("guild", value(Cardinality::One, relation("test::Guild"))),
+                ],
+                order: Vec::new(),
+            },
+        }));
+
+        let ty = schema.field_primitive_type("test::Player", "rank");
+        assert_eq!(ty, Some(PrimitiveType::U8));
+        assert_eq!(ty.map(|ty| ty.group()), Some(PrimitiveGroup::Integer));
+        assert_eq!(
+            schema.field_primitive_type("test::Player", "guild"),
+            Some(PrimitiveType::Ulid)
+        );
+        assert_eq!(schema.field_primitive_type("test::Player", "missing"), None);
     }
 }
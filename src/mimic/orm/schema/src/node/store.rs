@@ -6,7 +6,7 @@ use crate::{
 use lib_case::{Case, Casing};
 use quote::format_ident;
 use serde::{Deserialize, Serialize};
-use std::ops::Range;
+use std::ops::{ControlFlow, Range};
 use syn::Ident;
 use types::ErrorVec;
 
@@ -75,8 +75,10 @@ impl VisitableNode for Store {
         self.def.path()
     }
 
-    fn drive<V: Visitor>(&self, v: &mut V) {
-        self.def.accept(v);
-        self.crud.accept(v);
+    fn drive<V: Visitor>(&self, v: &mut V) -> ControlFlow<()> {
+        self.def.accept(v)?;
+        self.crud.accept(v)?;
+
+        ControlFlow::Continue(())
     }
 }
@@ -1,6 +1,6 @@
 use crate::{
     build::schema,
-    node::{Args, Sanitizer, ValidateNode, Validator, VisitableNode},
+    node::{Arg, ArgNumber, Args, Sanitizer, ValidateNode, Validator, VisitableNode},
 };
 use serde::{Deserialize, Serialize};
 use types::ErrorVec;
@@ -33,6 +33,15 @@ impl ValidateNode for TypeSanitizer {
 
 impl VisitableNode for TypeSanitizer {}
 
+///
+/// NUMBER_VALIDATOR_PATH
+/// the module the number validators are defined in, check_number only runs
+/// validators from here so a user type with the same name isn't mistaken
+/// for one
+///
+
+pub const NUMBER_VALIDATOR_PATH: &str = "mimic_base::validator::number";
+
 ///
 /// TypeValidator
 ///
@@ -45,6 +54,43 @@ pub struct TypeValidator {
     pub args: Args,
 }
 
+impl TypeValidator {
+    // check_number
+    // runs the number range validators against a schema value, any other
+    // validator, or one without number args, passes
+    pub fn check_number(&self, n: &ArgNumber) -> Result<(), String> {
+        let n = n.as_f64();
+        let args: Vec<f64> = self
+            .args
+            .iter()
+            .filter_map(|arg| match arg {
+                Arg::Number(arg) => Some(arg.as_f64()),
+                _ => None,
+            })
+            .collect();
+
+        let name = self
+            .path
+            .strip_prefix(NUMBER_VALIDATOR_PATH)
+            .and_then(|name| name.strip_prefix("::"))
+            .unwrap_or_default();
+        let ok = match (name, args.as_slice()) {
+            ("Lt", [than]) => n < *than,
+            ("Gt", [than]) => n > *than,
+            ("Ltoe", [than]) => n <= *than,
+            ("Gtoe", [than]) => n >= *than,
+            ("Range", [min, max]) => n >= *min && n <= *max,
+            _ => true,
+        };
+
+        if ok {
+            Ok(())
+        } else {
+            Err(format!("{n} fails validator {}", self.path))
+        }
+    }
+}
+
 impl ValidateNode for TypeValidator {
     fn validate(&self) -> Result<(), ErrorVec> {
         let mut errs = ErrorVec::new();
@@ -60,3 +106,41 @@ impl ValidateNode for TypeValidator {
 }
 
 impl VisitableNode for TypeValidator {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_number() {
+        let range = TypeValidator {
+            path: "mimic_base::validator::number::Range".to_string(),
+            args: Args(vec![
+                Arg::Number(ArgNumber::I32(1)),
+                Arg::Number(ArgNumber::I32(10)),
+            ]),
+        };
+        assert!(range.check_number(&ArgNumber::U8(5)).is_ok());
+        assert!(range.check_number(&ArgNumber::U8(11)).is_err());
+
+        let lt = TypeValidator {
+            path: "mimic_base::validator::number::Lt".to_string(),
+            args: Args(vec![Arg::Number(ArgNumber::U8(5))]),
+        };
+        assert!(lt.check_number(&ArgNumber::U8(5)).is_err());
+
+        // not a range validator
+        let other = TypeValidator {
+            path: "mimic_base::validator::number::MultipleOf".to_string(),
+            args: Args(vec![Arg::Number(ArgNumber::U8(10))]),
+        };
+        assert!(other.check_number(&ArgNumber::U8(5)).is_ok());
+
+        // same name outside the number module
+        let mine = TypeValidator {
+            path: "design::validator::Lt".to_string(),
+            args: Args(vec![Arg::Number(ArgNumber::U8(5))]),
+        };
+        assert!(mine.check_number(&ArgNumber::U8(5)).is_ok());
+    }
+}
@@ -4,6 +4,7 @@ use crate::{
     visit::Visitor,
 };
 use serde::{Deserialize, Serialize};
+use std::ops::ControlFlow;
 
 ///
 /// Value
@@ -30,10 +31,12 @@ impl VisitableNode for Value {
         .to_string()
     }
 
-    fn drive<V: Visitor>(&self, v: &mut V) {
-        self.item.accept(v);
+    fn drive<V: Visitor>(&self, v: &mut V) -> ControlFlow<()> {
+        self.item.accept(v)?;
         if let Some(node) = &self.default {
-            node.accept(v);
+            node.accept(v)?;
         }
+
+        ControlFlow::Continue(())
     }
 }
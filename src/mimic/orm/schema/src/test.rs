@@ -0,0 +1,71 @@
+use crate::{
+    node::{Def, EnumVariant, Field, Item, ItemIs, ItemRelation, Value},
+    types::Cardinality,
+};
+
+///
+/// Test Fixtures
+///
+/// helpers for building schema nodes by hand in unit tests, every node
+/// lives in the "test" module
+///
+
+// def
+#[must_use]
+pub fn def(ident: &str) -> Def {
+    Def {
+        module_path: "test".to_string(),
+        ident: ident.to_string(),
+        comments: String::new(),
+    }
+}
+
+// value
+#[must_use]
+pub fn value(cardinality: Cardinality, item: Item) -> Value {
+    Value {
+        cardinality,
+        item,
+        default: None,
+    }
+}
+
+// is
+#[must_use]
+pub fn is(path: &str) -> Item {
+    Item::Is(ItemIs {
+        path: path.to_string(),
+    })
+}
+
+// relation
+#[must_use]
+pub fn relation(path: &str) -> Item {
+    Item::Relation(ItemRelation {
+        path: path.to_string(),
+    })
+}
+
+// field
+#[must_use]
+pub fn field(name: &str, value: Value) -> Field {
+    Field {
+        name: name.to_string(),
+        value,
+        aliases: Vec::new(),
+        check_relation: false,
+        owned: false,
+    }
+}
+
+// variant
+#[must_use]
+pub fn variant(name: &str, value: Option<Value>) -> EnumVariant {
+    EnumVariant {
+        name: name.to_string(),
+        value,
+        discriminant: None,
+        default: false,
+        invalid: false,
+    }
+}
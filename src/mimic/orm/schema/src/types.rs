@@ -35,11 +35,47 @@ impl From<u128> for Cycles {
     }
 }
 
+///
+/// KeyStrategy
+///
+/// how an entity's primary key is set when it's created
+///
+/// ContentHash : derived from a hash of the rest of the entity
+/// Provided    : left as the caller set it
+/// Random      : the primary key type's own on_create, ie. a new Ulid
+/// Sequential  : the next value of a counter kept in the entity's store
+/// Ulid        : a new monotonic Ulid, so keys sort in creation order, for
+///               key types that can hold one like a u128
+///
+/// ContentHash and Sequential fill in a u64, so their key has to be a type
+/// that holds any u64, the entity fails schema validation otherwise
+///
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[remain::sorted]
+pub enum KeyStrategy {
+    ContentHash,
+    Provided,
+    #[default]
+    Random,
+    Sequential,
+    Ulid,
+}
+
+impl KeyStrategy {
+    // needs_single_key
+    // the derived strategies can only fill in one primary key field
+    #[must_use]
+    pub const fn needs_single_key(&self) -> bool {
+        matches!(self, Self::ContentHash | Self::Sequential)
+    }
+}
+
 ///
 /// PrimitiveType
 ///
 
-#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
 #[remain::sorted]
 pub enum PrimitiveType {
     Blob,
@@ -63,11 +99,37 @@ pub enum PrimitiveType {
     Ulid,
 }
 
+impl PrimitiveType {
+    // group
+    // what generic tooling renders and parses the primitive as
+    #[must_use]
+    pub const fn group(&self) -> PrimitiveGroup {
+        match self {
+            Self::Blob => PrimitiveGroup::Blob,
+            Self::Bool => PrimitiveGroup::Bool,
+            Self::F32 | Self::F64 => PrimitiveGroup::Float,
+            Self::Timestamp
+            | Self::I8
+            | Self::I16
+            | Self::I32
+            | Self::I64
+            | Self::I128
+            | Self::U8
+            | Self::U16
+            | Self::U32
+            | Self::U64
+            | Self::U128 => PrimitiveGroup::Integer,
+            Self::String | Self::Ulid | Self::Principal => PrimitiveGroup::String,
+            Self::Decimal => PrimitiveGroup::Decimal,
+        }
+    }
+}
+
 ///
 /// PrimitiveGroup
 ///
 
-#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
 #[remain::sorted]
 pub enum PrimitiveGroup {
     Blob,
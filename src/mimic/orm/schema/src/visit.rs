@@ -1,4 +1,5 @@
 use crate::node::VisitableNode;
+use std::ops::ControlFlow;
 use types::ErrorTree;
 
 ///
@@ -15,6 +16,7 @@ pub enum Event {
 
 ///
 /// Visitor
+/// return ControlFlow::Break from visit to stop walking the tree
 ///
 
 pub trait Visitor {
@@ -22,7 +24,9 @@ pub trait Visitor {
     fn name(&self) -> &'static str;
 
     // visit
-    fn visit<V: VisitableNode + ?Sized>(&mut self, _: &V, _: Event) {}
+    fn visit<V: VisitableNode + ?Sized>(&mut self, _: &V, _: Event) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
 
     // key
     fn push(&mut self, _: &str) {}
@@ -66,7 +70,7 @@ impl Visitor for Validator {
         "Validator"
     }
 
-    fn visit<T: VisitableNode + ?Sized>(&mut self, node: &T, event: Event) {
+    fn visit<T: VisitableNode + ?Sized>(&mut self, node: &T, event: Event) -> ControlFlow<()> {
         match event {
             Event::Enter => {
                 self.node_count += 1;
@@ -83,13 +87,15 @@ impl Visitor for Validator {
                                 .collect::<Vec<String>>()
                                 .join(" -> ");
 
-                            self.errors.set_list(route, &errs);
+                            self.errors.set_list(route, &errs.messages());
                         }
                     }
                 }
             }
             Event::Exit => {}
         }
+
+        ControlFlow::Continue(())
     }
 
     fn push(&mut self, s: &str) {
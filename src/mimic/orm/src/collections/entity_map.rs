@@ -0,0 +1,93 @@
+use crate::{traits::Entity, Error};
+use ic::structures::{memory::VirtualMemory, BTreeMap, Storable};
+use std::marker::PhantomData;
+
+///
+/// EntityMap
+///
+/// a stable BTreeMap of one entity type, the entities are stored as their
+/// serialized bytes so this is for lookup tables that sit outside the db
+/// stores and don't need indexes or queries
+///
+
+pub struct EntityMap<K, E>
+where
+    K: Storable + Ord + Clone,
+    E: Entity,
+{
+    data: BTreeMap<K, Vec<u8>>,
+    _entity: PhantomData<E>,
+}
+
+impl<K, E> EntityMap<K, E>
+where
+    K: Storable + Ord + Clone,
+    E: Entity,
+{
+    // init
+    #[must_use]
+    pub fn init(memory: VirtualMemory) -> Self {
+        Self {
+            data: BTreeMap::init(memory),
+            _entity: PhantomData,
+        }
+    }
+
+    // get
+    pub fn get(&self, key: &K) -> Result<Option<E>, Error> {
+        self.data
+            .get(key)
+            .map(|bytes| crate::deserialize::<E>(&bytes))
+            .transpose()
+    }
+
+    // insert
+    // returns the entity previously stored under the key, that's decoded and
+    // the new one checked against the bound before anything is written
+    pub fn insert(&mut self, key: K, entity: &E) -> Result<Option<E>, Error> {
+        let bytes = entity.serialize_dyn()?;
+        let old = self.get(&key)?;
+        self.data.try_insert(key, bytes)?;
+
+        Ok(old)
+    }
+
+    // remove
+    pub fn remove(&mut self, key: &K) -> Result<Option<E>, Error> {
+        self.data
+            .remove(key)
+            .map(|bytes| crate::deserialize::<E>(&bytes))
+            .transpose()
+    }
+
+    // contains_key
+    #[must_use]
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.data.contains_key(key)
+    }
+
+    // iter
+    // each entity is decoded as it's reached, a bad row doesn't stop the rest
+    pub fn iter(&self) -> impl Iterator<Item = (K, Result<E, Error>)> + '_ {
+        self.data
+            .iter()
+            .map(|(key, bytes)| (key, crate::deserialize::<E>(&bytes)))
+    }
+
+    // len
+    #[must_use]
+    pub fn len(&self) -> u64 {
+        self.data.len()
+    }
+
+    // is_empty
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    // clear
+    pub fn clear(&mut self) {
+        self.data.clear();
+    }
+}
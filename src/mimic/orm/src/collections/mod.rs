@@ -1,5 +1,7 @@
+pub mod entity_map;
 pub mod hashmap;
 pub mod hashset;
 
+pub use entity_map::EntityMap;
 pub use hashmap::HashMap;
 pub use hashset::HashSet;
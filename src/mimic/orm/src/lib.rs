@@ -1,13 +1,22 @@
 pub mod collections;
+pub mod patch;
 pub mod traits;
 pub mod types;
 pub mod visit;
 
-use ::types::ErrorTree;
+pub use lib_cbor::Value;
+pub use serde_json::Value as JsonValue;
+
+#[cfg(feature = "test")]
+pub use proptest;
+
+use ::types::FieldErrors;
 use candid::CandidType;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use snafu::Snafu;
-use traits::Visitable;
+use std::{collections::HashMap, fmt::Debug};
+use traits::{Entity, EntityDynamic, Visitable};
 use visit::{perform_visit, perform_visit_mut, SanitizeVisitor, ValidateVisitor};
 
 ///
@@ -16,20 +25,39 @@ use visit::{perform_visit, perform_visit_mut, SanitizeVisitor, ValidateVisitor};
 
 #[derive(CandidType, Debug, Serialize, Deserialize, Snafu)]
 pub enum Error {
+    #[snafu(display("failed to deserialize as {type_name}: {source}"))]
+    Deserialize {
+        type_name: String,
+        source: lib_cbor::Error,
+    },
+
     #[snafu(display("invalid enum hash '{key}'"))]
     InvalidEnumHash { key: u64 },
 
+    #[snafu(display("json error: {msg}"))]
+    Json { msg: String },
+
     #[snafu(display("cannot parse field '{field}'"))]
     ParseField { field: String },
 
     #[snafu(display("validation failed: {errors}"))]
-    Validation { errors: ErrorTree },
+    Validation { errors: FieldErrors },
 
     #[snafu(transparent)]
     Cbor { source: lib_cbor::Error },
+
+    #[snafu(transparent)]
+    Ic { source: ic::Error },
 }
 
 impl Error {
+    #[must_use]
+    pub fn json(err: &serde_json::Error) -> Self {
+        Self::Json {
+            msg: err.to_string(),
+        }
+    }
+
     #[must_use]
     pub fn parse_field(field: &str) -> Self {
         Self::ParseField {
@@ -56,23 +84,133 @@ pub fn deserialize<T>(bytes: &[u8]) -> Result<T, Error>
 where
     T: DeserializeOwned,
 {
-    lib_cbor::deserialize::<T>(bytes).map_err(Error::from)
+    lib_cbor::deserialize::<T>(bytes).map_err(|source| Error::Deserialize {
+        type_name: std::any::type_name::<T>().to_string(),
+        source,
+    })
+}
+
+// to_json
+pub fn to_json<T>(ty: &T) -> Result<JsonValue, Error>
+where
+    T: Serialize,
+{
+    serde_json::to_value(ty).map_err(|e| Error::json(&e))
+}
+
+// deserialize_fields
+// a projection of the serialized type, only the requested fields are decoded
+pub fn deserialize_fields(bytes: &[u8], fields: &[&str]) -> Result<HashMap<String, Value>, Error> {
+    lib_cbor::deserialize_fields(bytes, fields).map_err(Error::from)
+}
+
+// field_slices
+// the raw encoded bytes of the requested fields, without decoding them
+pub fn field_slices<'a>(
+    bytes: &'a [u8],
+    fields: &[&str],
+) -> Result<HashMap<String, &'a [u8]>, Error> {
+    lib_cbor::field_slices(bytes, fields).map_err(Error::from)
+}
+
+// entity_to_json
+// json goes through the same serde derives as the stored CBOR, only Blob
+// and Timestamp change form, into base64 and rfc3339 strings, a timestamp
+// past the year 9999 has no rfc3339 form so it's an error
+pub fn entity_to_json(entity: &dyn EntityDynamic) -> Result<JsonValue, Error> {
+    ::types::timestamp::with_rfc3339(|| entity.to_json_dyn())
+}
+
+// entity_from_json
+// the entity is decoded but not validated, that happens when it's saved
+pub fn entity_from_json<E: Entity>(value: JsonValue) -> Result<E, Error> {
+    serde_json::from_value(value).map_err(|e| Error::json(&e))
+}
+
+// content_seed
+// the leading bytes of a sha256 of the data, seeds ContentHash keys
+#[must_use]
+pub fn content_seed(bytes: &[u8]) -> u64 {
+    let hash = Sha256::digest(bytes);
+    let mut seed = [0u8; 8];
+    seed.copy_from_slice(&hash[..8]);
+
+    u64::from_be_bytes(seed)
 }
 
 // sanitize
+// a node is sanitized before its children, so an outer newtype's
+// sanitizers run before those of the type it wraps
 pub fn sanitize(node: &mut dyn Visitable) {
     let mut visitor = SanitizeVisitor::new();
 
-    perform_visit_mut(&mut visitor, node, "");
+    let _ = perform_visit_mut(&mut visitor, node, "");
 }
 
 // validate
 pub fn validate(node: &dyn Visitable) -> Result<(), Error> {
+    validate_fields(node).map_err(|errors| Error::Validation { errors })
+}
+
+// validate_fields
+// every validator runs, so the errors cover all the fields that failed
+// and name the validator that rejected each one
+pub fn validate_fields(node: &dyn Visitable) -> Result<(), FieldErrors> {
     let mut visitor = ValidateVisitor::new();
-    perform_visit(&mut visitor, node, "");
+    let _ = perform_visit(&mut visitor, node, "");
+
+    visitor.errors.result()
+}
+
+///
+/// TEST HELPERS
+///
+
+// assert_roundtrip
+// serializes and deserializes the value, which should come back unchanged
+//
+// # Panics
+//
+// if either step fails or the values are not equal
+//
+pub fn assert_roundtrip<T>(value: T)
+where
+    T: Serialize + DeserializeOwned + PartialEq + Debug,
+{
+    let bytes = serialize(&value).expect("serialize");
+    let decoded: T = deserialize(&bytes).expect("deserialize");
+
+    assert_eq!(value, decoded, "value changed after a serialize roundtrip");
+}
+
+//
+// roundtrip_test!
+// registers a test that calls assert_roundtrip on the value
+//
+#[macro_export]
+macro_rules! roundtrip_test {
+    ($name:ident, $value:expr) => {
+        #[test]
+        fn $name() {
+            $crate::assert_roundtrip($value);
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::types::Blob;
+
+    #[test]
+    fn test_deserialize_error_type_name() {
+        let err = deserialize::<Blob>(&[0xff]).unwrap_err();
+
+        assert!(err
+            .to_string()
+            .starts_with("failed to deserialize as types::blob::Blob: "));
+    }
 
-    visitor
-        .errors
-        .result()
-        .map_err(|errors| Error::Validation { errors })
+    roundtrip_test!(test_roundtrip_blob, Blob::from(vec![0, 1, 2, 255]));
+    roundtrip_test!(test_roundtrip_blob_empty, Blob::default());
 }
@@ -0,0 +1,200 @@
+use crate::{
+    traits::{Sanitize, SanitizeAuto, Validate, ValidateAuto, Visitable},
+    visit::{perform_visit, perform_visit_mut, Visitor},
+};
+use candid::CandidType;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::ops::ControlFlow;
+
+///
+/// Patch
+///
+/// an optional field in a partial update, Unset leaves the stored value
+/// alone, Null clears it and Set replaces it
+///
+/// Unset and Null both encode as null, so a struct holding a Patch needs
+/// #[serde(default, skip_serializing_if = "Patch::is_unset")] on the field
+/// for a missing key to come back as Unset
+///
+
+#[derive(CandidType, Clone, Debug, Default, Eq, PartialEq)]
+pub enum Patch<T> {
+    #[default]
+    Unset,
+    Null,
+    Set(T),
+}
+
+impl<T> Patch<T> {
+    #[must_use]
+    pub const fn is_unset(&self) -> bool {
+        matches!(self, Self::Unset)
+    }
+
+    #[must_use]
+    pub const fn state(&self) -> PatchState {
+        match self {
+            Self::Unset => PatchState::Unset,
+            Self::Null => PatchState::Null,
+            Self::Set(_) => PatchState::Set,
+        }
+    }
+
+    // apply
+    // writes the patch over an Opt field
+    pub fn apply(self, target: &mut Option<T>) {
+        match self {
+            Self::Unset => {}
+            Self::Null => *target = None,
+            Self::Set(value) => *target = Some(value),
+        }
+    }
+}
+
+impl<T> From<Option<Option<T>>> for Patch<T> {
+    fn from(value: Option<Option<T>>) -> Self {
+        match value {
+            None => Self::Unset,
+            Some(None) => Self::Null,
+            Some(Some(value)) => Self::Set(value),
+        }
+    }
+}
+
+impl<T> From<Patch<T>> for Option<Option<T>> {
+    fn from(patch: Patch<T>) -> Self {
+        match patch {
+            Patch::Unset => None,
+            Patch::Null => Some(None),
+            Patch::Set(value) => Some(Some(value)),
+        }
+    }
+}
+
+impl<T: Serialize> Serialize for Patch<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Unset | Self::Null => serializer.serialize_none(),
+            Self::Set(value) => serializer.serialize_some(value),
+        }
+    }
+}
+
+// a present key is never Unset, that only comes from serde(default)
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Patch<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Option::<T>::deserialize(deserializer).map(|value| value.map_or(Self::Null, Self::Set))
+    }
+}
+
+impl<T: Visitable> Visitable for Patch<T> {
+    fn drive(&self, visitor: &mut dyn Visitor) -> ControlFlow<()> {
+        visitor.visit_patch(self.state());
+        match self {
+            Self::Set(value) => perform_visit(visitor, value, ""),
+            Self::Unset | Self::Null => ControlFlow::Continue(()),
+        }
+    }
+
+    fn drive_mut(&mut self, visitor: &mut dyn Visitor) -> ControlFlow<()> {
+        visitor.visit_patch(self.state());
+        match self {
+            Self::Set(value) => perform_visit_mut(visitor, value, ""),
+            Self::Unset | Self::Null => ControlFlow::Continue(()),
+        }
+    }
+}
+
+impl<T> Sanitize for Patch<T> {}
+impl<T> SanitizeAuto for Patch<T> {}
+impl<T> Validate for Patch<T> {}
+impl<T> ValidateAuto for Patch<T> {}
+
+///
+/// PatchState
+///
+/// what a visitor is told about each Patch it walks through
+///
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PatchState {
+    Unset,
+    Null,
+    Set,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::visit::Event;
+
+    #[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+    struct NamePatch {
+        #[serde(default, skip_serializing_if = "Patch::is_unset")]
+        name: Patch<String>,
+    }
+
+    #[derive(Default)]
+    struct StateVisitor {
+        states: Vec<(String, PatchState)>,
+        path: Vec<String>,
+    }
+
+    impl Visitor for StateVisitor {
+        fn visit(&mut self, _: &dyn Visitable, _: Event) -> ControlFlow<()> {
+            ControlFlow::Continue(())
+        }
+
+        fn visit_mut(&mut self, _: &mut dyn Visitable, _: Event) -> ControlFlow<()> {
+            ControlFlow::Continue(())
+        }
+
+        fn visit_patch(&mut self, state: PatchState) {
+            self.states.push((self.path.join("."), state));
+        }
+
+        fn push(&mut self, s: &str) {
+            self.path.push(s.to_string());
+        }
+
+        fn pop(&mut self) {
+            self.path.pop();
+        }
+    }
+
+    #[test]
+    fn test_patch_roundtrip() {
+        for name in [Patch::Unset, Patch::Null, Patch::Set("bob".to_string())] {
+            crate::assert_roundtrip(NamePatch { name });
+        }
+    }
+
+    #[test]
+    fn test_patch_apply() {
+        let mut target = Some("alice".to_string());
+
+        Patch::Unset.apply(&mut target);
+        assert_eq!(target.as_deref(), Some("alice"));
+
+        Patch::Set("bob".to_string()).apply(&mut target);
+        assert_eq!(target.as_deref(), Some("bob"));
+
+        Patch::Null.apply(&mut target);
+        assert_eq!(target, None);
+    }
+
+    #[test]
+    fn test_patch_visit() {
+        let mut visitor = StateVisitor::default();
+        for patch in [Patch::Unset, Patch::Null, Patch::Set("bob".to_string())] {
+            let _ = perform_visit(&mut visitor, &patch, "name");
+        }
+
+        let states: Vec<PatchState> = visitor.states.iter().map(|(_, state)| *state).collect();
+        assert_eq!(
+            states,
+            vec![PatchState::Unset, PatchState::Null, PatchState::Set]
+        );
+        assert!(visitor.states.iter().all(|(path, _)| path == "name"));
+    }
+}
@@ -3,10 +3,12 @@
 pub use candid::CandidType;
 pub use ic::structures::storable::Storable;
 pub use num_traits::{FromPrimitive as NumFromPrimitive, NumCast, ToPrimitive as NumToPrimitive};
+#[cfg(feature = "test")]
+pub use proptest::arbitrary::Arbitrary;
 pub use serde::{de::DeserializeOwned, Deserialize, Serialize};
 pub use std::{
     cmp::Ordering,
-    convert::{AsRef, From},
+    convert::{AsRef, From, TryFrom},
     default::Default,
     fmt::{Debug, Display},
     hash::Hash,
@@ -15,8 +17,13 @@ pub use std::{
     str::FromStr,
 };
 
-use crate::{types::SortDirection, visit::Visitor, Error};
-use ::types::ErrorVec;
+use crate::{
+    types::{FieldPredicate, KeyStrategy, SortDirection},
+    visit::Visitor,
+    Error,
+};
+use ::types::{ErrorVec, FieldErrors};
+use std::ops::ControlFlow;
 
 ///
 /// MACROS
@@ -88,6 +95,8 @@ impl_primitive_filterable!(i8, i16, i32, i64, i128, u8, u16, u32, u64, u128, f32
 
 pub trait Inner<T> {
     fn inner(&self) -> &T;
+
+    fn inner_mut(&mut self) -> &mut T;
 }
 
 // impl_primitive_inner
@@ -99,6 +108,10 @@ macro_rules! impl_primitive_inner {
                 fn inner(&self) -> &$type {
                     &self
                 }
+
+                fn inner_mut(&mut self) -> &mut $type {
+                    self
+                }
             }
         )*
     };
@@ -106,14 +119,111 @@ macro_rules! impl_primitive_inner {
 
 impl_primitive_inner!(bool, f32, f64, i8, i16, i32, i64, i128, String, u8, u16, u32, u64, u128);
 
+///
+/// NumChecked
+///
+/// overflow-aware arithmetic for the numeric newtypes, derive_more's Add, Sub
+/// and Mul wrap on overflow in release builds so these are for values where
+/// that would be a bug, each newtype delegates down to its primitive
+///
+
+pub trait NumChecked: Sized {
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn checked_sub(self, other: Self) -> Option<Self>;
+    fn checked_mul(self, other: Self) -> Option<Self>;
+    fn saturating_add(self, other: Self) -> Self;
+    fn saturating_sub(self, other: Self) -> Self;
+    fn saturating_mul(self, other: Self) -> Self;
+}
+
+// impl_primitive_num_checked
+// Self:: finds the inherent methods before the trait ones
+#[macro_export]
+macro_rules! impl_primitive_num_checked {
+    ($($type:ty),*) => {
+        $(
+            impl NumChecked for $type {
+                fn checked_add(self, other: Self) -> Option<Self> {
+                    Self::checked_add(self, other)
+                }
+
+                fn checked_sub(self, other: Self) -> Option<Self> {
+                    Self::checked_sub(self, other)
+                }
+
+                fn checked_mul(self, other: Self) -> Option<Self> {
+                    Self::checked_mul(self, other)
+                }
+
+                fn saturating_add(self, other: Self) -> Self {
+                    Self::saturating_add(self, other)
+                }
+
+                fn saturating_sub(self, other: Self) -> Self {
+                    Self::saturating_sub(self, other)
+                }
+
+                fn saturating_mul(self, other: Self) -> Self {
+                    Self::saturating_mul(self, other)
+                }
+            }
+        )*
+    };
+}
+
+impl_primitive_num_checked!(
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    ::types::Decimal
+);
+
+impl NumChecked for ::types::Timestamp {
+    fn checked_add(self, other: Self) -> Option<Self> {
+        (*self).checked_add(*other).map(Self::from)
+    }
+
+    fn checked_sub(self, other: Self) -> Option<Self> {
+        (*self).checked_sub(*other).map(Self::from)
+    }
+
+    fn checked_mul(self, other: Self) -> Option<Self> {
+        (*self).checked_mul(*other).map(Self::from)
+    }
+
+    fn saturating_add(self, other: Self) -> Self {
+        (*self).saturating_add(*other).into()
+    }
+
+    fn saturating_sub(self, other: Self) -> Self {
+        (*self).saturating_sub(*other).into()
+    }
+
+    fn saturating_mul(self, other: Self) -> Self {
+        (*self).saturating_mul(*other).into()
+    }
+}
+
 ///
 /// Orderable
 ///
 /// wrapper around the Ord/PartialOrd traits so that we can extend it to
 /// more ORM types
 ///
+/// ORDERABLE is false for types that keep the default cmp, which says
+/// everything is equal, so range filters know not to trust it
+///
 
 pub trait Orderable {
+    const ORDERABLE: bool = false;
+
     fn cmp(&self, _other: &Self) -> std::cmp::Ordering {
         std::cmp::Ordering::Equal
     }
@@ -128,6 +238,8 @@ macro_rules! impl_primitive_order {
     ($($type:ty),*) => {
         $(
             impl Orderable for $type {
+                const ORDERABLE: bool = true;
+
                 fn cmp(&self, other: &Self) -> std::cmp::Ordering {
                     std::cmp::Ord::cmp(self, other)
                 }
@@ -139,6 +251,8 @@ macro_rules! impl_primitive_order {
 impl_primitive_order!(bool, i8, i16, i32, i64, i128, String, u8, u16, u32, u64, u128);
 
 impl<T: Orderable> Orderable for Option<T> {
+    const ORDERABLE: bool = T::ORDERABLE;
+
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
         match (self, other) {
             // Both are None, they are equal
@@ -198,6 +312,10 @@ impl_primitive_list!(
 ///
 /// Sanitize
 ///
+/// the hand written sanitize_manual runs first, then sanitize_auto which
+/// applies a newtype's declared sanitizers in the order they're declared,
+/// each one working on the output of the one before
+///
 
 pub trait Sanitize: SanitizeAuto {
     fn sanitize(&mut self) {
@@ -239,6 +357,13 @@ pub trait Validate: ValidateAuto {
     fn validate_manual(&self) -> Result<(), ErrorVec> {
         Ok(())
     }
+
+    // validate_after
+    // runs once the node's children have been validated, for checks that
+    // span them, entities use it to call validate_entity
+    fn validate_after(&self) -> Result<(), FieldErrors> {
+        Ok(())
+    }
 }
 
 impl_primitive!(Validate);
@@ -263,8 +388,13 @@ impl_primitive!(ValidateAuto);
 ///
 
 pub trait Visitable: Validate + Sanitize {
-    fn drive(&self, _: &mut dyn Visitor) {}
-    fn drive_mut(&mut self, _: &mut dyn Visitor) {}
+    fn drive(&self, _: &mut dyn Visitor) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    fn drive_mut(&mut self, _: &mut dyn Visitor) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
 }
 
 impl_primitive!(Visitable);
@@ -287,6 +417,21 @@ pub trait PrimaryKey: FromStr {
     // format_key
     // how is this type formatted within a sort key string
     fn format(&self) -> String;
+
+    // from_seed
+    // the key for a sequence number or content hash, None if this type
+    // can't be built from one
+    #[must_use]
+    fn from_seed(_seed: u64) -> Option<Self> {
+        None
+    }
+
+    // from_ulid
+    // the key for the Ulid key strategy, None if this type can't hold one
+    #[must_use]
+    fn from_ulid(_ulid: ::types::Ulid) -> Option<Self> {
+        None
+    }
 }
 
 macro_rules! impl_primary_key_for_ints {
@@ -297,6 +442,10 @@ macro_rules! impl_primary_key_for_ints {
                     *self
                 }
 
+                fn from_seed(seed: u64) -> Option<Self> {
+                    <$t>::try_from(seed).ok()
+                }
+
                 #[allow(clippy::cast_sign_loss)]
                 fn format(&self) -> String {
                     if *self < 0 {
@@ -322,6 +471,10 @@ macro_rules! impl_primary_key_for_uints {
                 fn format(&self) -> String {
                     format!("{:0>width$}", self, width = $len)
                 }
+
+                fn from_seed(seed: u64) -> Option<Self> {
+                    <$t>::try_from(seed).ok()
+                }
             }
         )*
     };
@@ -342,9 +495,27 @@ impl_primary_key_for_uints!(
     u16, 5,
     u32, 10,
     u64, 20,
-    u128, 40
 );
 
+// ulids are a u128 with the timestamp in the high bits, so they keep their order
+impl PrimaryKey for u128 {
+    fn on_create(&self) -> Self {
+        *self
+    }
+
+    fn format(&self) -> String {
+        format!("{:0>width$}", self, width = 40)
+    }
+
+    fn from_seed(seed: u64) -> Option<Self> {
+        Some(seed.into())
+    }
+
+    fn from_ulid(ulid: ::types::Ulid) -> Option<Self> {
+        Some(ulid.0)
+    }
+}
+
 ///
 /// NODE TRAITS
 ///
@@ -384,6 +555,16 @@ pub trait EntityDynamic: Debug + Visitable {
     // modifies the entity's record in-place before saving it to the database
     fn on_create(&mut self) {}
 
+    // generate_ids
+    // sets the primary keys for the entity's key strategy after on_create,
+    // a save with preserve_ids skips it and keeps the keys it was given
+    fn generate_ids(&mut self) {}
+
+    // on_update
+    // the Update and Replace counterpart of on_create, it runs before the
+    // entity is sanitized and validated so derived fields are checked too
+    fn on_update(&mut self) {}
+
     // composite_key_dyn
     // returns the record's composite key (parent keys + primary key) as a Vec<String>
     fn composite_key_dyn(&self) -> Vec<String>;
@@ -391,8 +572,40 @@ pub trait EntityDynamic: Debug + Visitable {
     // path_dyn
     fn path_dyn(&self) -> String;
 
+    // key_strategy_dyn
+    fn key_strategy_dyn(&self) -> KeyStrategy {
+        KeyStrategy::Random
+    }
+
+    // set_sequence_dyn
+    // the save executor passes in the next value of the store's counter
+    // for Sequential entities, as generate_ids has no access to the store
+    fn set_sequence_dyn(&mut self, _seq: u64) {}
+
     // serialize_dyn
     fn serialize_dyn(&self) -> Result<Vec<u8>, Error>;
+
+    // to_json_dyn
+    fn to_json_dyn(&self) -> Result<crate::JsonValue, Error>;
+
+    // validate_entity
+    // cross-field invariants, run after every field has been validated,
+    // the entity's check functions are generated into it
+    fn validate_entity(&self) -> Result<(), FieldErrors> {
+        Ok(())
+    }
+
+    // sanitize_self
+    // runs the same sanitize pass as a save, without the save
+    fn sanitize_self(&mut self) {
+        crate::sanitize(&mut crate::visit::EntityAdapterMut(self));
+    }
+
+    // validate_self
+    // runs the same validate pass as a save, so input can be rejected early
+    fn validate_self(&self) -> Result<(), Error> {
+        crate::validate(&crate::visit::EntityAdapter(self))
+    }
 }
 
 ///
@@ -435,6 +648,44 @@ pub trait EnumHash: Sized {
 
 pub trait FieldFilter {
     fn fields_contain_text(&self, fields: Option<&[String]>, text: &str) -> bool;
+
+    // field_matches
+    // false if the field doesn't exist or is a collection
+    fn field_matches(&self, field: &str, predicate: &FieldPredicate) -> bool;
+}
+
+// field_matches
+// applies a FieldPredicate to a single field value, serialized predicate
+// values that don't deserialize into the field's type never match, and
+// neither does a range on a type that can't be ordered
+pub fn field_matches<T>(value: &T, predicate: &FieldPredicate) -> bool
+where
+    T: Filterable + Orderable + PartialEq + DeserializeOwned,
+{
+    let decode = |bytes: &[u8]| crate::deserialize::<T>(bytes).ok();
+
+    match predicate {
+        FieldPredicate::Eq(bytes) => decode(bytes).is_some_and(|other| *value == other),
+        FieldPredicate::Contains(text) => value.contains_text(text),
+        FieldPredicate::StartsWith(text) => value
+            .as_text()
+            .is_some_and(|s| s.to_lowercase().starts_with(&text.to_lowercase())),
+        FieldPredicate::Range { .. } if !T::ORDERABLE => false,
+        FieldPredicate::Range { min, max } => {
+            let above_min = match min {
+                Some(bytes) => decode(bytes).is_some_and(|min| value.cmp(&min) != Ordering::Less),
+                None => true,
+            };
+            let below_max = match max {
+                Some(bytes) => {
+                    decode(bytes).is_some_and(|max| value.cmp(&max) != Ordering::Greater)
+                }
+                None => true,
+            };
+
+            above_min && below_max
+        }
+    }
 }
 
 ///
@@ -463,3 +714,48 @@ pub trait FieldSort {
 
     fn generate_sorter(order: &[(String, SortDirection)]) -> Box<FieldSortFn<Self>>;
 }
+
+//
+// TESTS
+//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_field_matches_text() {
+        let value = "Hello World".to_string();
+
+        assert!(field_matches(&value, &FieldPredicate::contains("world")));
+        assert!(field_matches(&value, &FieldPredicate::starts_with("hello")));
+        assert!(!field_matches(&value, &FieldPredicate::starts_with("world")));
+        assert!(field_matches(&value, &FieldPredicate::eq(&value).unwrap()));
+    }
+
+    #[test]
+    fn test_field_matches_range() {
+        let range = FieldPredicate::range(Some(&10_u32), Some(&20_u32)).unwrap();
+
+        assert!(field_matches(&10_u32, &range));
+        assert!(field_matches(&20_u32, &range));
+        assert!(!field_matches(&21_u32, &range));
+
+        let open = FieldPredicate::range(None, Some(&5_u32)).unwrap();
+        assert!(field_matches(&0_u32, &open));
+        assert!(!field_matches(&6_u32, &open));
+    }
+
+    #[test]
+    fn test_field_matches_unordered() {
+        // f64 keeps the default cmp, so equality can't go through it
+        let eq = FieldPredicate::eq(&1.5_f64).unwrap();
+        assert!(field_matches(&1.5_f64, &eq));
+        assert!(!field_matches(&2.5_f64, &eq));
+
+        // and a range on it never matches rather than matching everything
+        let range = FieldPredicate::range(Some(&1.0_f64), Some(&2.0_f64)).unwrap();
+        assert!(!field_matches(&1.5_f64, &range));
+        assert!(!field_matches(&9.0_f64, &range));
+    }
+}
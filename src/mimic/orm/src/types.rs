@@ -154,6 +154,86 @@ fn parse_cycles(value: &str) -> darling::Result<u128> {
     Ok((number * multiplier) as u128)
 }
 
+///
+/// FieldPredicate
+///
+/// a predicate on a single entity field, used to scan and filter rows
+///
+/// Eq / Range : values are serialized so they can be compared as the
+///              field's own type using Orderable
+/// Contains / StartsWith : compare against the Filterable text
+///
+
+#[derive(CandidType, Clone, Debug, Deserialize, Serialize)]
+pub enum FieldPredicate {
+    Eq(Vec<u8>),
+    Contains(String),
+    StartsWith(String),
+    Range {
+        min: Option<Vec<u8>>,
+        max: Option<Vec<u8>>,
+    },
+}
+
+impl FieldPredicate {
+    // eq
+    pub fn eq<T: Serialize>(value: &T) -> Result<Self, crate::Error> {
+        Ok(Self::Eq(crate::serialize(value)?))
+    }
+
+    // contains
+    #[must_use]
+    pub fn contains(text: &str) -> Self {
+        Self::Contains(text.to_string())
+    }
+
+    // starts_with
+    #[must_use]
+    pub fn starts_with(text: &str) -> Self {
+        Self::StartsWith(text.to_string())
+    }
+
+    // range
+    // both bounds are inclusive, None is unbounded
+    pub fn range<T: Serialize>(min: Option<&T>, max: Option<&T>) -> Result<Self, crate::Error> {
+        Ok(Self::Range {
+            min: min.map(crate::serialize).transpose()?,
+            max: max.map(crate::serialize).transpose()?,
+        })
+    }
+}
+
+///
+/// KeyStrategy
+///
+
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, Display, EnumString, Eq, PartialEq, Serialize,
+)]
+#[remain::sorted]
+pub enum KeyStrategy {
+    ContentHash,
+    Provided,
+    #[default]
+    Random,
+    Sequential,
+    Ulid,
+}
+
+impl FromMeta for KeyStrategy {
+    fn from_string(s: &str) -> Result<Self, darling::Error> {
+        s.parse().map_err(|_| darling::Error::unknown_value(s))
+    }
+}
+
+impl Schemable for KeyStrategy {
+    fn schema(&self) -> TokenStream {
+        let ident = format_ident!("{}", self.to_string());
+
+        quote!(::mimic::orm::schema::types::KeyStrategy::#ident)
+    }
+}
+
 ///
 /// PrimitiveType
 ///
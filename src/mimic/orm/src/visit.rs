@@ -1,5 +1,9 @@
-use crate::traits::{EntityDynamic, Sanitize, SanitizeAuto, Validate, ValidateAuto, Visitable};
-use types::ErrorTree;
+use crate::{
+    patch::PatchState,
+    traits::{EntityDynamic, Sanitize, SanitizeAuto, Validate, ValidateAuto, Visitable},
+};
+use std::ops::ControlFlow;
+use types::FieldErrors;
 
 ///
 /// Event
@@ -16,11 +20,20 @@ pub enum Event {
 ///
 /// plus helper functions that allow navigation of the tree in an object-safe way
 ///
+/// returning ControlFlow::Break from a visit stops the walk, the path
+/// is still unwound but no further nodes are visited
+///
+/// visit_patch is called for every Patch on the way down, so a visitor
+/// can tell a field left unchanged from one being cleared
+///
 
 pub trait Visitor {
     // nodes
-    fn visit(&mut self, item: &dyn Visitable, event: Event);
-    fn visit_mut(&mut self, item: &mut dyn Visitable, event: Event);
+    fn visit(&mut self, item: &dyn Visitable, event: Event) -> ControlFlow<()>;
+    fn visit_mut(&mut self, item: &mut dyn Visitable, event: Event) -> ControlFlow<()>;
+
+    // patch
+    fn visit_patch(&mut self, _: PatchState) {}
 
     // path
     fn push(&mut self, _: &str) {}
@@ -28,21 +41,43 @@ pub trait Visitor {
 }
 
 // perform_visit
-pub fn perform_visit(visitor: &mut dyn Visitor, node: &dyn Visitable, key: &str) {
+pub fn perform_visit(
+    visitor: &mut dyn Visitor,
+    node: &dyn Visitable,
+    key: &str,
+) -> ControlFlow<()> {
     visitor.push(key);
-    visitor.visit(node, Event::Enter);
-    node.drive(visitor);
-    visitor.visit(node, Event::Exit);
+    let flow = visit_node(visitor, node);
     visitor.pop();
+
+    flow
 }
 
 // perform_visit_mut
-pub fn perform_visit_mut(visitor: &mut dyn Visitor, node: &mut dyn Visitable, key: &str) {
+pub fn perform_visit_mut(
+    visitor: &mut dyn Visitor,
+    node: &mut dyn Visitable,
+    key: &str,
+) -> ControlFlow<()> {
     visitor.push(key);
-    visitor.visit_mut(node, Event::Enter);
-    node.drive_mut(visitor);
-    visitor.visit_mut(node, Event::Exit);
+    let flow = visit_node_mut(visitor, node);
     visitor.pop();
+
+    flow
+}
+
+// visit_node
+fn visit_node(visitor: &mut dyn Visitor, node: &dyn Visitable) -> ControlFlow<()> {
+    visitor.visit(node, Event::Enter)?;
+    node.drive(visitor)?;
+    visitor.visit(node, Event::Exit)
+}
+
+// visit_node_mut
+fn visit_node_mut(visitor: &mut dyn Visitor, node: &mut dyn Visitable) -> ControlFlow<()> {
+    visitor.visit_mut(node, Event::Enter)?;
+    node.drive_mut(visitor)?;
+    visitor.visit_mut(node, Event::Exit)
 }
 
 ///
@@ -60,17 +95,19 @@ impl SanitizeVisitor {
 }
 
 impl Visitor for SanitizeVisitor {
-    fn visit(&mut self, _: &dyn Visitable, _: Event) {
+    fn visit(&mut self, _: &dyn Visitable, _: Event) -> ControlFlow<()> {
         panic!("sanitize requires visit_mut");
     }
 
-    fn visit_mut(&mut self, item: &mut dyn Visitable, event: Event) {
+    fn visit_mut(&mut self, item: &mut dyn Visitable, event: Event) -> ControlFlow<()> {
         match event {
             Event::Enter => {
                 item.sanitize();
             }
             Event::Exit => {}
         }
+
+        ControlFlow::Continue(())
     }
 }
 
@@ -80,7 +117,7 @@ impl Visitor for SanitizeVisitor {
 
 #[derive(Debug, Default)]
 pub struct ValidateVisitor {
-    pub errors: ErrorTree,
+    pub errors: FieldErrors,
     pub path: Vec<String>,
 }
 
@@ -89,32 +126,54 @@ impl ValidateVisitor {
     pub fn new() -> Self {
         Self::default()
     }
+
+    // key
+    // the dotted path of the current node
+    fn key(&self) -> String {
+        self.path
+            .iter()
+            .filter(|s| !s.is_empty())
+            .cloned()
+            .collect::<Vec<String>>()
+            .join(".")
+    }
 }
 
 impl Visitor for ValidateVisitor {
-    fn visit(&mut self, item: &dyn Visitable, event: Event) {
+    fn visit(&mut self, item: &dyn Visitable, event: Event) -> ControlFlow<()> {
         match event {
             Event::Enter => match item.validate() {
                 Ok(()) => {}
                 Err(errs) => {
                     if !errs.is_empty() {
-                        let key = self
-                            .path
-                            .iter()
-                            .filter(|s| !s.is_empty())
-                            .cloned()
-                            .collect::<Vec<String>>()
-                            .join(".");
-
-                        self.errors.set_list(&key, &errs);
+                        let key = self.key();
+                        self.errors.add_vec(&key, errs);
                     }
                 }
             },
-            Event::Exit => {}
+
+            // the after checks name paths relative to the node
+            Event::Exit => {
+                if let Err(errs) = item.validate_after() {
+                    let key = self.key();
+                    self.errors.extend(errs.into_iter().map(|mut error| {
+                        if !key.is_empty() {
+                            error.path = if error.path.is_empty() {
+                                key.clone()
+                            } else {
+                                format!("{key}.{}", error.path)
+                            };
+                        }
+                        error
+                    }));
+                }
+            }
         }
+
+        ControlFlow::Continue(())
     }
 
-    fn visit_mut(&mut self, _: &mut dyn Visitable, _: Event) {
+    fn visit_mut(&mut self, _: &mut dyn Visitable, _: Event) -> ControlFlow<()> {
         panic!("validate requires visit (not visit_mut)");
     }
 
@@ -134,32 +193,37 @@ impl Visitor for ValidateVisitor {
 /// `<https://github.com/rust-lang/rust/issues/65991>`
 ///
 
-pub struct EntityAdapter<'a>(pub &'a dyn EntityDynamic);
+pub struct EntityAdapter<'a, E: ?Sized + EntityDynamic = dyn EntityDynamic>(pub &'a E);
 
-impl<'a> Visitable for EntityAdapter<'a> {
-    fn drive(&self, visitor: &mut dyn Visitor) {
-        self.0.drive(visitor);
+impl<E: ?Sized + EntityDynamic> Visitable for EntityAdapter<'_, E> {
+    fn drive(&self, visitor: &mut dyn Visitor) -> ControlFlow<()> {
+        self.0.drive(visitor)
     }
 }
 
-impl<'a> Sanitize for EntityAdapter<'a> {}
-impl<'a> SanitizeAuto for EntityAdapter<'a> {}
-impl<'a> Validate for EntityAdapter<'a> {}
-impl<'a> ValidateAuto for EntityAdapter<'a> {}
+impl<E: ?Sized + EntityDynamic> Sanitize for EntityAdapter<'_, E> {}
+impl<E: ?Sized + EntityDynamic> SanitizeAuto for EntityAdapter<'_, E> {}
+impl<E: ?Sized + EntityDynamic> Validate for EntityAdapter<'_, E> {
+    // the adapter drives the entity's fields, so it runs the entity's checks
+    fn validate_after(&self) -> Result<(), FieldErrors> {
+        self.0.validate_entity()
+    }
+}
+impl<E: ?Sized + EntityDynamic> ValidateAuto for EntityAdapter<'_, E> {}
 
 ///
 /// EntityAdapterMut
 ///
 
-pub struct EntityAdapterMut<'a>(pub &'a mut dyn EntityDynamic);
+pub struct EntityAdapterMut<'a, E: ?Sized + EntityDynamic = dyn EntityDynamic>(pub &'a mut E);
 
-impl<'a> Visitable for EntityAdapterMut<'a> {
-    fn drive_mut(&mut self, visitor: &mut dyn Visitor) {
-        self.0.drive_mut(visitor);
+impl<E: ?Sized + EntityDynamic> Visitable for EntityAdapterMut<'_, E> {
+    fn drive_mut(&mut self, visitor: &mut dyn Visitor) -> ControlFlow<()> {
+        self.0.drive_mut(visitor)
     }
 }
 
-impl<'a> Sanitize for EntityAdapterMut<'a> {}
-impl<'a> SanitizeAuto for EntityAdapterMut<'a> {}
-impl<'a> Validate for EntityAdapterMut<'a> {}
-impl<'a> ValidateAuto for EntityAdapterMut<'a> {}
+impl<E: ?Sized + EntityDynamic> Sanitize for EntityAdapterMut<'_, E> {}
+impl<E: ?Sized + EntityDynamic> SanitizeAuto for EntityAdapterMut<'_, E> {}
+impl<E: ?Sized + EntityDynamic> Validate for EntityAdapterMut<'_, E> {}
+impl<E: ?Sized + EntityDynamic> ValidateAuto for EntityAdapterMut<'_, E> {}
@@ -5,11 +5,86 @@ pub mod macros;
 /// [for external use only]
 ///
 pub use api;
-pub use cli;
 pub use config;
 pub use ic;
 pub use types;
 
+pub mod cli {
+    pub use ::cli::*;
+
+    // run
+    // the cli with the candid of the mimic types the generated actor uses
+    pub fn run() {
+        ::cli::run_with(&external());
+    }
+
+    // external
+    // a type that's missing here fails the candid command rather than
+    // leaving the .did file with an undefined name
+    #[must_use]
+    pub fn external() -> candid::External {
+        let mut external = candid::External::new();
+        external
+            .add::<crate::Error>("Error")
+            .add::<api::create::CreateCost>("CreateCost")
+            .add::<api::request::CycleStatus>("CycleStatus")
+            .add::<api::request::Request>("Request")
+            .add::<api::request::Response>("Response")
+            .add::<core_state::AppCommand>("AppCommand")
+            .add::<core_state::AppState>("AppState")
+            .add::<core_state::CanisterState>("CanisterState")
+            .add::<core_state::ChildIndex>("ChildIndex")
+            .add::<core_state::RequestCosts>("RequestCosts")
+            .add::<core_state::SubnetIndex>("SubnetIndex")
+            .add::<core_state::User>("User")
+            .add::<core_state::UserIndex>("UserIndex")
+            .add::<db::StoreStats>("StoreStats")
+            .add::<db_query::DeleteRequest>("DeleteRequest")
+            .add::<db_query::DeleteResponse>("DeleteResponse")
+            .add::<db_query::LoadRequest>("LoadRequest")
+            .add::<db_query::LoadResponse>("LoadResponse")
+            .add::<db_query::SaveRequest>("SaveRequest")
+            .add::<db_query::SaveResponse>("SaveResponse")
+            .add::<ic::api::management_canister::main::CanisterInfoResponse>("CanisterInfoResponse")
+            .add::<ic::structures::memory::MemoryUsage>("MemoryUsage");
+
+        external
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use orm_schema::node::{Canister, CanisterBuild, Def, Schema};
+
+        // every type the root and user actors name has a definition
+        #[test]
+        fn test_external() {
+            let schema = Schema::new();
+            let external = external();
+
+            for build in [CanisterBuild::Root, CanisterBuild::User] {
+                let canister = Canister {
+                    def: Def {
+                        module_path: "test".to_string(),
+                        ident: "Canister".to_string(),
+                        comments: String::new(),
+                    },
+                    initial_cycles: 0,
+                    min_cycles: 0,
+                    build,
+                    cycles_check_secs: None,
+                    upgrade_warn_bytes: None,
+                };
+                let actor = actor::ActorBuilder::new(canister).expand();
+
+                let mut builder = candid::CandidBuilder::new(&schema, &external);
+                builder.add_service(actor).unwrap();
+                builder.expand().unwrap();
+            }
+        }
+    }
+}
+
 pub mod core {
     pub use core_schema as schema;
     pub use core_state as state;
@@ -74,6 +149,16 @@ pub enum Error {
     CoreWasm { source: core_wasm::Error },
 }
 
+// the save errors are wrapped as a Query error, the variants are transparent
+// so Display and source() still come from the SaveError
+impl From<db_query::save::SaveError> for Error {
+    fn from(source: db_query::save::SaveError) -> Self {
+        Self::Query {
+            source: source.into(),
+        }
+    }
+}
+
 ///
 /// MIMIC PRELUDE
 ///
@@ -112,15 +197,15 @@ pub mod orm {
         pub use ::orm::{
             collections::HashSet,
             traits::{
-                EntityDynamic, EntityFixture, EnumHash, Filterable, Inner, Orderable, Path,
-                PrimaryKey, Sanitize, Storable, Validate, Visitable,
+                EntityDynamic, EntityFixture, EnumHash, Filterable, Inner, NumChecked, Orderable,
+                Path, PrimaryKey, Sanitize, Storable, Validate, Visitable,
             },
         };
         pub use ::orm_macros::*;
         pub use ::serde::{Deserialize, Serialize};
         pub use ::snafu::Snafu;
         pub use ::std::{cmp::Ordering, fmt::Display};
-        pub use ::types::ErrorVec;
+        pub use ::types::{ErrorVec, FieldErrors};
     }
 
     pub use orm::*;
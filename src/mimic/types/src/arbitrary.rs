@@ -0,0 +1,53 @@
+use crate::{Blob, Decimal, Timestamp, Ulid};
+use proptest::{
+    arbitrary::{any, Arbitrary},
+    strategy::{BoxedStrategy, Strategy},
+};
+
+///
+/// Arbitrary
+///
+/// proptest strategies for the types that the orm macros can't derive
+///
+
+impl Arbitrary for Blob {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with((): Self::Parameters) -> Self::Strategy {
+        any::<Vec<u8>>().prop_map(Self::from).boxed()
+    }
+}
+
+impl Arbitrary for Decimal {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    // 28 is the largest scale rust_decimal supports
+    fn arbitrary_with((): Self::Parameters) -> Self::Strategy {
+        (any::<i64>(), 0..=28_u32)
+            .prop_map(|(num, scale)| Self::new(num, scale))
+            .boxed()
+    }
+}
+
+impl Arbitrary for Timestamp {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with((): Self::Parameters) -> Self::Strategy {
+        any::<u64>().prop_map(Self::from).boxed()
+    }
+}
+
+impl Arbitrary for Ulid {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    // the timestamp part of a ulid is 48 bits
+    fn arbitrary_with((): Self::Parameters) -> Self::Strategy {
+        (0..1_u64 << 48, any::<u128>())
+            .prop_map(|(timestamp_ms, random)| Self::from_parts(timestamp_ms, random))
+            .boxed()
+    }
+}
@@ -1,26 +1,27 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
 use candid::CandidType;
 use derive_more::{Deref, DerefMut};
-use serde::{Deserialize, Serialize};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use serde_bytes::ByteBuf;
+use snafu::Snafu;
+
+///
+/// Error
+///
+
+#[derive(CandidType, Debug, Serialize, Deserialize, Snafu)]
+pub enum Error {
+    #[snafu(display("invalid base64: {input}"))]
+    InvalidBase64 { input: String },
+}
 
 ///
 /// Blob
+/// a base64 string in human readable formats like json, bytes everywhere else
 ///
 
 #[derive(
-    CandidType,
-    Clone,
-    Debug,
-    Default,
-    Deref,
-    DerefMut,
-    Eq,
-    PartialEq,
-    Hash,
-    Ord,
-    PartialOrd,
-    Serialize,
-    Deserialize,
+    CandidType, Clone, Debug, Default, Deref, DerefMut, Eq, PartialEq, Hash, Ord, PartialOrd,
 )]
 pub struct Blob(ByteBuf);
 
@@ -34,6 +35,50 @@ impl Blob {
     pub fn is_empty(&self) -> bool {
         self.0.is_empty()
     }
+
+    // to_base64
+    // the standard alphabet, padded
+    #[must_use]
+    pub fn to_base64(&self) -> String {
+        STANDARD.encode(&self.0)
+    }
+
+    // from_base64
+    pub fn from_base64(input: &str) -> Result<Self, Error> {
+        let bytes = STANDARD.decode(input).map_err(|_| Error::InvalidBase64 {
+            input: input.to_string(),
+        })?;
+
+        Ok(Self::from(bytes))
+    }
+}
+
+impl Serialize for Blob {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&self.to_base64())
+        } else {
+            self.0.serialize(serializer)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Blob {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if deserializer.is_human_readable() {
+            let s = String::deserialize(deserializer)?;
+
+            Self::from_base64(&s).map_err(de::Error::custom)
+        } else {
+            ByteBuf::deserialize(deserializer).map(Self)
+        }
+    }
 }
 
 impl From<Vec<u8>> for Blob {
@@ -41,3 +86,30 @@ impl From<Vec<u8>> for Blob {
         Self(ByteBuf::from(bytes))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base64_roundtrip() {
+        let cases = [
+            (&b""[..], ""),
+            (b"f", "Zg=="),
+            (b"fo", "Zm8="),
+            (b"foo", "Zm9v"),
+            (b"foob", "Zm9vYg=="),
+            (&[0xff, 0xfe, 0x00], "//4A"),
+        ];
+
+        for (bytes, text) in cases {
+            let blob = Blob::from(bytes.to_vec());
+            assert_eq!(blob.to_base64(), text);
+            assert_eq!(Blob::from_base64(text).unwrap(), blob);
+        }
+
+        for bad in ["Zg=", "Zg=a", "Z===", "Zg==Zm8=", "Zm9*"] {
+            assert!(Blob::from_base64(bad).is_err(), "{bad}");
+        }
+    }
+}
@@ -50,6 +50,35 @@ impl Decimal {
     }
 }
 
+// impl_decimal_ops
+// the checked and saturating ops of the wrapped decimal
+macro_rules! impl_decimal_ops {
+    ($($checked:ident, $saturating:ident),*) => {
+        impl Decimal {
+            $(
+                #[must_use]
+                pub fn $checked(self, other: Self) -> Option<Self> {
+                    self.0.$checked(other.0).map(Self)
+                }
+
+                #[must_use]
+                pub fn $saturating(self, other: Self) -> Self {
+                    Self(self.0.$saturating(other.0))
+                }
+            )*
+        }
+    };
+}
+
+impl_decimal_ops!(
+    checked_add,
+    saturating_add,
+    checked_sub,
+    saturating_sub,
+    checked_mul,
+    saturating_mul
+);
+
 impl CandidType for Decimal {
     fn _ty() -> candid::types::Type {
         candid::types::TypeInner::Float64.into()
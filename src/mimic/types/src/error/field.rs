@@ -0,0 +1,177 @@
+use crate::error::{ErrorTree, ErrorVec};
+use candid::CandidType;
+use derive_more::{Deref, DerefMut, IntoIterator};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+///
+/// FieldError
+/// a validation error scoped to the path of the field that failed
+///
+
+#[derive(CandidType, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct FieldError {
+    pub path: String,
+    pub validator: Option<String>,
+    pub message: String,
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if !self.path.is_empty() {
+            write!(f, "{}: ", self.path)?;
+        }
+        write!(f, "{}", self.message)?;
+        if let Some(validator) = &self.validator {
+            write!(f, " ({validator})")?;
+        }
+
+        Ok(())
+    }
+}
+
+///
+/// FieldErrors
+/// every failure from a validation pass, in the order they were found
+///
+
+#[derive(
+    CandidType,
+    Clone,
+    Debug,
+    Default,
+    Deref,
+    DerefMut,
+    Eq,
+    IntoIterator,
+    PartialEq,
+    Serialize,
+    Deserialize,
+)]
+pub struct FieldErrors(Vec<FieldError>);
+
+impl FieldErrors {
+    // new
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // add
+    pub fn add<S: Into<String>>(&mut self, path: &str, message: S) {
+        self.push(FieldError {
+            path: path.to_string(),
+            validator: None,
+            message: message.into(),
+        });
+    }
+
+    // add_check
+    // merges the errors from an entity check, the ones that don't name a
+    // validator are put down to the check itself
+    pub fn add_check(&mut self, check: &str, result: Result<(), Self>) {
+        if let Err(errors) = result {
+            self.extend(errors.into_iter().map(|mut error| {
+                error.validator.get_or_insert_with(|| check.to_string());
+                error
+            }));
+        }
+    }
+
+    // add_vec
+    // scopes each error in the ErrorVec to the path
+    pub fn add_vec(&mut self, path: &str, errs: ErrorVec) {
+        self.extend(errs.into_iter().map(|entry| FieldError {
+            path: path.to_string(),
+            validator: entry.validator,
+            message: entry.message,
+        }));
+    }
+
+    // result
+    pub fn result(self) -> Result<(), Self> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(self)
+        }
+    }
+}
+
+// an ErrorTree doesn't know its validators, ordered by path so it's stable
+impl From<ErrorTree> for FieldErrors {
+    fn from(tree: ErrorTree) -> Self {
+        let mut paths: Vec<_> = tree.iter().collect();
+        paths.sort_by(|a, b| a.0.cmp(b.0));
+
+        Self(
+            paths
+                .into_iter()
+                .flat_map(|(path, errors)| {
+                    errors.iter().map(move |message| FieldError {
+                        path: path.clone(),
+                        validator: None,
+                        message: message.clone(),
+                    })
+                })
+                .collect(),
+        )
+    }
+}
+
+// one error per line, written so to avoid trailing newlines
+impl fmt::Display for FieldErrors {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, error) in self.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{error}")?;
+        }
+
+        Ok(())
+    }
+}
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_vec_keeps_validators() {
+        let mut errs = ErrorVec::new();
+        errs.add("manual");
+        errs.add_validator("validator::number::Lt", "too big");
+
+        let mut fields = FieldErrors::new();
+        fields.add_vec("level", errs);
+
+        assert_eq!(fields.len(), 2);
+        assert_eq!(fields[0].validator, None);
+        assert_eq!(
+            fields[1].validator.as_deref(),
+            Some("validator::number::Lt")
+        );
+        assert_eq!(
+            fields.to_string(),
+            "level: manual\nlevel: too big (validator::number::Lt)"
+        );
+    }
+
+    #[test]
+    fn test_add_check() {
+        let mut check = FieldErrors::new();
+        check.add("end", "end is before start");
+
+        let mut fields = FieldErrors::new();
+        fields.add_check("date_order", Ok(()));
+        fields.add_check("date_order", check.result());
+
+        assert_eq!(fields.len(), 1);
+        assert_eq!(fields[0].validator.as_deref(), Some("date_order"));
+    }
+}
@@ -1,5 +1,7 @@
+pub mod field;
 pub mod tree;
 pub mod vec;
 
+pub use field::{FieldError, FieldErrors};
 pub use tree::ErrorTree;
-pub use vec::ErrorVec;
+pub use vec::{ErrorEntry, ErrorVec};
@@ -1,7 +1,7 @@
 use candid::CandidType;
 use derive_more::{Deref, DerefMut, IntoIterator};
 use serde::{Deserialize, Serialize};
-use std::{error::Error, fmt::Display};
+use std::{error::Error, fmt};
 
 ///
 /// ErrorVec
@@ -11,7 +11,7 @@ use std::{error::Error, fmt::Display};
 #[derive(
     CandidType, Debug, Default, Deref, DerefMut, Eq, IntoIterator, PartialEq, Serialize, Deserialize,
 )]
-pub struct ErrorVec(Vec<String>);
+pub struct ErrorVec(Vec<ErrorEntry>);
 
 impl ErrorVec {
     // new
@@ -30,7 +30,7 @@ impl ErrorVec {
 
     // add
     pub fn add<S: Into<String>>(&mut self, s: S) {
-        self.push(s.into());
+        self.push(ErrorEntry::new(s));
     }
 
     // add_result
@@ -40,6 +40,22 @@ impl ErrorVec {
         }
     }
 
+    // add_validator
+    // records the validator that raised the error alongside it
+    pub fn add_validator<S: Into<String>>(&mut self, validator: &str, s: S) {
+        self.push(ErrorEntry {
+            validator: Some(validator.to_string()),
+            message: s.into(),
+        });
+    }
+
+    // add_validator_result
+    pub fn add_validator_result<E: Error>(&mut self, validator: &str, result: Result<(), E>) {
+        if let Err(e) = result {
+            self.add_validator(validator, e.to_string());
+        }
+    }
+
     // merge
     pub fn merge(&mut self, result: Result<(), Self>) {
         if let Err(errors) = result {
@@ -47,6 +63,12 @@ impl ErrorVec {
         }
     }
 
+    // messages
+    #[must_use]
+    pub fn messages(&self) -> Vec<String> {
+        self.iter().map(|entry| entry.message.clone()).collect()
+    }
+
     // result
     pub fn result(self) -> Result<(), Self> {
         if self.is_empty() {
@@ -57,8 +79,35 @@ impl ErrorVec {
     }
 }
 
-impl<T: Display> From<T> for ErrorVec {
+impl<T: fmt::Display> From<T> for ErrorVec {
     fn from(item: T) -> Self {
-        Self(vec![item.to_string()])
+        Self(vec![ErrorEntry::new(item.to_string())])
+    }
+}
+
+///
+/// ErrorEntry
+/// one error, and the path of the validator that raised it if there was one
+///
+
+#[derive(CandidType, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ErrorEntry {
+    pub validator: Option<String>,
+    pub message: String,
+}
+
+impl ErrorEntry {
+    #[must_use]
+    pub fn new<S: Into<String>>(message: S) -> Self {
+        Self {
+            validator: None,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ErrorEntry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
     }
 }
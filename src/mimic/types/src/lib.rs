@@ -1,3 +1,5 @@
+#[cfg(feature = "test")]
+mod arbitrary;
 pub mod blob;
 pub mod decimal;
 pub mod error;
@@ -6,7 +8,7 @@ pub mod ulid;
 
 pub use blob::Blob;
 pub use decimal::Decimal;
-pub use error::{ErrorTree, ErrorVec};
+pub use error::{ErrorEntry, ErrorTree, ErrorVec, FieldError, FieldErrors};
 pub use timestamp::Timestamp;
 pub use ulid::Ulid;
 
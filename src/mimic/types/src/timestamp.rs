@@ -1,27 +1,86 @@
 use candid::CandidType;
 use derive_more::{Deref, DerefMut};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use snafu::Snafu;
+use std::{cell::Cell, time::Duration};
+
+///
+/// Error
+///
+
+#[derive(CandidType, Debug, Serialize, Deserialize, Snafu)]
+pub enum Error {
+    #[snafu(display("invalid rfc3339 timestamp: {input}"))]
+    InvalidRfc3339 { input: String },
+
+    #[snafu(display("timestamp is before the unix epoch: {input}"))]
+    BeforeEpoch { input: String },
+
+    #[snafu(display("timestamp doesn't fit in u64 nanoseconds: {input}"))]
+    NanosOverflow { input: String },
+}
+
+///
+/// NANOS_PER_SEC
+/// the IC clock, ic_cdk::api::time(), counts nanoseconds
+///
+
+pub const NANOS_PER_SEC: u64 = 1_000_000_000;
 
 ///
 /// Timestamp
+/// seconds since the unix epoch, the same unit as Timestamp::now()
+/// the seconds in every format, unless it's serialized inside with_rfc3339
 ///
 
-#[derive(
-    CandidType,
-    Clone,
-    Copy,
-    Debug,
-    Deref,
-    DerefMut,
-    Eq,
-    PartialEq,
-    Ord,
-    PartialOrd,
-    Serialize,
-    Deserialize,
-)]
+#[derive(CandidType, Clone, Copy, Debug, Deref, DerefMut, Eq, PartialEq, Ord, PartialOrd)]
 pub struct Timestamp(u64);
 
+impl Serialize for Timestamp {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if RFC3339.get() {
+            rfc3339::serialize(self, serializer)
+        } else {
+            serializer.serialize_u64(self.0)
+        }
+    }
+}
+
+// takes the rfc3339 string as well, so json written inside with_rfc3339
+// reads back the same way
+impl<'de> Deserialize<'de> for Timestamp {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        rfc3339::deserialize(deserializer)
+    }
+}
+
+thread_local! {
+    static RFC3339: Cell<bool> = const { Cell::new(false) };
+}
+
+// with_rfc3339
+// runs f with every Timestamp serialized by a human readable format as an
+// rfc3339 string, the json codec in orm goes through this
+pub fn with_rfc3339<R>(f: impl FnOnce() -> R) -> R {
+    struct Reset(bool);
+
+    impl Drop for Reset {
+        fn drop(&mut self) {
+            RFC3339.set(self.0);
+        }
+    }
+
+    let _reset = Reset(RFC3339.replace(true));
+
+    f()
+}
+
 impl From<u64> for Timestamp {
     fn from(n: u64) -> Self {
         Self(n)
@@ -38,4 +97,444 @@ impl Timestamp {
     pub fn now_millis() -> Self {
         Self(lib_time::now_millis())
     }
+
+    // from_nanos
+    // sub-second nanoseconds are truncated
+    #[must_use]
+    pub const fn from_nanos(nanos: u64) -> Self {
+        Self(nanos / NANOS_PER_SEC)
+    }
+
+    // as_nanos
+    // None past 2554, where the nanoseconds no longer fit in a u64
+    #[must_use]
+    pub const fn as_nanos(&self) -> Option<u64> {
+        self.0.checked_mul(NANOS_PER_SEC)
+    }
+
+    // to_rfc3339
+    // always UTC, ie. 2024-01-31T12:00:00Z, rfc3339 years only have four
+    // digits so anything after 9999 won't parse back, rfc3339::serialize
+    // rejects those
+    #[must_use]
+    pub fn to_rfc3339(&self) -> String {
+        format_rfc3339(self.0, 0)
+    }
+
+    // from_rfc3339
+    // fractional seconds are truncated and offsets are converted to UTC,
+    // nanos::from_rfc3339 keeps them
+    pub fn from_rfc3339(input: &str) -> Result<Self, Error> {
+        parse_rfc3339(input).map(|(secs, _)| Self(secs))
+    }
+
+    // checked_add
+    // sub-second parts of the duration are truncated
+    #[must_use]
+    pub fn checked_add(&self, duration: Duration) -> Option<Self> {
+        self.0.checked_add(duration.as_secs()).map(Self)
+    }
+
+    // checked_sub
+    #[must_use]
+    pub fn checked_sub(&self, duration: Duration) -> Option<Self> {
+        self.0.checked_sub(duration.as_secs()).map(Self)
+    }
+
+    // duration_since
+    // None if other is later than self
+    #[must_use]
+    pub fn duration_since(&self, other: Self) -> Option<Duration> {
+        self.0.checked_sub(other.0).map(Duration::from_secs)
+    }
+}
+
+///
+/// nanos
+/// the helpers for a u64 of nanoseconds since the unix epoch, the unit of
+/// the IC clock, so nothing below a second is lost
+///
+
+pub mod nanos {
+    use super::{format_rfc3339, parse_rfc3339, Error, NANOS_PER_SEC};
+    use std::time::Duration;
+
+    // to_rfc3339
+    // as Timestamp::to_rfc3339, with the fraction when there is one,
+    // ie. 2024-01-31T12:00:00.5Z
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn to_rfc3339(nanos: u64) -> String {
+        format_rfc3339(nanos / NANOS_PER_SEC, (nanos % NANOS_PER_SEC) as u32)
+    }
+
+    // from_rfc3339
+    // fractional digits past the ninth are truncated
+    pub fn from_rfc3339(input: &str) -> Result<u64, Error> {
+        let (secs, frac) = parse_rfc3339(input)?;
+
+        secs.checked_mul(NANOS_PER_SEC)
+            .and_then(|nanos| nanos.checked_add(u64::from(frac)))
+            .ok_or_else(|| Error::NanosOverflow {
+                input: input.to_string(),
+            })
+    }
+
+    // checked_add
+    #[must_use]
+    pub fn checked_add(nanos: u64, duration: Duration) -> Option<u64> {
+        u64::try_from(duration.as_nanos())
+            .ok()
+            .and_then(|d| nanos.checked_add(d))
+    }
+
+    // checked_sub
+    #[must_use]
+    pub fn checked_sub(nanos: u64, duration: Duration) -> Option<u64> {
+        u64::try_from(duration.as_nanos())
+            .ok()
+            .and_then(|d| nanos.checked_sub(d))
+    }
+
+    // duration_since
+    // None if other is later than nanos
+    #[must_use]
+    pub fn duration_since(nanos: u64, other: u64) -> Option<Duration> {
+        nanos.checked_sub(other).map(Duration::from_nanos)
+    }
+
+    // from_secs
+    // None past 2554, where the nanoseconds no longer fit in a u64
+    #[must_use]
+    pub const fn from_secs(secs: u64) -> Option<u64> {
+        secs.checked_mul(NANOS_PER_SEC)
+    }
+
+    // to_secs
+    // sub-second nanoseconds are truncated
+    #[must_use]
+    pub const fn to_secs(nanos: u64) -> u64 {
+        nanos / NANOS_PER_SEC
+    }
+}
+
+///
+/// rfc3339
+/// serde for a Timestamp that's an rfc3339 string in human readable formats
+/// like json and the seconds everywhere else, for #[serde(with)]
+///
+
+pub mod rfc3339 {
+    use super::Timestamp;
+    use serde::{de, ser, Deserialize, Deserializer, Serializer};
+    use std::fmt;
+
+    // MAX_SECS
+    // 9999-12-31T23:59:59Z, past it there's no rfc3339 form
+    pub const MAX_SECS: u64 = 253_402_300_799;
+
+    // serialize
+    // a timestamp past MAX_SECS is an error rather than the seconds, so a
+    // field is always the one type in json
+    pub fn serialize<S>(ts: &Timestamp, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if !serializer.is_human_readable() {
+            serializer.serialize_u64(**ts)
+        } else if **ts <= MAX_SECS {
+            serializer.serialize_str(&ts.to_rfc3339())
+        } else {
+            Err(ser::Error::custom(format!(
+                "timestamp {} is past 9999-12-31T23:59:59Z and has no rfc3339 form",
+                **ts
+            )))
+        }
+    }
+
+    // deserialize
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Timestamp, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(TimestampVisitor)
+        } else {
+            u64::deserialize(deserializer).map(Timestamp)
+        }
+    }
+
+    ///
+    /// TimestampVisitor
+    /// takes the seconds as well, json that isn't written as rfc3339 has them
+    ///
+
+    struct TimestampVisitor;
+
+    impl de::Visitor<'_> for TimestampVisitor {
+        type Value = Timestamp;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("an rfc3339 timestamp or seconds since the unix epoch")
+        }
+
+        fn visit_u64<E: de::Error>(self, v: u64) -> Result<Timestamp, E> {
+            Ok(Timestamp(v))
+        }
+
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<Timestamp, E> {
+            Timestamp::from_rfc3339(v).map_err(E::custom)
+        }
+    }
+}
+
+// format_rfc3339
+// the fraction is only written when it isn't zero, without trailing zeros
+fn format_rfc3339(secs: u64, frac: u32) -> String {
+    let days = secs / 86_400;
+    let rem = secs % 86_400;
+    let (year, month, day) = civil_from_days(days);
+
+    let mut out = format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}",
+        rem / 3600,
+        (rem / 60) % 60,
+        rem % 60
+    );
+    if frac > 0 {
+        let digits = format!("{frac:09}");
+        out.push('.');
+        out.push_str(digits.trim_end_matches('0'));
+    }
+    out.push('Z');
+
+    out
+}
+
+// parse_rfc3339
+// the seconds since the epoch in UTC and the nanoseconds of the fraction
+fn parse_rfc3339(input: &str) -> Result<(u64, u32), Error> {
+    let invalid = || Error::InvalidRfc3339 {
+        input: input.to_string(),
+    };
+    let bytes = input.as_bytes();
+    let num = |range: std::ops::Range<usize>| -> Result<i64, Error> {
+        input
+            .get(range)
+            .filter(|s| s.bytes().all(|b| b.is_ascii_digit()))
+            .and_then(|s| s.parse().ok())
+            .ok_or_else(invalid)
+    };
+
+    // date and time
+    if bytes.len() < 20
+        || bytes[4] != b'-'
+        || bytes[7] != b'-'
+        || !matches!(bytes[10], b'T' | b't' | b' ')
+        || bytes[13] != b':'
+        || bytes[16] != b':'
+    {
+        return Err(invalid());
+    }
+    let (year, month, day) = (num(0..4)?, num(5..7)?, num(8..10)?);
+    let (hour, minute, second) = (num(11..13)?, num(14..16)?, num(17..19)?);
+    if !(1..=12).contains(&month)
+        || day < 1
+        || day > days_in_month(year, month)
+        || hour > 23
+        || minute > 59
+        || second > 60
+    {
+        return Err(invalid());
+    }
+
+    // fractional seconds, only the first nine digits count
+    let mut pos = 19;
+    let mut frac = 0;
+    if bytes[pos] == b'.' {
+        pos += 1;
+        let start = pos;
+        while pos < bytes.len() && bytes[pos].is_ascii_digit() {
+            if pos - start < 9 {
+                frac = frac * 10 + u32::from(bytes[pos] - b'0');
+            }
+            pos += 1;
+        }
+        if pos == start {
+            return Err(invalid());
+        }
+        for _ in (pos - start)..9 {
+            frac *= 10;
+        }
+    }
+
+    // offset
+    let offset = match &input[pos..] {
+        "Z" | "z" => 0,
+        rest if rest.len() == 6 && rest.as_bytes()[3] == b':' => {
+            let sign = match rest.as_bytes()[0] {
+                b'+' => 1,
+                b'-' => -1,
+                _ => return Err(invalid()),
+            };
+            let (h, m) = (num(pos + 1..pos + 3)?, num(pos + 4..pos + 6)?);
+            if h > 23 || m > 59 {
+                return Err(invalid());
+            }
+
+            sign * (h * 3600 + m * 60)
+        }
+        _ => return Err(invalid()),
+    };
+
+    let secs =
+        days_from_civil(year, month, day) * 86_400 + hour * 3600 + minute * 60 + second - offset;
+
+    u64::try_from(secs)
+        .map(|secs| (secs, frac))
+        .map_err(|_| Error::BeforeEpoch {
+            input: input.to_string(),
+        })
+}
+
+// civil_from_days
+// days since the epoch to a (year, month, day) in the proleptic gregorian calendar
+#[allow(clippy::cast_possible_wrap, clippy::cast_sign_loss)]
+const fn civil_from_days(days: u64) -> (i64, u64, u64) {
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097) as u64;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe as i64 + era * 400 + (month <= 2) as i64;
+
+    (year, month, day)
+}
+
+// days_from_civil
+// the inverse of civil_from_days
+const fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+
+    era * 146_097 + doe - 719_468
+}
+
+// days_in_month
+const fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rfc3339_roundtrip() {
+        let cases = [
+            (0, "1970-01-01T00:00:00Z"),
+            (951_782_400, "2000-02-29T00:00:00Z"),
+            (1_706_702_400, "2024-01-31T12:00:00Z"),
+        ];
+
+        for (secs, text) in cases {
+            assert_eq!(Timestamp(secs).to_rfc3339(), text);
+            assert_eq!(Timestamp::from_rfc3339(text).unwrap(), Timestamp(secs));
+        }
+    }
+
+    #[test]
+    fn test_rfc3339_serde_boundary() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Row(#[serde(with = "rfc3339")] Timestamp);
+
+        let cases = [
+            (0, "\"1970-01-01T00:00:00Z\""),
+            (rfc3339::MAX_SECS, "\"9999-12-31T23:59:59Z\""),
+        ];
+
+        for (secs, json) in cases {
+            let row = Row(Timestamp(secs));
+            assert_eq!(serde_json::to_string(&row).unwrap(), json);
+            assert_eq!(serde_json::from_str::<Row>(json).unwrap(), row);
+        }
+
+        // past 9999 there's nothing to write, the seconds still read
+        assert!(serde_json::to_string(&Row(Timestamp(rfc3339::MAX_SECS + 1))).is_err());
+        let row: Row = serde_json::from_str("18446744073709551615").unwrap();
+        assert_eq!(row, Row(Timestamp(u64::MAX)));
+
+        // a plain Timestamp is the seconds unless it's inside with_rfc3339
+        assert_eq!(serde_json::to_string(&Timestamp(0)).unwrap(), "0");
+        let json = with_rfc3339(|| serde_json::to_string(&Timestamp(0))).unwrap();
+        assert_eq!(json, "\"1970-01-01T00:00:00Z\"");
+        assert_eq!(serde_json::to_string(&Timestamp(0)).unwrap(), "0");
+    }
+
+    #[test]
+    fn test_from_rfc3339_offset_and_fraction() {
+        let ts = Timestamp::from_rfc3339("2024-01-31T14:30:00.123456789+02:30").unwrap();
+        assert_eq!(ts.to_rfc3339(), "2024-01-31T12:00:00Z");
+
+        assert!(Timestamp::from_rfc3339("2024-02-30T00:00:00Z").is_err());
+        assert!(Timestamp::from_rfc3339("2024-01-31 12:00").is_err());
+        assert!(Timestamp::from_rfc3339("1969-12-31T23:59:59Z").is_err());
+    }
+
+    #[test]
+    fn test_duration_arithmetic() {
+        let ts = Timestamp(100);
+
+        assert_eq!(
+            ts.checked_add(Duration::from_secs(20)),
+            Some(Timestamp(120))
+        );
+        assert_eq!(ts.checked_sub(Duration::from_secs(101)), None);
+        assert_eq!(
+            Timestamp(120).duration_since(ts),
+            Some(Duration::from_secs(20))
+        );
+        assert_eq!(ts.duration_since(Timestamp(120)), None);
+    }
+
+    #[test]
+    fn test_nanos() {
+        let ts = nanos::from_rfc3339("2024-01-31T12:00:00.123456789Z").unwrap();
+        assert_eq!(ts, 1_706_702_400_123_456_789);
+        assert_eq!(nanos::to_rfc3339(ts), "2024-01-31T12:00:00.123456789Z");
+        assert_eq!(
+            nanos::to_rfc3339(1_706_702_400_500_000_000),
+            "2024-01-31T12:00:00.5Z"
+        );
+        assert_eq!(
+            nanos::from_rfc3339("2024-01-31T12:00:00.5Z").unwrap(),
+            1_706_702_400_500_000_000
+        );
+        assert!(nanos::from_rfc3339("9999-12-31T23:59:59Z").is_err());
+
+        // sub-second durations are kept
+        let half = Duration::from_millis(500);
+        assert_eq!(nanos::checked_add(ts, half), Some(ts + 500_000_000));
+        assert_eq!(nanos::checked_sub(0, half), None);
+        assert_eq!(nanos::duration_since(ts + 500_000_000, ts), Some(half));
+
+        // the seconds converters
+        assert_eq!(nanos::from_secs(2), Some(2 * NANOS_PER_SEC));
+        assert_eq!(nanos::to_secs(ts), 1_706_702_400);
+        assert_eq!(Timestamp::from_nanos(ts), Timestamp(1_706_702_400));
+        assert_eq!(Timestamp(1).as_nanos(), Some(NANOS_PER_SEC));
+        assert_eq!(Timestamp(u64::MAX).as_nanos(), None);
+    }
 }
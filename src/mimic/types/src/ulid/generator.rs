@@ -27,6 +27,15 @@ pub fn generate() -> Result<Ulid, Error> {
     generator.generate()
 }
 
+// set_clock
+// replaces the millisecond clock the shared generator takes timestamps
+// from, with lib_rand::seed_rng that makes the generated ulids reproducible
+#[cfg(any(test, feature = "test"))]
+pub fn set_clock(clock: fn() -> u64) {
+    let mut generator = GENERATOR.lock().unwrap();
+    *generator = Generator::with_clock(clock);
+}
+
 //
 // Generator
 //
@@ -36,6 +45,7 @@ pub fn generate() -> Result<Ulid, Error> {
 
 pub struct Generator {
     previous: Ulid,
+    clock: fn() -> u64,
 }
 
 impl Generator {
@@ -43,13 +53,25 @@ impl Generator {
     pub const fn new() -> Self {
         Self {
             previous: Ulid::nil(),
+            clock: lib_time::now_millis,
+        }
+    }
+
+    // with_clock
+    // a generator that takes its timestamps from clock instead of the time
+    #[cfg(any(test, feature = "test"))]
+    #[must_use]
+    pub const fn with_clock(clock: fn() -> u64) -> Self {
+        Self {
+            previous: Ulid::nil(),
+            clock,
         }
     }
 
     // generate
     pub fn generate(&mut self) -> Result<Ulid, Error> {
         let last_ts: Timestamp = self.previous.timestamp_ms().into();
-        let ts = Timestamp::now_millis();
+        let ts: Timestamp = (self.clock)().into();
 
         // maybe time went backward, or it is the same ms.
         // increment instead of generating a new random so that it is monotonic
@@ -79,3 +101,20 @@ impl Default for Generator {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_with_clock() {
+        let mut generator = Generator::with_clock(|| 1_000);
+        let a = generator.generate().unwrap();
+        let b = generator.generate().unwrap();
+
+        // the same millisecond so the second one is an increment
+        assert_eq!(a.timestamp_ms(), 1_000);
+        assert_eq!(b.timestamp_ms(), 1_000);
+        assert!(b > a);
+    }
+}
@@ -1,5 +1,11 @@
 use mimic::orm::prelude::*;
 
+// caller checks for the top of an endpoint
+pub use mimic::api::auth::{
+    require_caller_in, require_controller, require_not_anonymous, require_role, AuthError,
+    EntityRoleResolver, RoleAssignment, RoleResolver,
+};
+
 pub mod role {
     use super::*;
 
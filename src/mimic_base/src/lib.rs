@@ -1,3 +1,6 @@
+// relation fields name their type as mimic_base::types::Ulid
+extern crate self as mimic_base;
+
 pub mod auth;
 pub mod canister;
 pub mod sanitizer;
@@ -20,6 +20,21 @@ impl CollapseWhitespace {
     }
 }
 
+///
+/// Trim
+/// strips leading and trailing whitespace
+///
+
+#[sanitizer]
+pub struct Trim {}
+
+impl Trim {
+    #[must_use]
+    pub fn sanitize<S: Display>(s: S) -> String {
+        s.to_string().trim().to_string()
+    }
+}
+
 ///
 /// Title
 /// formats with title case, and strips and collapses whitespace
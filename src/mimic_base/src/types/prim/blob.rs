@@ -26,6 +26,18 @@ use mimic::{
 )]
 pub struct Blob(WrappedBlob);
 
+#[cfg(feature = "test")]
+impl mimic::orm::traits::Arbitrary for Blob {
+    type Parameters = ();
+    type Strategy = mimic::orm::proptest::strategy::BoxedStrategy<Self>;
+
+    fn arbitrary_with((): Self::Parameters) -> Self::Strategy {
+        use mimic::orm::proptest::{arbitrary::any, strategy::Strategy};
+
+        any::<WrappedBlob>().prop_map(Self).boxed()
+    }
+}
+
 impl Blob {
     #[must_use]
     pub fn len(&self) -> usize {
@@ -50,6 +62,10 @@ impl Inner<Self> for Blob {
     fn inner(&self) -> &Self {
         self
     }
+
+    fn inner_mut(&mut self) -> &mut Self {
+        self
+    }
 }
 
 impl Orderable for Blob {}
@@ -37,6 +37,18 @@ use std::{cmp::Ordering, fmt};
 )]
 pub struct Decimal(WrappedDecimal);
 
+#[cfg(feature = "test")]
+impl mimic::orm::traits::Arbitrary for Decimal {
+    type Parameters = ();
+    type Strategy = mimic::orm::proptest::strategy::BoxedStrategy<Self>;
+
+    fn arbitrary_with((): Self::Parameters) -> Self::Strategy {
+        use mimic::orm::proptest::{arbitrary::any, strategy::Strategy};
+
+        any::<WrappedDecimal>().prop_map(Self).boxed()
+    }
+}
+
 impl Decimal {
     #[must_use]
     pub fn new(num: i64, scale: u32) -> Self {
@@ -56,6 +68,8 @@ impl Decimal {
     }
 }
 
+impl_num_checked!(Decimal);
+
 impl fmt::Display for Decimal {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         self.0.fmt(f)
@@ -134,9 +148,15 @@ impl Inner<Self> for Decimal {
     fn inner(&self) -> &Self {
         self
     }
+
+    fn inner_mut(&mut self) -> &mut Self {
+        self
+    }
 }
 
 impl Orderable for Decimal {
+    const ORDERABLE: bool = true;
+
     fn cmp(&self, other: &Self) -> Ordering {
         Ord::cmp(self, other)
     }
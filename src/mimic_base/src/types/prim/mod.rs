@@ -1,3 +1,41 @@
+// impl_num_checked
+// for the primitives that wrap another numeric type
+macro_rules! impl_num_checked {
+    ($type:ty) => {
+        impl ::mimic::orm::traits::NumChecked for $type {
+            fn checked_add(self, other: Self) -> Option<Self> {
+                ::mimic::orm::traits::NumChecked::checked_add(self.0, other.0).map(Self)
+            }
+
+            fn checked_sub(self, other: Self) -> Option<Self> {
+                ::mimic::orm::traits::NumChecked::checked_sub(self.0, other.0).map(Self)
+            }
+
+            fn checked_mul(self, other: Self) -> Option<Self> {
+                ::mimic::orm::traits::NumChecked::checked_mul(self.0, other.0).map(Self)
+            }
+
+            fn saturating_add(self, other: Self) -> Self {
+                Self(::mimic::orm::traits::NumChecked::saturating_add(
+                    self.0, other.0,
+                ))
+            }
+
+            fn saturating_sub(self, other: Self) -> Self {
+                Self(::mimic::orm::traits::NumChecked::saturating_sub(
+                    self.0, other.0,
+                ))
+            }
+
+            fn saturating_mul(self, other: Self) -> Self {
+                Self(::mimic::orm::traits::NumChecked::saturating_mul(
+                    self.0, other.0,
+                ))
+            }
+        }
+    };
+}
+
 pub mod blob;
 pub mod decimal;
 pub mod principal;
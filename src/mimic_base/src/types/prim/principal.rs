@@ -46,6 +46,21 @@ pub enum Error {
 )]
 pub struct Principal(WrappedPrincipal);
 
+#[cfg(feature = "test")]
+impl mimic::orm::traits::Arbitrary for Principal {
+    type Parameters = ();
+    type Strategy = mimic::orm::proptest::strategy::BoxedStrategy<Self>;
+
+    fn arbitrary_with((): Self::Parameters) -> Self::Strategy {
+        use mimic::orm::proptest::{arbitrary::any, collection::vec, strategy::Strategy};
+
+        // principals are at most 29 bytes
+        vec(any::<u8>(), 0..=29)
+            .prop_map(|bytes| Self(WrappedPrincipal::from_slice(&bytes)))
+            .boxed()
+    }
+}
+
 impl Principal {
     #[must_use]
     pub fn caller() -> Self {
@@ -95,9 +110,15 @@ impl Inner<Self> for Principal {
     fn inner(&self) -> &Self {
         self
     }
+
+    fn inner_mut(&mut self) -> &mut Self {
+        self
+    }
 }
 
 impl Orderable for Principal {
+    const ORDERABLE: bool = true;
+
     fn cmp(&self, other: &Self) -> Ordering {
         Ord::cmp(self, other)
     }
@@ -1,30 +1,70 @@
 use derive_more::{Deref, DerefMut};
 use mimic::orm::{
     prelude::*,
-    traits::{SanitizeAuto, ValidateAuto},
+    traits::{Inner, SanitizeAuto, ValidateAuto},
 };
 
 ///
 /// Timestamp
+/// serializes as mimic::types::Timestamp, so it's an rfc3339 string in the
+/// json from entity_to_json
 ///
 
 #[derive(
-    CandidType,
-    Clone,
-    Debug,
-    Default,
-    Deref,
-    DerefMut,
-    Eq,
-    PartialEq,
-    Hash,
-    Ord,
-    PartialOrd,
-    Serialize,
-    Deserialize,
+    CandidType, Clone, Debug, Default, Deref, DerefMut, Eq, PartialEq, Hash, Ord, PartialOrd,
 )]
 pub struct Timestamp(u64);
 
+#[cfg(feature = "test")]
+impl mimic::orm::traits::Arbitrary for Timestamp {
+    type Parameters = ();
+    type Strategy = mimic::orm::proptest::strategy::BoxedStrategy<Self>;
+
+    fn arbitrary_with((): Self::Parameters) -> Self::Strategy {
+        use mimic::orm::proptest::{arbitrary::any, strategy::Strategy};
+
+        any::<u64>().prop_map(Self).boxed()
+    }
+}
+
+impl Filterable for Timestamp {}
+
+impl From<u64> for Timestamp {
+    fn from(n: u64) -> Self {
+        Self(n)
+    }
+}
+
+impl Inner<Self> for Timestamp {
+    fn inner(&self) -> &Self {
+        self
+    }
+
+    fn inner_mut(&mut self) -> &mut Self {
+        self
+    }
+}
+
+impl Serialize for Timestamp {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        mimic::types::Timestamp::from(self.0).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Timestamp {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        mimic::types::Timestamp::deserialize(deserializer).map(|ts| Self(*ts))
+    }
+}
+
+impl_num_checked!(Timestamp);
+
 impl Orderable for Timestamp {}
 
 impl Sanitize for Timestamp {}
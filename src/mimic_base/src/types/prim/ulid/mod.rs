@@ -48,6 +48,18 @@ pub enum Error {
 )]
 pub struct Ulid(WrappedUlid);
 
+#[cfg(feature = "test")]
+impl mimic::orm::traits::Arbitrary for Ulid {
+    type Parameters = ();
+    type Strategy = mimic::orm::proptest::strategy::BoxedStrategy<Self>;
+
+    fn arbitrary_with((): Self::Parameters) -> Self::Strategy {
+        use mimic::orm::proptest::{arbitrary::any, strategy::Strategy};
+
+        any::<WrappedUlid>().prop_map(Self).boxed()
+    }
+}
+
 impl Ulid {
     /// nil
     #[must_use]
@@ -122,6 +134,8 @@ impl<T: Into<WrappedUlid>> From<T> for Ulid {
 }
 
 impl Orderable for Ulid {
+    const ORDERABLE: bool = true;
+
     fn cmp(&self, other: &Self) -> Ordering {
         Ord::cmp(self, other)
     }
@@ -135,6 +149,16 @@ impl PrimaryKey for Ulid {
     fn format(&self) -> String {
         self.0.to_string()
     }
+
+    // from_seed
+    // a zero timestamp keeps seeded ulids in seed order
+    fn from_seed(seed: u64) -> Option<Self> {
+        Some(Self(WrappedUlid::from_parts(0, seed.into())))
+    }
+
+    fn from_ulid(ulid: WrappedUlid) -> Option<Self> {
+        Some(Self(ulid))
+    }
 }
 
 impl Sanitize for Ulid {}
@@ -34,6 +34,7 @@ use mimic::orm::prelude::*;
         field(name = "bytes_test", value(item(is = "types::bytes::Bytes<20>"))),
         field(name = "utf8_test", value(item(is = "types::bytes::Utf8<20>"))),
         field(name = "timestamp", value(item(is = "types::time::Timestamp"))),
+        field(name = "timestamp_test", value(item(is = "types::Timestamp"))),
         field(name = "tuple_test", value(item(is = "test::admin::Tuple"))),
         field(name = "name_many", value(many, item(is = "types::String"))),
         field(name = "name_opt", value(opt, item(is = "types::String"))),
@@ -178,10 +179,17 @@ pub struct EnumA {}
 #[enum_(
     variant(name = "F", default),
     variant(name = "G"),
-    traits(add(Default))
+    traits(add(Default, EnumDisplay))
 )]
 pub struct EnumB {}
 
+///
+/// EnumBNewtype
+///
+
+#[newtype(value(item(is = "EnumB")), traits(add(Display)))]
+pub struct EnumBNewtype {}
+
 ///
 /// EnumC
 ///
@@ -190,7 +198,8 @@ pub struct EnumB {}
     variant(name = "F", value(item(is = "types::String"))),
     variant(name = "G", value(item(is = "MapStringString"))),
     variant(name = "H", value(item(is = "MapStringRecord"))),
-    variant(name = "I", value(item(is = "RecordB")))
+    variant(name = "I", value(item(is = "RecordB"))),
+    traits(add(EnumDisplay))
 )]
 pub struct EnumC {}
 
@@ -239,6 +248,13 @@ pub struct Newtype {}
 )]
 pub struct NewtypeGuide {}
 
+///
+/// RenamedRecord
+///
+
+#[record(fields(field(name = "title", alias = "name", value(item(is = "types::String")))))]
+pub struct RenamedRecord {}
+
 ///
 /// Tuple
 ///
@@ -249,3 +265,153 @@ pub struct NewtypeGuide {}
     traits(add(Default))
 )]
 pub struct Tuple {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mimic::orm::{
+        roundtrip_test,
+        visit::{perform_visit, Event, Visitor},
+    };
+    use std::ops::ControlFlow;
+
+    // stops at the first field it enters
+    #[derive(Default)]
+    struct FirstField {
+        path: Vec<String>,
+        entered: Vec<String>,
+    }
+
+    impl Visitor for FirstField {
+        fn visit(&mut self, _: &dyn Visitable, event: Event) -> ControlFlow<()> {
+            match (event, self.path.last()) {
+                (Event::Enter, Some(key)) if !key.is_empty() => {
+                    self.entered.push(key.clone());
+                    ControlFlow::Break(())
+                }
+                _ => ControlFlow::Continue(()),
+            }
+        }
+
+        fn visit_mut(&mut self, _: &mut dyn Visitable, _: Event) -> ControlFlow<()> {
+            ControlFlow::Continue(())
+        }
+
+        fn push(&mut self, s: &str) {
+            self.path.push(s.to_string());
+        }
+
+        fn pop(&mut self) {
+            self.path.pop();
+        }
+    }
+
+    #[test]
+    fn test_visit_break() {
+        let entity = SimpleEntity {
+            id: types::Ulid::generate(),
+            name: "simple".to_string(),
+        };
+
+        let mut visitor = FirstField::default();
+        let flow = perform_visit(&mut visitor, &entity, "");
+
+        assert!(flow.is_break());
+        assert_eq!(visitor.entered, vec!["id".to_string()]);
+        assert!(visitor.path.is_empty());
+    }
+
+    #[test]
+    fn test_enum_display() {
+        assert_eq!(EnumB::G.to_string(), "G");
+        assert_eq!(EnumC::F("text".into()).to_string(), "F");
+        assert_eq!(EnumBNewtype::from(EnumB::G).to_string(), "G");
+    }
+
+    #[test]
+    fn test_field_alias() {
+        #[derive(Serialize)]
+        struct Old {
+            name: String,
+        }
+
+        let bytes = mimic::orm::serialize(&Old {
+            name: "old".to_string(),
+        })
+        .unwrap();
+        let record: RenamedRecord = mimic::orm::deserialize(&bytes).unwrap();
+
+        assert_eq!(record.title, "old");
+    }
+
+    #[test]
+    fn test_json_roundtrip() {
+        let entity = ComplexEntity {
+            blob_test: vec![1, 2, 3].into(),
+            timestamp_test: 1_706_702_400_u64.into(),
+            ..Default::default()
+        };
+
+        let json = mimic::orm::entity_to_json(&entity).unwrap();
+        assert_eq!(json["blob_test"], "AQID");
+        assert_eq!(json["timestamp_test"], "2024-01-31T12:00:00Z");
+
+        let back: ComplexEntity = mimic::orm::entity_from_json(json).unwrap();
+        assert_eq!(
+            mimic::orm::serialize(&back).unwrap(),
+            mimic::orm::serialize(&entity).unwrap()
+        );
+
+        // only the json codec writes rfc3339, plain json keeps the seconds
+        let json = mimic::orm::to_json(&entity).unwrap();
+        assert_eq!(json["timestamp_test"], 1_706_702_400_u64);
+        let back: ComplexEntity = mimic::orm::entity_from_json(json).unwrap();
+        assert_eq!(back.timestamp_test, entity.timestamp_test);
+
+        // a timestamp past 9999 has no rfc3339 form
+        let entity = ComplexEntity {
+            timestamp_test: u64::MAX.into(),
+            ..Default::default()
+        };
+        assert!(mimic::orm::entity_to_json(&entity).is_err());
+    }
+
+    #[test]
+    fn test_entity_map() {
+        use mimic::ic::structures::{
+            memory::{MemoryId, MemoryManager},
+            DefaultMemoryImpl,
+        };
+
+        let mm = MemoryManager::init(DefaultMemoryImpl::default());
+        let mut map =
+            mimic::orm::collections::EntityMap::<u32, SimpleEntity>::init(mm.get(MemoryId::new(0)));
+        let entity = SimpleEntity {
+            id: types::Ulid::generate(),
+            name: "simple".to_string(),
+        };
+
+        assert!(map.insert(1, &entity).unwrap().is_none());
+        let got = map.get(&1).unwrap().unwrap();
+        assert_eq!((got.id, got.name), (entity.id, entity.name.clone()));
+
+        let renamed = SimpleEntity {
+            name: "renamed".to_string(),
+            ..entity
+        };
+        let old = map.insert(1, &renamed).unwrap().unwrap();
+        assert_eq!(old.name, "simple");
+
+        let names: Vec<String> = map.iter().map(|(_, e)| e.unwrap().name).collect();
+        assert_eq!(names, vec!["renamed".to_string()]);
+        assert!(map.get(&2).unwrap().is_none());
+    }
+
+    roundtrip_test!(
+        test_roundtrip_simple_entity,
+        SimpleEntity {
+            id: types::Ulid::generate(),
+            name: "simple".to_string(),
+        }
+    );
+}
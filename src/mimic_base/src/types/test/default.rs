@@ -15,6 +15,14 @@ use mimic::orm::prelude::*;
                 default = "types::test::default::Record::u8_static_fn"
             )
         ),
+        field(
+            name = "u8_expr",
+            value(
+                item(is = "types::U8"),
+                default = "types::test::default::Record::u8_offset(8)"
+            )
+        ),
+        field(name = "counter", value(item(is = "types::test::default::Counter"))),
     ),
     traits(add(Default))
 )]
@@ -25,4 +33,42 @@ impl Record {
     pub const fn u8_static_fn() -> u8 {
         32
     }
+
+    #[must_use]
+    pub const fn u8_offset(offset: u8) -> u8 {
+        Self::u8_static_fn() + offset
+    }
+}
+
+///
+/// Counter
+///
+
+#[newtype(
+    primitive = "U64",
+    value(
+        item(is = "types::U64"),
+        default = "types::test::default::Counter::start(2, 5)"
+    )
+)]
+pub struct Counter {}
+
+impl Counter {
+    #[must_use]
+    pub const fn start(step: u64, count: u64) -> u64 {
+        step * count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_expr() {
+        let r = Record::default();
+
+        assert_eq!(r.u8_expr, 40);
+        assert_eq!(*r.counter, 10);
+    }
 }
@@ -1,4 +1,4 @@
-use crate::{sanitizer, types};
+use crate::{canister, sanitizer, types};
 use mimic::orm::prelude::*;
 
 ///
@@ -17,6 +17,21 @@ impl ClampRecord {
     }
 }
 
+///
+/// ClampEntity
+///
+
+#[entity(
+    store = "canister::test::store::Data",
+    pks = "id",
+    builder,
+    fields(
+        field(name = "id", value(item(is = "types::Ulid"))),
+        field(name = "value", value(item(is = "types::test::sanitize::U8Clamp"))),
+    )
+)]
+pub struct ClampEntity {}
+
 ///
 /// U8Clamp
 ///
@@ -29,3 +44,41 @@ impl ClampRecord {
     sanitizer(path = "sanitizer::number::Clamp", args(10_u8, 20_u8))
 )]
 pub struct U8Clamp {}
+
+///
+/// TrimLower
+///
+
+#[newtype(
+    primitive = "String",
+    value(item(is = "types::String")),
+    sanitizer(path = "sanitizer::string::Trim"),
+    sanitizer(path = "sanitizer::string::case::Lower")
+)]
+pub struct TrimLower {}
+
+///
+/// U8ClampHighLow
+///
+/// the sanitizers run in declared order, so the second clamp wins
+///
+
+#[newtype(
+    primitive = "U8",
+    value(item(is = "types::U8")),
+    sanitizer(path = "sanitizer::number::Clamp", args(10_u8, 20_u8)),
+    sanitizer(path = "sanitizer::number::Clamp", args(0_u8, 5_u8))
+)]
+pub struct U8ClampHighLow {}
+
+///
+/// U8ClampLowHigh
+///
+
+#[newtype(
+    primitive = "U8",
+    value(item(is = "types::U8")),
+    sanitizer(path = "sanitizer::number::Clamp", args(0_u8, 5_u8)),
+    sanitizer(path = "sanitizer::number::Clamp", args(10_u8, 20_u8))
+)]
+pub struct U8ClampLowHigh {}
@@ -12,12 +12,123 @@ use mimic::orm::prelude::*;
 )]
 pub struct CreateBasic {}
 
+///
+/// CheckedRelation
+///
+
+#[entity(
+    store = "canister::test::store::Data",
+    pks = "id",
+    fields(
+        field(name = "id", value(item(is = "types::Ulid"))),
+        field(
+            name = "create_basic_id",
+            value(item(rel = "types::test::store::CreateBasic")),
+            check_relation
+        ),
+    )
+)]
+pub struct CheckedRelation {}
+
+///
+/// CheckedSortKeyRelation
+/// the target has sort keys, so the relation can't be checked
+///
+
+#[entity(
+    store = "canister::test::store::Data",
+    pks = "id",
+    fields(
+        field(name = "id", value(item(is = "types::Ulid"))),
+        field(
+            name = "sort_key_b_id",
+            value(item(rel = "types::test::store::SortKeyB")),
+            check_relation
+        ),
+    )
+)]
+pub struct CheckedSortKeyRelation {}
+
+///
+/// CreateContentHash
+///
+
+#[entity(
+    store = "canister::test::store::Data",
+    pks = "id",
+    key_strategy = "ContentHash",
+    fields(
+        field(name = "id", value(item(is = "types::Ulid"))),
+        field(name = "name", value(item(is = "types::String"))),
+    )
+)]
+pub struct CreateContentHash {}
+
+///
+/// CreateUlid
+///
+
+#[entity(
+    store = "canister::test::store::Data",
+    pks = "id",
+    key_strategy = "Ulid",
+    fields(field(name = "id", value(item(is = "types::U128"))))
+)]
+pub struct CreateUlid {}
+
+///
+/// CreateSequential
+///
+
+#[entity(
+    store = "canister::test::store::Data",
+    pks = "id",
+    key_strategy = "Sequential",
+    fields(field(name = "id", value(item(is = "types::U64"))))
+)]
+pub struct CreateSequential {}
+
+///
+/// OwnedChild
+///
+
+#[entity(
+    store = "canister::test::store::Data",
+    pks = "id",
+    index(field = "create_basic_id"),
+    fields(
+        field(name = "id", value(item(is = "types::Ulid"))),
+        field(
+            name = "create_basic_id",
+            value(item(rel = "types::test::store::CreateBasic")),
+            owned
+        ),
+    )
+)]
+pub struct OwnedChild {}
+
+///
+/// UniqueName
+///
+
+#[entity(
+    store = "canister::test::store::Data",
+    pks = "id",
+    index(field = "name", unique),
+    fields(
+        field(name = "id", value(item(is = "types::Ulid"))),
+        field(name = "name", value(item(is = "types::String"))),
+    )
+)]
+pub struct UniqueName {}
+
 ///
 /// Filterable
 ///
 
 #[entity(
     store = "canister::test::store::Data",
+    arbitrary,
     pks = "id",
     fields(
         field(name = "id", value(item(is = "types::Ulid"))),
@@ -122,3 +233,66 @@ pub struct MissingFieldSmall {}
     traits(add(Default))
 )]
 pub struct MissingFieldLarge {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_strategy() {
+        let mut a = CreateContentHash {
+            name: "a".into(),
+            ..Default::default()
+        };
+        a.generate_ids();
+        let first = a.id;
+        a.generate_ids();
+        assert_eq!(a.id, first);
+
+        a.name = "b".into();
+        a.generate_ids();
+        assert_ne!(a.id, first);
+
+        // ulid keys are in creation order
+        let mut ulid = CreateUlid::default();
+        ulid.generate_ids();
+        let first = ulid.id;
+        ulid.generate_ids();
+        assert!(first > 0 && ulid.id > first);
+
+        let mut seq = CreateSequential::default();
+        seq.generate_ids();
+        assert_eq!(seq.id, 0);
+        seq.set_sequence_dyn(7);
+        assert_eq!(seq.id, 7);
+    }
+
+    #[test]
+    fn test_composite_sort() {
+        use mimic::orm::{traits::FieldSort, types::SortDirection};
+
+        let row = |name: &str, id: u64| CreateContentHash {
+            id: crate::types::Ulid::from_seed(id).unwrap(),
+            name: name.into(),
+        };
+        let mut rows = [row("b", 1), row("a", 1), row("b", 2), row("a", 2)];
+
+        // ties on name fall through to id
+        let sorter = CreateContentHash::sort(&[
+            ("name".to_string(), SortDirection::Asc),
+            ("id".to_string(), SortDirection::Desc),
+        ]);
+        rows.sort_by(|a, b| sorter(a, b));
+
+        let sorted: Vec<_> = rows.iter().map(|r| (r.name.to_string(), r.id)).collect();
+        assert_eq!(
+            sorted,
+            vec![
+                ("a".to_string(), row("a", 2).id),
+                ("a".to_string(), row("a", 1).id),
+                ("b".to_string(), row("b", 2).id),
+                ("b".to_string(), row("b", 1).id),
+            ]
+        );
+    }
+}
@@ -17,6 +17,33 @@ use mimic::orm::prelude::*;
 )]
 pub struct Validator {}
 
+///
+/// DateRange
+///
+
+#[entity(
+    store = "canister::test::store::Data",
+    pks = "id",
+    check = "date_order",
+    builder,
+    fields(
+        field(name = "id", value(item(is = "types::Ulid"))),
+        field(name = "start", value(item(is = "types::U64"))),
+        field(name = "end", value(item(is = "types::U64"))),
+    )
+)]
+pub struct DateRange {}
+
+// date_order
+fn date_order(range: &DateRange) -> Result<(), FieldErrors> {
+    let mut errs = FieldErrors::new();
+    if range.end < range.start {
+        errs.add("end", "end is before start");
+    }
+
+    errs.result()
+}
+
 ///
 /// GuideType
 ///
@@ -42,3 +69,33 @@ pub struct GuideType {}
     validator(path = "validator::number::MultipleOf", args(10))
 )]
 pub struct MultipleTenType {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_self() {
+        let mut entity = Validator {
+            guide: 5_u8.into(),
+            multiple_ten: MultipleTenType::try_from(20).unwrap(),
+        };
+        assert!(entity.validate_self().is_ok());
+
+        *entity.multiple_ten = 11;
+        assert!(entity.validate_self().is_err());
+    }
+
+    #[test]
+    fn test_try_from() {
+        use mimic::orm::traits::{NumCast, NumFromPrimitive};
+
+        assert_eq!(*MultipleTenType::try_from(30).unwrap(), 30);
+        assert!(MultipleTenType::try_from(43).is_err());
+
+        // the numeric constructors are validated as well
+        assert!(MultipleTenType::from_i64(30).is_some());
+        assert!(MultipleTenType::from_u64(43).is_none());
+        assert!(<MultipleTenType as NumCast>::from(43_u8).is_none());
+    }
+}
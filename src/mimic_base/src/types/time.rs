@@ -33,6 +33,38 @@ impl Timestamp {
     }
 }
 
+///
+/// Rfc3339
+///
+/// A Timestamp that's an rfc3339 string in json, for the fields that are
+/// read off chain, it's still the seconds everywhere else
+///
+
+#[newtype(
+    primitive = "U64",
+    value(item(is = "types::U64")),
+    traits(remove(Serialize, Deserialize))
+)]
+pub struct Rfc3339 {}
+
+impl Serialize for Rfc3339 {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        mimic::types::timestamp::rfc3339::serialize(&self.0.into(), serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Rfc3339 {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        mimic::types::timestamp::rfc3339::deserialize(deserializer).map(|ts| Self(*ts))
+    }
+}
+
 ///
 /// Created
 ///
@@ -48,3 +80,24 @@ impl Timestamp {
     )
 )]
 pub struct Created {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_num_checked() {
+        let max: Duration = u64::MAX.into();
+        let one: Duration = 1_u64.into();
+
+        assert_eq!(max.checked_add(one), None);
+        assert_eq!(one.checked_sub(max), None);
+        assert_eq!(max.checked_mul(one), Some(max));
+        assert_eq!(max.saturating_add(one), max);
+        assert_eq!(*one.saturating_sub(max), 0);
+
+        // delegates through the newtype to the wrapped decimal
+        let d: types::math::DecimalFormat<8, 2> = types::Decimal::from(2).into();
+        assert_eq!(*d.saturating_mul(d), types::Decimal::from(4));
+    }
+}